@@ -6,13 +6,13 @@ use amplify::{Bytes, Wrapper};
 use bp::Txid;
 use ifaces::rgb21::{EmbeddedMedia, TokenData};
 use ifaces::{IssuerWrapper, Rgb21};
-use rgbstd::containers::{ConsignmentExt, FileContent, Kit};
+use rgbstd::containers::{ConsignmentExt, FileContent};
 use rgbstd::invoice::Precision;
 use rgbstd::persistence::Stock;
 use rgbstd::stl::{AssetSpec, Attachment, ContractTerms, MediaType, RicardianContract};
 use rgbstd::{Allocation, GenesisSeal, TokenIndex, XChain};
 use schemata::dumb::NoResolver;
-use schemata::UniqueDigitalAsset;
+use schemata::{BundledSchema, UniqueDigitalAsset};
 use sha2::{Digest, Sha256};
 
 #[rustfmt::skip]
@@ -44,7 +44,7 @@ fn main() {
     let allocation = Allocation::with(index, 1);
 
     // Let's create some stock - an in-memory stash and inventory around it:
-    let kit = Kit::load_file("schemata/UniqueDigitalAsset.rgb").unwrap().validate().unwrap();
+    let kit = BundledSchema::UniqueDigitalAsset.embedded_kit();
     let mut stock = Stock::in_memory();
     stock.import_kit(kit).expect("invalid issuer kit");
 
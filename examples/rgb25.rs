@@ -2,13 +2,13 @@ use amplify::hex::FromHex;
 use bp::dbc::Method;
 use bp::{Outpoint, Txid};
 use ifaces::{Rgb25, Rgb25Wrapper};
-use rgbstd::containers::{ConsignmentExt, FileContent, Kit};
+use rgbstd::containers::{ConsignmentExt, FileContent};
 use rgbstd::interface::{FilterIncludeAll, FungibleAllocation};
 use rgbstd::invoice::Precision;
 use rgbstd::persistence::{MemContract, Stock};
 use rgbstd::XWitnessId;
 use schemata::dumb::NoResolver;
-use schemata::CollectibleFungibleAsset;
+use schemata::{BundledSchema, CollectibleFungibleAsset};
 
 #[rustfmt::skip]
 fn main() {
@@ -16,7 +16,7 @@ fn main() {
         Txid::from_hex("14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5").unwrap();
     let beneficiary = Outpoint::new(beneficiary_txid, 1);
 
-    let kit = Kit::load_file("schemata/CollectibleFungibleAsset.rgb").unwrap().validate().unwrap();
+    let kit = BundledSchema::CollectibleFungibleAsset.embedded_kit();
 
     // Let's create some stock - an in-memory stash and inventory around it:
     let mut stock = Stock::in_memory();
@@ -0,0 +1,62 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of genesis with arbitrary global state and allocations.
+
+#![no_main]
+
+use bp::dbc::Method;
+use bp::Txid;
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use rgbstd::{GenesisSeal, Operation};
+use schemata::BundledSchema;
+use schemata_fuzz::{check_deterministic, fuzz_assigns, fuzz_globals, Fixture};
+
+fn run(u: &mut Unstructured) -> Result<()> {
+    let schema = *u.choose(&BundledSchema::ALL)?;
+    let fixture = Fixture::get(schema);
+    let mut genesis = fixture.genesis.clone();
+
+    if u.arbitrary()? {
+        fuzz_globals(u, &mut genesis)?;
+    }
+    if u.arbitrary()? {
+        let types = genesis.assignments.keys().copied().collect::<Vec<_>>();
+        let ty = *u.choose(&types)?;
+        let template = &genesis.assignments[&ty];
+        let assigns = fuzz_assigns(u, template, |no| {
+            GenesisSeal::with_blinding(Method::OpretFirst, Txid::coinbase(), no, no as u64)
+        })?;
+        genesis
+            .assignments
+            .insert(ty, assigns)
+            .expect("replacing existing assignments");
+    }
+
+    let opid = genesis.id();
+    check_deterministic(&fixture.validator(genesis), opid);
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(&mut Unstructured::new(data));
+});
@@ -0,0 +1,76 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of a state transfer spending genesis allocations into
+//! arbitrary owned state.
+
+#![no_main]
+
+use amplify::confinement::Confined;
+use bp::dbc::Method;
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use rgbstd::{GraphSeal, Input, Inputs, Operation, Opout, Transition};
+use schemata::{BundledSchema, OS_ASSET, TS_TRANSFER};
+use schemata_fuzz::{check_deterministic, fuzz_assigns, tag_outputs, Fixture};
+
+fn run(u: &mut Unstructured) -> Result<()> {
+    let schema = *u.choose(&BundledSchema::ALL)?;
+    let fixture = Fixture::get(schema);
+    let genesis = &fixture.genesis;
+
+    // Asset swap offers allocate no asset in genesis
+    let Some(prev) = genesis.assignments.get(&OS_ASSET) else {
+        return Ok(());
+    };
+    let genesis_id = genesis.id();
+    let inputs = (0..prev.len_u16()).map(|no| Input::with(Opout::new(genesis_id, OS_ASSET, no)));
+    let mut assignments = genesis.assignments.transmutate_seals();
+    let assigns = fuzz_assigns(u, prev, |no| {
+        GraphSeal::with_blinded_vout(Method::OpretFirst, no, no as u64)
+    })?;
+    assignments
+        .insert(OS_ASSET, assigns)
+        .expect("replacing existing assignments");
+    let mut transition = Transition {
+        ffv: Default::default(),
+        contract_id: genesis.contract_id(),
+        nonce: u.arbitrary()?,
+        transition_type: TS_TRANSFER,
+        metadata: Default::default(),
+        globals: Default::default(),
+        inputs: Inputs::from(Confined::from_iter_checked(inputs)),
+        assignments,
+        valencies: Default::default(),
+        validator: Default::default(),
+        witness: Default::default(),
+    };
+    tag_outputs(schema, &mut transition);
+
+    let mut validator = fixture.validator(genesis.clone());
+    let opid = validator.add_transition(transition);
+    check_deterministic(&validator, opid);
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(&mut Unstructured::new(data));
+});
@@ -0,0 +1,96 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shared code of the fuzzing targets: construction of operations with state
+//! taken from the fuzzer input. Sample contracts of the bundled schemata are
+//! provided by the library test kit.
+
+use amplify::confinement::{Confined, U16};
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use rgbstd::{
+    Assign, BlindingFactor, ExposedSeal, Genesis, GlobalValues, OpId, RevealedData, RevealedValue,
+    TypedAssigns, XChain,
+};
+pub use schemata::testkit::{tag_outputs, Fixture};
+use schemata::OpValidator;
+
+/// Data of strict-encoded state with arbitrary content.
+pub fn state_data(u: &mut Unstructured) -> Result<Confined<Vec<u8>, 0, U16>> {
+    let len = u.arbitrary_len::<u8>()?.min(U16);
+    Ok(Confined::try_from(u.bytes(len)?.to_vec()).expect("length is checked"))
+}
+
+/// Replaces one of the global state values of the genesis with an arbitrary
+/// data.
+pub fn fuzz_globals(u: &mut Unstructured, genesis: &mut Genesis) -> Result<()> {
+    let types = genesis.globals.keys().copied().collect::<Vec<_>>();
+    let ty = *u.choose(&types)?;
+    let data = state_data(u)?;
+    genesis
+        .globals
+        .insert(ty, GlobalValues::with(data.into()))
+        .expect("replacing existing global state");
+    Ok(())
+}
+
+/// Constructs assignments of the same state type as in `template`, with
+/// arbitrary state.
+pub fn fuzz_assigns<Seal: ExposedSeal, Template: ExposedSeal>(
+    u: &mut Unstructured,
+    template: &TypedAssigns<Template>,
+    seal: impl Fn(u32) -> Seal,
+) -> Result<TypedAssigns<Seal>> {
+    let count = u.int_in_range(1..=4u32)?;
+    Ok(match template {
+        TypedAssigns::Fungible(assigns) => {
+            let tag = assigns
+                .iter()
+                .find_map(|assign| assign.as_revealed_state().map(|state| state.tag))
+                .expect("revealed fungible state");
+            let assigns = (0..count)
+                .map(|no| {
+                    let value = u.arbitrary::<u64>()?;
+                    let blinding = BlindingFactor::try_from(u.arbitrary::<[u8; 32]>()?)
+                        .unwrap_or(BlindingFactor::EMPTY);
+                    let state = RevealedValue::with_blinding(value, blinding, tag);
+                    Ok(Assign::revealed(XChain::Bitcoin(seal(no)), state))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            TypedAssigns::Fungible(Confined::try_from(assigns).expect("small number of assigns"))
+        }
+        TypedAssigns::Structured(_) => {
+            let assigns = (0..count)
+                .map(|no| {
+                    let state = RevealedData::with_salt(state_data(u)?, u.arbitrary::<u128>()?);
+                    Ok(Assign::revealed(XChain::Bitcoin(seal(no)), state))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            TypedAssigns::Structured(Confined::try_from(assigns).expect("small number of assigns"))
+        }
+        _ => unreachable!("bundled schemata use only fungible and structured state"),
+    })
+}
+
+/// Validates operation twice, asserting that the results are the same.
+pub fn check_deterministic(validator: &OpValidator, opid: OpId) {
+    let status = validator.validate(opid);
+    assert_eq!(status, validator.validate(opid), "non-deterministic validation of {opid}");
+}
@@ -0,0 +1,186 @@
+/* RGB smart contract schemata C API. Generated with cbindgen, do not edit. */
+
+#ifndef RGB_SCHEMATA_H
+#define RGB_SCHEMATA_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/*
+ Status code returned by all functions of the C API.
+ */
+typedef enum SchemataStatus {
+  /*
+   Operation succeeded.
+   */
+  SCHEMATA_STATUS_OK = 0,
+  /*
+   One of the pointer arguments is null.
+   */
+  SCHEMATA_STATUS_NULL_ARGUMENT = 1,
+  /*
+   String argument is not a valid UTF-8.
+   */
+  SCHEMATA_STATUS_INVALID_UTF8 = 2,
+  /*
+   Unknown bundled schema name.
+   */
+  SCHEMATA_STATUS_UNKNOWN_SCHEMA = 3,
+  /*
+   Issuance parameters are not a valid JSON issuance manifest.
+   */
+  SCHEMATA_STATUS_INVALID_PARAMS = 4,
+  /*
+   Issuance parameters specify schema different from the one being
+   issued.
+   */
+  SCHEMATA_STATUS_SCHEMA_MISMATCH = 5,
+  /*
+   Contract can't be issued with the provided parameters.
+   */
+  SCHEMATA_STATUS_ISSUE_FAILED = 6,
+  /*
+   Serialization of a kit or contract has failed.
+   */
+  SCHEMATA_STATUS_SERIALIZATION = 7,
+} SchemataStatus;
+
+/*
+ Byte buffer allocated by the library.
+ */
+typedef struct SchemataBytes {
+  uint8_t *data;
+  size_t len;
+} SchemataBytes;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Returns description of the last failure of a function called from the
+ current thread, or an empty string if it has succeeded.
+
+ The returned string is owned by the library and remains valid until the
+ next call to the library from the same thread.
+ */
+const char *schemata_error_message(void);
+
+/*
+ Writes binary kit of the bundled schema with the provided name into `out`.
+
+ # Safety
+
+ `schema` must be a null-terminated string and `out` must be valid for
+ writes.
+ */
+SchemataStatus schemata_kit(const char *schema, struct SchemataBytes *out);
+
+/*
+ Writes ASCII-armored kit of the bundled schema with the provided name into
+ `out`.
+
+ # Safety
+
+ `schema` must be a null-terminated string and `out` must be valid for
+ writes.
+ */
+SchemataStatus schemata_kit_armored(const char *schema, char **out);
+
+/*
+ Issues NIA contract from JSON issuance parameters, writing its id and
+ ASCII-armored contract into `contract_id` and `contract`.
+
+ The parameters follow the issuance manifest format, where `schema` field
+ may be omitted.
+
+ # Safety
+
+ `params` must be a null-terminated string; `contract_id` and `contract`
+ must be valid for writes.
+ */
+SchemataStatus schemata_issue_nia(const char *params, char **contract_id, char **contract);
+
+/*
+ Issues CFA contract from JSON issuance parameters, writing its id and
+ ASCII-armored contract into `contract_id` and `contract`.
+
+ The parameters follow the issuance manifest format, where `schema` field
+ may be omitted.
+
+ # Safety
+
+ `params` must be a null-terminated string; `contract_id` and `contract`
+ must be valid for writes.
+ */
+SchemataStatus schemata_issue_cfa(const char *params, char **contract_id, char **contract);
+
+/*
+ Issues LFA contract from JSON issuance parameters, writing its id and
+ ASCII-armored contract into `contract_id` and `contract`.
+
+ The parameters follow the issuance manifest format, where `schema` field
+ may be omitted.
+
+ # Safety
+
+ `params` must be a null-terminated string; `contract_id` and `contract`
+ must be valid for writes.
+ */
+SchemataStatus schemata_issue_lfa(const char *params, char **contract_id, char **contract);
+
+/*
+ Issues ASO contract from JSON issuance parameters, writing its id and
+ ASCII-armored contract into `contract_id` and `contract`.
+
+ The parameters follow the issuance manifest format, where `schema` field
+ may be omitted.
+
+ # Safety
+
+ `params` must be a null-terminated string; `contract_id` and `contract`
+ must be valid for writes.
+ */
+SchemataStatus schemata_issue_aso(const char *params, char **contract_id, char **contract);
+
+/*
+ Issues UDA contract from JSON issuance parameters, writing its id and
+ ASCII-armored contract into `contract_id` and `contract`.
+
+ The parameters follow the issuance manifest format, where `schema` field
+ may be omitted. Paths to media files are relative to the current
+ directory.
+
+ # Safety
+
+ `params` must be a null-terminated string; `contract_id` and `contract`
+ must be valid for writes.
+ */
+SchemataStatus schemata_issue_uda(const char *params, char **contract_id, char **contract);
+
+/*
+ Releases string returned by the library. Does nothing for a null pointer.
+
+ # Safety
+
+ `s` must be either null or a string returned by the library which was not
+ released yet.
+ */
+void schemata_string_free(char *s);
+
+/*
+ Releases byte buffer returned by the library. Does nothing for a buffer
+ with null data.
+
+ # Safety
+
+ `bytes` must either have null data or be a buffer returned by the library
+ which was not released yet.
+ */
+void schemata_bytes_free(struct SchemataBytes bytes);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RGB_SCHEMATA_H */
@@ -0,0 +1,199 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Python module `rgb_schemata` exposing the bundled schemata registry,
+//! contract issuance and inspection of contracts and kits.
+//!
+//! Issuance parameters follow the JSON issuance manifest format; results of
+//! issuance and inspection are returned as Python dicts with the same fields
+//! as the serde JSON of the library.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use rgbstd::containers::{ConsignmentExt, FileContent, UniversalFile};
+use schemata::{historic_schema, BundledSchema, ContractReport, IssueManifest, SchemaRelease};
+use serde_json::{json, Value};
+
+create_exception!(rgb_schemata, SchemataError, PyException);
+
+fn error(err: impl ToString) -> PyErr { SchemataError::new_err(err.to_string()) }
+
+fn bundled(schema: &str) -> PyResult<BundledSchema> { schema.parse().map_err(error) }
+
+fn to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    py.import("json")?
+        .call_method1("loads", (value.to_string(),))
+}
+
+fn from_py(py: Python, value: &Bound<PyAny>) -> PyResult<String> {
+    py.import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()
+}
+
+/// Returns dict of the bundled schemata by their names, with their
+/// abbreviations, ids and interfaces.
+#[pyfunction]
+fn registry(py: Python) -> PyResult<Bound<PyDict>> {
+    let registry = PyDict::new(py);
+    for schema in BundledSchema::ALL {
+        let abbr = match schema {
+            BundledSchema::NonInflatableAsset => "NIA",
+            BundledSchema::UniqueDigitalAsset => "UDA",
+            BundledSchema::CollectibleFungibleAsset => "CFA",
+            BundledSchema::LightningFungibleAsset => "LFA",
+            BundledSchema::AssetSwapOffer => "ASO",
+        };
+        let kit = schema.kit();
+        let iface = kit.ifaces.first().expect("kit without interface");
+        let info = json!({
+            "abbr": abbr,
+            "schemaId": schema.schema().schema_id().to_string(),
+            "interface": iface.name.to_string(),
+            "ifaceId": iface.iface_id().to_string(),
+        });
+        registry.set_item(schema.to_string(), to_py(py, &info)?)?;
+    }
+    Ok(registry)
+}
+
+/// Returns binary kit of the bundled schema with the provided name.
+#[pyfunction]
+fn kit<'py>(py: Python<'py>, schema: &str) -> PyResult<Bound<'py, PyBytes>> {
+    let mut data = vec![];
+    bundled(schema)?.kit().save(&mut data).map_err(error)?;
+    Ok(PyBytes::new(py, &data))
+}
+
+/// Returns ASCII-armored kit of the bundled schema with the provided name.
+#[pyfunction]
+fn kit_armored(schema: &str) -> PyResult<String> {
+    Ok(UniversalFile::Kit(bundled(schema)?.kit()).to_string())
+}
+
+fn issue<'py>(
+    py: Python<'py>,
+    schema: BundledSchema,
+    params: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let contract = IssueManifest::from_json_for(schema, &from_py(py, params)?)
+        .and_then(|manifest| manifest.issue())
+        .map_err(error)?;
+    let issued = json!({
+        "contractId": contract.contract_id().to_string(),
+        "contract": contract.to_string(),
+    });
+    to_py(py, &issued)
+}
+
+/// Issues NIA contract from the dict of issuance manifest fields, returning
+/// dict with `contractId` and ASCII-armored `contract`.
+#[pyfunction]
+fn issue_nia<'py>(py: Python<'py>, params: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    issue(py, BundledSchema::NonInflatableAsset, params)
+}
+
+/// Issues CFA contract from the dict of issuance manifest fields, returning
+/// dict with `contractId` and ASCII-armored `contract`.
+#[pyfunction]
+fn issue_cfa<'py>(py: Python<'py>, params: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    issue(py, BundledSchema::CollectibleFungibleAsset, params)
+}
+
+/// Issues LFA contract from the dict of issuance manifest fields, returning
+/// dict with `contractId` and ASCII-armored `contract`.
+#[pyfunction]
+fn issue_lfa<'py>(py: Python<'py>, params: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    issue(py, BundledSchema::LightningFungibleAsset, params)
+}
+
+/// Issues ASO contract from the dict of issuance manifest fields, returning
+/// dict with `contractId` and ASCII-armored `contract`.
+#[pyfunction]
+fn issue_aso<'py>(py: Python<'py>, params: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    issue(py, BundledSchema::AssetSwapOffer, params)
+}
+
+/// Issues UDA contract from the dict of issuance manifest fields, returning
+/// dict with `contractId` and ASCII-armored `contract`. Paths to media files
+/// are relative to the current directory.
+#[pyfunction]
+fn issue_uda<'py>(py: Python<'py>, params: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    issue(py, BundledSchema::UniqueDigitalAsset, params)
+}
+
+/// Validates binary or ASCII-armored contract and returns dict with its
+/// interface state.
+#[pyfunction]
+fn inspect_contract<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    let report = ContractReport::load(data).map_err(error)?;
+    to_py(py, &serde_json::to_value(report).map_err(error)?)
+}
+
+/// Returns dict describing schema and interface implementations of a binary
+/// or ASCII-armored kit.
+#[pyfunction]
+fn inspect_kit<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+    let release = SchemaRelease::load(data).map_err(error)?;
+    let schema_id = release.schema.schema_id();
+    let names = |fields: Vec<String>| Value::from(fields);
+    let iimpls = release
+        .iimpls
+        .iter()
+        .map(|iimpl| {
+            json!({
+                "ifaceId": iimpl.iface_id.to_string(),
+                "globalState": names(iimpl.global_state.iter().map(|f| f.name.to_string()).collect()),
+                "assignments": names(iimpl.assignments.iter().map(|f| f.name.to_string()).collect()),
+                "transitions": names(iimpl.transitions.iter().map(|f| f.name.to_string()).collect()),
+            })
+        })
+        .collect::<Vec<_>>();
+    let info = json!({
+        "schemaId": schema_id.to_string(),
+        "name": release.schema.name.to_string(),
+        "developer": release.schema.developer.to_string(),
+        "timestamp": release.schema.timestamp,
+        "bundled": BundledSchema::from_schema_id(schema_id).map(|schema| schema.to_string()),
+        "release": historic_schema(schema_id).map(|version| version.release),
+        "interfaces": iimpls,
+        "scripts": release.scripts.keys().map(ToString::to_string).collect::<Vec<_>>(),
+    });
+    to_py(py, &info)
+}
+
+#[pymodule]
+fn rgb_schemata(m: &Bound<PyModule>) -> PyResult<()> {
+    m.add("SchemataError", m.py().get_type::<SchemataError>())?;
+    m.add_function(wrap_pyfunction!(registry, m)?)?;
+    m.add_function(wrap_pyfunction!(kit, m)?)?;
+    m.add_function(wrap_pyfunction!(kit_armored, m)?)?;
+    m.add_function(wrap_pyfunction!(issue_nia, m)?)?;
+    m.add_function(wrap_pyfunction!(issue_cfa, m)?)?;
+    m.add_function(wrap_pyfunction!(issue_lfa, m)?)?;
+    m.add_function(wrap_pyfunction!(issue_aso, m)?)?;
+    m.add_function(wrap_pyfunction!(issue_uda, m)?)?;
+    m.add_function(wrap_pyfunction!(inspect_contract, m)?)?;
+    m.add_function(wrap_pyfunction!(inspect_kit, m)?)?;
+    Ok(())
+}
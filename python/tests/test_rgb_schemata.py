@@ -0,0 +1,132 @@
+# Tests of the Python bindings, run with `pytest` after `maturin develop`
+# from the `python` directory.
+
+import pathlib
+
+import pytest
+
+import rgb_schemata
+
+ROOT = pathlib.Path(__file__).parents[2]
+OUTPOINT = "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1"
+
+
+def test_registry():
+    registry = rgb_schemata.registry()
+    assert list(registry) == [
+        "NonInflatableAsset",
+        "UniqueDigitalAsset",
+        "CollectibleFungibleAsset",
+        "LightningFungibleAsset",
+        "AssetSwapOffer",
+    ]
+    assert registry["NonInflatableAsset"]["abbr"] == "NIA"
+    assert registry["UniqueDigitalAsset"]["schemaId"].startswith("rgb:sch:")
+
+
+def test_kits():
+    registry = rgb_schemata.registry()
+    for name in registry:
+        kit = rgb_schemata.kit(name)
+        assert kit.startswith(b"RGB\0KIT")
+        info = rgb_schemata.inspect_kit(kit)
+        assert info["schemaId"] == registry[name]["schemaId"]
+        assert info["bundled"] == name
+        assert info["interfaces"][0]["ifaceId"] == registry[name]["ifaceId"]
+        assert rgb_schemata.kit_armored(name).startswith("-----BEGIN RGB KIT-----")
+
+    info = rgb_schemata.inspect_kit((ROOT / "schemata/NonInflatableAssets.rgb").read_bytes())
+    assert info["name"] == "NonInflatableAsset"
+    assert "assetOwner" in info["interfaces"][0]["assignments"]
+
+    with pytest.raises(rgb_schemata.SchemataError, match="RGB20"):
+        rgb_schemata.kit("RGB20")
+
+
+def test_issue_nia():
+    issued = rgb_schemata.issue_nia({
+        "ticker": "TEST",
+        "name": "Test asset",
+        "precision": "centiMicro",
+        "allocations": [{"outpoint": OUTPOINT, "amount": 100000}],
+    })
+    assert issued["contractId"].startswith("rgb:")
+    report = rgb_schemata.inspect_contract(issued["contract"].encode())
+    assert report["contractId"] == issued["contractId"]
+    assert report["schema"] == "NonInflatableAsset"
+    assert report["spec"]["ticker"] == "TEST"
+    assert report["issuedSupply"] == 100000
+
+
+def test_issue_lfa():
+    issued = rgb_schemata.issue_lfa({
+        "ticker": "TEST",
+        "name": "Test asset",
+        "allocations": [{"outpoint": OUTPOINT, "amount": 1000}],
+    })
+    report = rgb_schemata.inspect_contract(issued["contract"].encode())
+    assert report["schema"] == "LightningFungibleAsset"
+    assert report["interface"] == "RGB20"
+
+
+def test_issue_aso():
+    counter = rgb_schemata.issue_nia({
+        "ticker": "USDT",
+        "name": "Counter asset",
+        "allocations": [{"outpoint": OUTPOINT, "amount": 500}],
+    })
+    issued = rgb_schemata.issue_aso({
+        "ticker": "TEST",
+        "name": "Test asset",
+        "offer": {"counterAsset": counter["contractId"], "counterAmount": 500},
+        "allocations": [{"outpoint": OUTPOINT, "amount": 1000}],
+    })
+    report = rgb_schemata.inspect_contract(issued["contract"].encode())
+    assert report["schema"] == "AssetSwapOffer"
+    assert report["interface"] == "RGB20"
+
+    with pytest.raises(rgb_schemata.SchemataError, match="offer"):
+        rgb_schemata.issue_aso({
+            "ticker": "TEST",
+            "name": "Test asset",
+            "allocations": [{"outpoint": OUTPOINT, "amount": 1000}],
+        })
+
+
+def test_issue_cfa_uda():
+    issued = rgb_schemata.issue_cfa({
+        "name": "Test asset",
+        "details": "Some details",
+        "allocations": [{"outpoint": OUTPOINT, "amount": 100}],
+    })
+    report = rgb_schemata.inspect_contract(issued["contract"].encode())
+    assert report["interface"] == "RGB25"
+    assert report["details"] == "Some details"
+
+    issued = rgb_schemata.issue_uda({
+        "schema": "UDA",
+        "ticker": "TEST",
+        "name": "Test uda",
+        "allocations": [{"outpoint": OUTPOINT}],
+        "token": {"index": 2, "attachments": [{"type": "text/plain", "file": str(ROOT / "DCO")}]},
+    })
+    report = rgb_schemata.inspect_contract(issued["contract"].encode())
+    assert report["token"]["index"] == 2
+    assert len(report["allocations"]) == 1
+
+
+def test_inspect_file():
+    report = rgb_schemata.inspect_contract((ROOT / "test/rgb21-example.rgb").read_bytes())
+    assert report["interface"] == "RGB21"
+    assert report["spec"]["name"] == "Test uda"
+
+
+def test_errors():
+    with pytest.raises(rgb_schemata.SchemataError, match="ticker"):
+        rgb_schemata.issue_nia({"name": "Test asset", "allocations": []})
+    with pytest.raises(rgb_schemata.SchemataError, match="can't be used to issue"):
+        rgb_schemata.issue_cfa({"schema": "NIA", "name": "Test asset", "allocations": []})
+    with pytest.raises(rgb_schemata.SchemataError):
+        rgb_schemata.inspect_contract(b"not a contract")
+    with pytest.raises(rgb_schemata.SchemataError):
+        rgb_schemata.inspect_kit((ROOT / "test/rgb20-example.rgb").read_bytes())
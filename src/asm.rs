@@ -0,0 +1,189 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Assembler of the validation scripts, which extends `rgbasm!` of RGB core
+//! library with the contract operations it has no mnemonics for: `cnp`, `cns`,
+//! `ldf` and `ldc`, and keeps the text of the assembled instructions, such that
+//! the library code can be mapped back to its source.
+
+use aluvm::isa::Instr;
+#[cfg(feature = "log")]
+use aluvm::library::Lib;
+use rgbstd::persistence::MemContract;
+use rgbstd::vm::RgbIsa;
+
+/// Validation script code assembled by [`schemata_asm!`].
+pub(crate) struct Asm {
+    pub code: Vec<Instr<RgbIsa<MemContract>>>,
+    #[cfg(feature = "log")]
+    pub source: AsmSource,
+}
+
+/// Assembler source of a validation script.
+#[cfg(feature = "log")]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct AsmSource {
+    /// Path of the source file, as provided by `file!()`.
+    pub file: &'static str,
+    /// Line of the source file at which the assembler code starts.
+    pub line: u32,
+    /// Text of each instruction, as it is tokenized by the compiler.
+    pub instrs: &'static [&'static str],
+}
+
+#[cfg(feature = "log")]
+impl AsmSource {
+    /// Numbers of the source lines of each instruction.
+    ///
+    /// The crate source is not a part of the compiled library, thus the lines
+    /// are found by reading the source file, if it is present at the location
+    /// from which the crate was compiled.
+    pub fn lines(&self) -> Option<Vec<usize>> {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(self.file);
+        let text = std::fs::read_to_string(path).ok()?;
+        let squeeze = |s: &str| s.split_whitespace().collect::<String>();
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .skip(self.line.saturating_sub(1) as usize);
+        self.instrs
+            .iter()
+            .map(|instr| {
+                let instr = squeeze(instr) + ";";
+                lines
+                    .find(|(_, line)| squeeze(line).starts_with(&instr))
+                    .map(|(no, _)| no + 1)
+            })
+            .collect()
+    }
+
+    /// Pairs library instructions, given by their offsets, with the source
+    /// text of each of them and its line, if known.
+    ///
+    /// # Panics
+    ///
+    /// If the library is not assembled from this source.
+    pub fn instrs(&self, lib: &Lib) -> Vec<(u16, &'static str, Option<usize>)> {
+        let code = crate::lint::decode(lib);
+        assert_eq!(code.len(), self.instrs.len(), "{} is not a source of {}", self.file, lib.id());
+        let lines = self.lines();
+        code.into_iter()
+            .zip(self.instrs)
+            .enumerate()
+            .map(|(no, ((pos, _), text))| {
+                (pos, *text, lines.as_ref().and_then(|lines| lines.get(no).copied()))
+            })
+            .collect()
+    }
+}
+
+/// Assembles AluVM code with RGB ISA extensions, accepting the same syntax as
+/// `rgbasm!`, into [`Asm`].
+macro_rules! schemata_asm {
+    ($( $tt:tt )+) => {{
+        let code = { #[allow(unused_imports, clippy::diverging_sub_expression)] {
+            use amplify::num::{u4, u5};
+            use rgbstd::vm::{aluasm_isa, ContractOp, RgbIsa};
+            use $crate::asm::isa_instr;
+            aluasm_isa! { RgbIsa<_> => $( $tt )+ }
+        } };
+        $crate::asm::Asm {
+            code,
+            #[cfg(feature = "log")]
+            source: $crate::asm::AsmSource {
+                file: file!(),
+                line: line!(),
+                instrs: $crate::asm::asm_text!([] $( $tt )+),
+            },
+        }
+    }};
+}
+
+/// Text of each instruction of [`schemata_asm!`] code.
+#[cfg(feature = "log")]
+macro_rules! asm_text {
+    ([$( $done:expr ),*]) => {
+        &[$( $done ),*]
+    };
+    ([$( $done:expr ),*] $op:ident $( . $flag:ident )? ; $( $tt:tt )*) => {
+        $crate::asm::asm_text!([$( $done, )* stringify!($op $( . $flag )?)] $( $tt )*)
+    };
+    ([$( $done:expr ),*] $op:ident $( . $flag:ident )? $( $arg:expr ),+ ; $( $tt:tt )*) => {
+        $crate::asm::asm_text!(
+            [$( $done, )* stringify!($op $( . $flag )? $( $arg ),+)] $( $tt )*
+        )
+    };
+}
+
+/// RGB ISA extension instructions for [`schemata_asm!`].
+macro_rules! isa_instr {
+    (pcvs $no:ident) => {{
+        RgbIsa::Contract(ContractOp::Pcvs($no))
+    }};
+    (pcas $no:ident) => {{
+        RgbIsa::Contract(ContractOp::Pcas($no))
+    }};
+    (pcps $no:ident) => {{
+        RgbIsa::Contract(ContractOp::Pcps($no))
+    }};
+    (cnp $t:ident,a16[$a_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::CnP($t, Reg32::from(u5::with($a_idx))))
+    }};
+    (cns $t:ident,a16[$a_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::CnS($t, Reg32::from(u5::with($a_idx))))
+    }};
+    (cng $t:ident,a8[$a_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::CnG($t, Reg32::from(u5::with($a_idx))))
+    }};
+    (cnc $t:ident,a16[$a_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::CnC($t, Reg32::from(u5::with($a_idx))))
+    }};
+    (ldm $t:ident,s16[$s_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::LdM($t, RegS::from($s_idx)))
+    }};
+    (ldf $t:ident,a16[$a_idx:literal],a64[$dst_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::LdF(
+            $t,
+            Reg16::from(u4::with($a_idx)),
+            Reg16::from(u4::with($dst_idx)),
+        ))
+    }};
+    (ldg $t:ident,a8[$a_idx:literal],s16[$s_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::LdG($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx)))
+    }};
+    (ldc $t:ident,a32[$a_idx:literal],s16[$s_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::LdC($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx)))
+    }};
+    (ldp $t:ident,a16[$a_idx:literal],s16[$s_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::LdP($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx)))
+    }};
+    (lds $t:ident,a16[$a_idx:literal],s16[$s_idx:literal]) => {{
+        RgbIsa::Contract(ContractOp::LdS($t, Reg16::from(u4::with($a_idx)), RegS::from($s_idx)))
+    }};
+    ($op:ident $($tt:tt)+) => {{
+        compile_error!(concat!("unknown RGB assembly opcode `", stringify!($op), "`"))
+    }};
+}
+
+#[cfg(feature = "log")]
+pub(crate) use asm_text;
+pub(crate) use isa_instr;
+pub(crate) use schemata_asm;
@@ -0,0 +1,826 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Asset Swap Offers (ASO) schema implementing RGB20 fungible assets interface
+//! for the assets locked in an offer to swap them for another RGB asset.
+//!
+//! The genesis defines the terms of the offer: the counter-asset contract and
+//! the amount of it asked for the whole issued supply, which is allocated in
+//! one or more lots. The lots are owned state of their own type, which only
+//! `take` transition can spend: it hands some of the lots over to a taker as a
+//! whole, paying them out into `assetOwner` allocations, and records the fill
+//! in the global state. The taken asset is then transferred as any other RGB20
+//! asset, with the transfers validated by the NIA library.
+//!
+//! The lot amounts are kept in structured state rather than in pedersen
+//! commitments, which can't be checked against the amount of a part of the
+//! lots. The validation library of the schema sums the lots allocated in
+//! genesis against the issued supply and the lots spent by a take against the
+//! taken amount of the fill, and checks the take outputs against the taken
+//! amount with `pcas`.
+//!
+//! The validation scripts don't check the fill against the offer terms: RGB
+//! core library fails to provide the scripts with the genesis global state
+//! (`ldc` operation), and the counter-asset is paid in a transfer of its own
+//! contract, committed in the same witness transaction, which the scripts
+//! can't access. Thus, before accepting both transitions, the maker checks the
+//! fill against the terms with [`AssetSwapOffer::check_take`] and the payment
+//! for the fill, received by the maker wallet.
+
+use aluvm::isa::opcodes::INSTR_PUTA;
+use aluvm::library::{Lib, LibSite};
+use amplify::confinement::{Confined, U16};
+use amplify::Wrapper;
+use bp::dbc::Method;
+use ifaces::rgb20::IssuerError;
+use ifaces::{IssuerWrapper, Rgb20};
+use rgbstd::containers::ValidContract;
+use rgbstd::interface::{
+    ContractBuilder, IfaceClass, IfaceImpl, NamedField, NamedVariant, TransitionBuilder,
+    TxOutpoint, VerNo,
+};
+use rgbstd::schema::{
+    FungibleType, GenesisSchema, GlobalStateSchema, GlobalStateType, Occurrences, OwnedStateSchema,
+    Schema, TransitionSchema,
+};
+use rgbstd::stl::{AssetSpec, ContractTerms, RicardianContract, StandardTypes};
+use rgbstd::validation::Scripts;
+use rgbstd::vm::opcodes::{INSTR_LDP, INSTR_LDS};
+use rgbstd::{
+    Amount, AssetTag, ContractId, Genesis, GenesisSeal, GlobalState, Operation, Precision,
+    RevealedData, Transition,
+};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+use strict_types::TypeSystem;
+
+use crate::asm::{schemata_asm, Asm};
+use crate::nia::{issuer_identity, nia_lib, FungibleIssueError, FN_NIA_TRANSFER_OFFSET};
+use crate::stl::schemata_stl;
+use crate::{
+    OfferFill, OfferTerms, SchemaParams, ERRNO_FILL_MISMATCH, ERRNO_ISSUED_MISMATCH,
+    ERRNO_NON_EQUAL_IN_OUT, GS_FILL, GS_ISSUED_SUPPLY, GS_NOMINAL, GS_OFFER, GS_TERMS, OS_ASSET,
+    OS_LOT, TS_TAKE, TS_TRANSFER,
+};
+
+/// Errors of checking `take` transition against the offer terms.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum TakeError {
+    /// transition is not a take of the offer.
+    NotTake,
+
+    /// global state {0} of the offer genesis or take transition is absent or
+    /// invalid.
+    InvalidState(GlobalStateType),
+
+    /// amount paid for the fill {0:?} doesn't cover the taken amount under the
+    /// offer terms.
+    Uncovered(OfferFill),
+}
+
+pub(crate) fn aso_asm() -> Asm {
+    schemata_asm! {
+        // SUBROUTINE Take validation
+        // Set errno
+        put     a8[0],ERRNO_FILL_MISMATCH;
+        // Read the fill into s16[0]
+        put     a8[1],0;
+        ldg     GS_FILL,a8[1],s16[0];
+        // Extract 64 bits of the taken amount from the beginning of s16[0] into a64[0]
+        put     a16[0],0;
+        extr    s16[0],a64[0],a16[0];
+        // Count the spent lots into a16[1] and sum their amounts into a64[1]
+        cnp     OS_LOT,a16[1];
+        put     a16[2],0;
+        put     a64[1],0;
+        // Add amount of the spent lot with a16[2] index; concealed lot or overflow fails the sum
+        ldp     OS_LOT,a16[2],s16[1];
+        extr    s16[1],a64[2],a16[0];
+        add.uc  a64[2],a64[1];
+        inc     a16[2];
+        lt.u    a16[2],a16[1];
+        jif     FN_ASO_TAKE_LOTS_OFFSET;
+        // Check that the taken amount matches the spent lots
+        eq.n    a64[0],a64[1];
+        test;
+        // Verify sum of pedersen commitments for the outputs against the taken amount
+        pcas    OS_ASSET;
+        test;
+        ret;
+
+        // SUBROUTINE Genesis validation
+        // Set errno
+        put     a8[0],ERRNO_ISSUED_MISMATCH;
+        // Read the issued supply into s16[0]
+        put     a8[1],0;
+        ldg     GS_ISSUED_SUPPLY,a8[1],s16[0];
+        // Extract 64 bits of the issued supply from the beginning of s16[0] into a64[0]
+        put     a16[0],0;
+        extr    s16[0],a64[0],a16[0];
+        // Count the lots into a16[1] and sum their amounts into a64[1]
+        cns     OS_LOT,a16[1];
+        put     a16[2],0;
+        put     a64[1],0;
+        // Add amount of the lot with a16[2] index; overflow fails the sum
+        lds     OS_LOT,a16[2],s16[1];
+        extr    s16[1],a64[2],a16[0];
+        add.uc  a64[2],a64[1];
+        inc     a16[2];
+        lt.u    a16[2],a16[1];
+        jif     FN_ASO_GENESIS_LOTS_OFFSET;
+        // Check that the issued supply matches the lots
+        eq.n    a64[0],a64[1];
+        test;
+        ret;
+    }
+}
+
+pub(crate) fn aso_lib() -> Lib {
+    Lib::assemble(&aso_asm().code).expect("wrong asset swap offer script")
+}
+pub(crate) const FN_ASO_TAKE_OFFSET: u16 = 0;
+pub(crate) const FN_ASO_TAKE_LOTS_OFFSET: u16 = 4 + 4 + 4 + 4 + 3 + 4 + 4 + 4;
+pub(crate) const FN_ASO_GENESIS_OFFSET: u16 =
+    FN_ASO_TAKE_LOTS_OFFSET + 4 + 3 + 3 + 3 + 3 + 3 + 3 + 1 + 3 + 1 + 1;
+pub(crate) const FN_ASO_GENESIS_LOTS_OFFSET: u16 = FN_ASO_GENESIS_OFFSET + FN_ASO_TAKE_LOTS_OFFSET;
+
+fn aso_schema() -> Schema { aso_schema_with(&SchemaParams::default()) }
+
+pub(crate) fn aso_schema_with(params: &SchemaParams) -> Schema {
+    let types = StandardTypes::with(schemata_stl());
+
+    let nia_id = nia_lib().id();
+
+    let alu_lib = aso_lib();
+    let aso_id = alu_lib.id();
+    let code = alu_lib.code.as_ref();
+    assert_eq!(code[FN_ASO_TAKE_OFFSET as usize], INSTR_PUTA);
+    assert_eq!(code[FN_ASO_TAKE_LOTS_OFFSET as usize], INSTR_LDP);
+    assert_eq!(code[FN_ASO_GENESIS_OFFSET as usize], INSTR_PUTA);
+    assert_eq!(code[FN_ASO_GENESIS_LOTS_OFFSET as usize], INSTR_LDS);
+
+    Schema {
+        ffv: zero!(),
+        flags: none!(),
+        name: params.name_or(tn!("AssetSwapOffer")),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
+        meta_types: none!(),
+        global_types: tiny_bmap! {
+            GS_NOMINAL => GlobalStateSchema::once(types.get("RGBContract.AssetSpec")),
+            GS_TERMS => GlobalStateSchema::once(types.get("RGBContract.ContractTerms")),
+            GS_ISSUED_SUPPLY => GlobalStateSchema::once(types.get("RGBContract.Amount")),
+            GS_OFFER => GlobalStateSchema::once(types.get("RGBSchemata.OfferTerms")),
+            GS_FILL => GlobalStateSchema::many(types.get("RGBSchemata.OfferFill")),
+        },
+        owned_types: tiny_bmap! {
+            OS_ASSET => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit),
+            OS_LOT => OwnedStateSchema::Structured(types.get("RGBContract.Amount")),
+        },
+        valency_types: none!(),
+        genesis: GenesisSchema {
+            metadata: none!(),
+            globals: tiny_bmap! {
+                GS_NOMINAL => Occurrences::Once,
+                GS_TERMS => Occurrences::Once,
+                GS_ISSUED_SUPPLY => Occurrences::Once,
+                GS_OFFER => Occurrences::Once,
+            },
+            assignments: tiny_bmap! {
+                OS_LOT => Occurrences::OnceOrMore,
+            },
+            valencies: none!(),
+            validator: Some(LibSite::with(FN_ASO_GENESIS_OFFSET, aso_id)),
+        },
+        extensions: none!(),
+        transitions: tiny_bmap! {
+            TS_TRANSFER => TransitionSchema {
+                metadata: none!(),
+                globals: none!(),
+                inputs: tiny_bmap! {
+                    OS_ASSET => Occurrences::OnceOrMore
+                },
+                assignments: tiny_bmap! {
+                    OS_ASSET => Occurrences::OnceOrMore
+                },
+                valencies: none!(),
+                validator: Some(LibSite::with(FN_NIA_TRANSFER_OFFSET, nia_id))
+            },
+            TS_TAKE => TransitionSchema {
+                metadata: none!(),
+                globals: tiny_bmap! {
+                    GS_FILL => Occurrences::Once,
+                },
+                inputs: tiny_bmap! {
+                    OS_LOT => Occurrences::OnceOrMore
+                },
+                assignments: tiny_bmap! {
+                    OS_ASSET => Occurrences::OnceOrMore
+                },
+                valencies: none!(),
+                validator: Some(LibSite::with(FN_ASO_TAKE_OFFSET, aso_id))
+            },
+        },
+        reserved: none!(),
+    }
+}
+
+fn aso_rgb20() -> IfaceImpl { aso_rgb20_with(&SchemaParams::default()) }
+
+pub(crate) fn aso_rgb20_with(params: &SchemaParams) -> IfaceImpl {
+    let schema = aso_schema_with(params);
+    let iface = Rgb20::FIXED;
+
+    IfaceImpl {
+        version: VerNo::V1,
+        schema_id: schema.schema_id(),
+        iface_id: iface.iface_id(),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
+        metadata: none!(),
+        global_state: tiny_bset! {
+            NamedField::with(GS_NOMINAL, fname!("spec")),
+            NamedField::with(GS_TERMS, fname!("terms")),
+            NamedField::with(GS_ISSUED_SUPPLY, fname!("issuedSupply")),
+            NamedField::with(GS_OFFER, fname!("offer")),
+            NamedField::with(GS_FILL, fname!("fill")),
+        },
+        assignments: tiny_bset! {
+            NamedField::with(OS_ASSET, fname!("assetOwner")),
+            NamedField::with(OS_LOT, fname!("lot")),
+        },
+        valencies: none!(),
+        transitions: tiny_bset! {
+            NamedField::with(TS_TRANSFER, fname!("transfer")),
+            NamedField::with(TS_TAKE, fname!("take")),
+        },
+        extensions: none!(),
+        errors: tiny_bset![
+            NamedVariant::with(ERRNO_ISSUED_MISMATCH, vname!("issuedMismatch")),
+            NamedVariant::with(ERRNO_NON_EQUAL_IN_OUT, vname!("nonEqualAmounts")),
+        ],
+    }
+}
+
+#[derive(Default)]
+pub struct AssetSwapOffer;
+
+impl IssuerWrapper for AssetSwapOffer {
+    const FEATURES: Rgb20 = Rgb20::FIXED;
+    type IssuingIface = Rgb20;
+
+    fn schema() -> Schema { aso_schema() }
+    fn issue_impl() -> IfaceImpl { aso_rgb20() }
+
+    fn types() -> TypeSystem { StandardTypes::with(schemata_stl()).type_system() }
+
+    fn scripts() -> Scripts {
+        let nia = nia_lib();
+        let aso = aso_lib();
+        Confined::from_checked(bmap! { nia.id() => nia, aso.id() => aso })
+    }
+}
+
+impl OfferTerms {
+    pub fn new(counter_asset: ContractId, counter_amount: impl Into<Amount>) -> Self {
+        OfferTerms {
+            counter_asset: counter_asset.into_inner(),
+            counter_amount: counter_amount.into().value(),
+        }
+    }
+
+    pub fn counter_contract_id(&self) -> ContractId { ContractId::from(self.counter_asset) }
+
+    /// Amount of the counter-asset due for taking `taken` out of the offered
+    /// `supply`, rounded up. Returns `None` if nothing is offered or the
+    /// taken amount exceeds the supply.
+    pub fn due(&self, supply: impl Into<Amount>, taken: impl Into<Amount>) -> Option<Amount> {
+        let supply = supply.into().value() as u128;
+        let taken = taken.into().value() as u128;
+        if supply == 0 || taken > supply {
+            return None;
+        }
+        let due = (self.counter_amount as u128 * taken).div_ceil(supply);
+        Some(Amount::from(due as u64))
+    }
+}
+
+impl OfferFill {
+    /// Detects whether the paid amount of the counter-asset covers the taken
+    /// part of the offered `supply` under the offer terms, as checked by
+    /// [`AssetSwapOffer::check_take`].
+    pub fn is_covered(&self, offer: &OfferTerms, supply: impl Into<Amount>) -> bool {
+        offer
+            .due(supply, self.taken)
+            .is_some_and(|due| self.paid >= due.value())
+    }
+}
+
+impl AssetSwapOffer {
+    /// Name of the global state with [`OfferTerms`].
+    pub const OFFER: &'static str = "offer";
+    /// Name of the global state with [`OfferFill`] records.
+    pub const FILL: &'static str = "fill";
+    /// Name of the offered lots, which only [`Self::TAKE`] transition can
+    /// spend.
+    pub const LOT: &'static str = "lot";
+    /// Name of the transition handing the offered asset over to a taker.
+    pub const TAKE: &'static str = "take";
+
+    /// Issues the offer with the allocations of the offered lots.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        terms: ContractTerms,
+        offer: OfferTerms,
+        allocations: impl IntoIterator<Item = (Method, impl TxOutpoint, impl Into<Amount>)>,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        let schema_id = Self::schema().schema_id();
+        let iface_id = Self::FEATURES.iface().iface_id();
+        let asset_tag = AssetTag::new_random(format!("{schema_id}/{iface_id}"), OS_ASSET);
+        let mut builder =
+            Self::builder(issuer, ticker, name, details, precision, terms, offer, false)?
+                .add_asset_tag("assetOwner", asset_tag)
+                .expect("invalid contract data");
+        let mut issued = Amount::ZERO;
+        for (method, beneficiary, amount) in allocations {
+            let amount = amount.into();
+            let beneficiary = beneficiary.map_to_xchain(|outpoint| {
+                GenesisSeal::new_random(method, outpoint.txid, outpoint.vout)
+            });
+            issued
+                .checked_add_assign(amount)
+                .ok_or(IssuerError::AmountOverflow)?;
+            builder = builder
+                .add_data(Self::LOT, beneficiary, amount)
+                .expect("invalid contract data");
+        }
+        Ok(builder
+            .add_global_state("issuedSupply", issued)
+            .and_then(|builder| builder.issue_contract())
+            .expect("invalid contract data"))
+    }
+
+    /// Issues contract in a deterministic way, such that the same arguments
+    /// always produce the same contract id.
+    ///
+    /// Each allocation of a lot is provided with the seal closing method,
+    /// outpoint and blinding, followed by the lot amount and its salt. The
+    /// asset tag is used by the allocations paid out to the takers.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet_det(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        offer: OfferTerms,
+        asset_tag: AssetTag,
+        allocations: impl IntoIterator<Item = (Method, impl TxOutpoint, u64, impl Into<Amount>, u128)>,
+        timestamp: i64,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        let terms = ContractTerms {
+            text: RicardianContract::default(),
+            media: None,
+        };
+        let mut builder =
+            Self::builder(issuer, ticker, name, details, precision, terms, offer, true)?
+                .add_asset_tag("assetOwner", asset_tag)
+                .expect("invalid contract data");
+        let mut issued = Amount::ZERO;
+        for (method, beneficiary, seal_blinding, amount, salt) in allocations {
+            let amount = amount.into();
+            let beneficiary = beneficiary.map_to_xchain(|outpoint| {
+                GenesisSeal::with_blinding(method, outpoint.txid, outpoint.vout, seal_blinding)
+            });
+            issued
+                .checked_add_assign(amount)
+                .ok_or(IssuerError::AmountOverflow)?;
+            let lot = amount
+                .to_strict_serialized::<U16>()
+                .expect("amount fits into structured state");
+            builder = builder
+                .add_data_det(Self::LOT, beneficiary, RevealedData::with_salt(lot, salt))
+                .expect("invalid contract data");
+        }
+        Ok(builder
+            .add_global_state("issuedSupply", issued)
+            .and_then(|builder| builder.issue_contract_det(timestamp))
+            .expect("invalid contract data"))
+    }
+
+    /// Checks `take` transition against the terms of the offer with the given
+    /// genesis, returning the fill if the paid amount covers the taken amount.
+    /// The check complements the validation scripts, which can't access the
+    /// offer terms.
+    pub fn check_take(genesis: &Genesis, take: &Transition) -> Result<OfferFill, TakeError> {
+        if take.contract_id != genesis.contract_id() || take.transition_type != TS_TAKE {
+            return Err(TakeError::NotTake);
+        }
+        let terms: OfferTerms = global_state(&genesis.globals, GS_OFFER)?;
+        let supply: Amount = global_state(&genesis.globals, GS_ISSUED_SUPPLY)?;
+        let fill: OfferFill = global_state(&take.globals, GS_FILL)?;
+        if !fill.is_covered(&terms, supply) {
+            return Err(TakeError::Uncovered(fill));
+        }
+        Ok(fill)
+    }
+
+    /// Builder of `take` transition of the offer contract, to which the taker
+    /// adds the inputs with the offered lots, the asset tag of `assetOwner`
+    /// state from the offer genesis, its `assetOwner` allocations and the
+    /// [`OfferFill`] under [`Self::FILL`] name.
+    ///
+    /// The take has no change: the lots are spent as a whole, and the taken
+    /// amount of the fill must be equal both to the sum of the spent lots and
+    /// to the sum of the allocations. A taker willing to take a part of a lot
+    /// takes the whole lot and transfers the rest of the asset away with a
+    /// separate `transfer`.
+    pub fn take(contract_id: ContractId) -> TransitionBuilder {
+        TransitionBuilder::named_transition(
+            contract_id,
+            Self::FEATURES.iface(),
+            Self::schema(),
+            Self::issue_impl(),
+            Self::TAKE,
+            Self::types(),
+        )
+        .expect("take transition is defined by the schema")
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn builder(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        terms: ContractTerms,
+        offer: OfferTerms,
+        deterministic: bool,
+    ) -> Result<ContractBuilder, FungibleIssueError> {
+        let spec = AssetSpec::with(ticker, name, precision, details)?;
+        let issuer = issuer_identity(issuer)?;
+        let builder = match deterministic {
+            false => ContractBuilder::with,
+            true => ContractBuilder::deterministic,
+        };
+        Ok(builder(
+            issuer,
+            Self::FEATURES.iface(),
+            Self::schema(),
+            Self::issue_impl(),
+            Self::types(),
+            Self::scripts(),
+        )
+        .add_global_state("spec", spec)
+        .and_then(|builder| builder.add_global_state("terms", terms))
+        .and_then(|builder| builder.add_global_state(Self::OFFER, offer))
+        .expect("invalid contract data"))
+    }
+}
+
+fn global_state<T: StrictDeserialize>(
+    globals: &GlobalState,
+    ty: GlobalStateType,
+) -> Result<T, TakeError> {
+    let value = globals
+        .get(&ty)
+        .and_then(|values| values.first())
+        .ok_or(TakeError::InvalidState(ty))?;
+    let value: &[u8] = value.as_ref();
+    T::from_strict_serialized::<U16>(Confined::from_checked(value.to_vec()))
+        .map_err(|_| TakeError::InvalidState(ty))
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use amplify::confinement::U16;
+    use bp::Txid;
+    use rgbstd::containers::BuilderSeal;
+    use rgbstd::persistence::PersistedState;
+    use rgbstd::validation::Failure;
+    use rgbstd::{Assign, DataState, GraphSeal, OccurrencesMismatch, OpId, Opout, XChain};
+
+    use super::*;
+    use crate::{BundledSchema, OpValidator};
+
+    const TXID: &str = "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5";
+
+    fn outpoint(vout: u32) -> bp::Outpoint {
+        bp::Outpoint::new(Txid::from_str(TXID).unwrap(), vout)
+    }
+
+    fn seal(vout: u32) -> BuilderSeal<GraphSeal> {
+        let seal = GraphSeal::new_random(Method::TapretFirst, Txid::from_str(TXID).unwrap(), vout);
+        BuilderSeal::from(XChain::Bitcoin(seal))
+    }
+
+    /// Offer of 1000 units in lots of 400 and 600 units for 500 units of the
+    /// counter-asset.
+    fn offer_for(counter_asset: ContractId) -> ValidContract {
+        AssetSwapOffer::testnet(
+            "ssi:anonymous",
+            "SWAP",
+            "Offered asset",
+            None,
+            Precision::Indivisible,
+            ContractTerms {
+                text: RicardianContract::default(),
+                media: None,
+            },
+            OfferTerms::new(counter_asset, 500u64),
+            [
+                (Method::TapretFirst, outpoint(1), 400u64),
+                (Method::TapretFirst, outpoint(2), 600u64),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn offer() -> ValidContract { offer_for(ContractId::from([0x11; 32])) }
+
+    fn offer_terms(offer: &ValidContract) -> OfferTerms {
+        let value: &[u8] = offer.genesis.globals[&GS_OFFER][0].as_ref();
+        OfferTerms::from_strict_serialized::<U16>(Confined::from_checked(value.to_vec())).unwrap()
+    }
+
+    /// Lot of the offer genesis with the given amount and its index.
+    fn lot(genesis: &Genesis, amount: u64) -> (u16, RevealedData) {
+        let value = DataState::from(Amount::from(amount).to_strict_serialized::<U16>().unwrap());
+        genesis.assignments[&OS_LOT]
+            .as_structured()
+            .iter()
+            .enumerate()
+            .find_map(|(no, assign)| match assign {
+                Assign::Revealed { state, .. } if state.value == value => {
+                    Some((no as u16, state.clone()))
+                }
+                _ => None,
+            })
+            .expect("no lot with the amount")
+    }
+
+    /// Takes the lot of 400 units, splitting it between two outputs of the
+    /// taker, and validates the resulting transition.
+    fn take(offer: &ValidContract, fill: Option<OfferFill>) -> (Transition, OpId, Vec<Failure>) {
+        take_into(offer, fill, [300, 100])
+    }
+
+    /// Takes the lot of 400 units into the outputs of the taker with the given
+    /// amounts and validates the resulting transition.
+    fn take_into(
+        offer: &ValidContract,
+        fill: Option<OfferFill>,
+        outputs: [u64; 2],
+    ) -> (Transition, OpId, Vec<Failure>) {
+        let genesis = offer.genesis.clone();
+        let (no, lot) = lot(&genesis, 400);
+        let mut builder = AssetSwapOffer::take(genesis.contract_id())
+            .add_asset_tag("assetOwner", genesis.asset_tags[&OS_ASSET])
+            .unwrap()
+            .add_input(
+                Opout::new(genesis.id(), OS_LOT, no),
+                PersistedState::Data(lot.value, lot.salt),
+            )
+            .unwrap()
+            .add_fungible_state("assetOwner", seal(3), outputs[0])
+            .unwrap()
+            .add_fungible_state("assetOwner", seal(4), outputs[1])
+            .unwrap();
+        if let Some(fill) = fill {
+            builder = builder
+                .add_global_state(AssetSwapOffer::FILL, fill)
+                .unwrap();
+        }
+        let transition: Transition = builder.complete_transition().unwrap();
+        let mut validator = OpValidator::bundled(BundledSchema::AssetSwapOffer, genesis);
+        let opid = validator.add_transition(transition.clone());
+        let failures = validator.validate(opid).failures;
+        (transition, opid, failures)
+    }
+
+    #[test]
+    fn iimpl_check() {
+        let iface = Rgb20::FIXED.iface();
+        if let Err(err) = aso_rgb20().check(&iface, &aso_schema()) {
+            for e in err {
+                eprintln!("{e}");
+            }
+            panic!("invalid ASO RGB20 interface implementation");
+        }
+    }
+
+    #[test]
+    fn terms() {
+        let terms = offer_terms(&offer());
+        assert_eq!(terms.counter_amount, 500);
+        assert_eq!(terms.due(1000u64, 1000u64), Some(Amount::from(500u64)));
+        assert_eq!(terms.due(1000u64, 3u64), Some(Amount::from(2u64)));
+        assert_eq!(terms.due(1000u64, 1001u64), None);
+        assert_eq!(terms.due(0u64, 0u64), None);
+
+        assert!(OfferFill { taken: 3, paid: 2 }.is_covered(&terms, 1000u64));
+        assert!(!OfferFill { taken: 3, paid: 1 }.is_covered(&terms, 1000u64));
+    }
+
+    #[test]
+    fn take_fill() {
+        let offer = offer();
+        let fill = OfferFill {
+            taken: 400,
+            paid: 200,
+        };
+        let (transition, _, failures) = take(&offer, Some(fill));
+        assert_eq!(failures, vec![]);
+        assert_eq!(AssetSwapOffer::check_take(&offer.genesis, &transition), Ok(fill));
+    }
+
+    #[test]
+    fn take_underpaid() {
+        let offer = offer();
+        let fill = OfferFill {
+            taken: 400,
+            paid: 199,
+        };
+        // The scripts can't access the offer terms, thus the underpaid fill is
+        // valid unless checked by the maker.
+        let (transition, _, failures) = take(&offer, Some(fill));
+        assert_eq!(failures, vec![]);
+        assert_eq!(
+            AssetSwapOffer::check_take(&offer.genesis, &transition),
+            Err(TakeError::Uncovered(fill))
+        );
+        assert_eq!(
+            AssetSwapOffer::check_take(
+                &offer_for(ContractId::from([0x22; 32])).genesis,
+                &transition
+            ),
+            Err(TakeError::NotTake)
+        );
+    }
+
+    #[test]
+    fn take_mismatch() {
+        let (_, opid, failures) = take(
+            &offer(),
+            Some(OfferFill {
+                taken: 300,
+                paid: 200,
+            }),
+        );
+        assert_eq!(failures, vec![Failure::ScriptFailure(opid, Some(ERRNO_FILL_MISMATCH), None)]);
+
+        let fill = OfferFill {
+            taken: 400,
+            paid: 200,
+        };
+        let (_, opid, failures) = take_into(&offer(), Some(fill), [300, 200]);
+        assert_eq!(failures, vec![Failure::ScriptFailure(opid, Some(ERRNO_FILL_MISMATCH), None)]);
+    }
+
+    #[test]
+    fn transfer_lot() {
+        let genesis = offer().genesis.clone();
+        let (no, lot) = lot(&genesis, 400);
+        let transfer: Transition = BundledSchema::AssetSwapOffer
+            .builder()
+            .transfer(genesis.contract_id())
+            .with(|builder| {
+                builder
+                    .add_asset_tag("assetOwner", genesis.asset_tags[&OS_ASSET])?
+                    .add_input(
+                        Opout::new(genesis.id(), OS_LOT, no),
+                        PersistedState::Data(lot.value, lot.salt),
+                    )?
+                    .add_fungible_state("assetOwner", seal(3), 400u64)
+            })
+            .unwrap()
+            .complete_transition()
+            .unwrap();
+        let mut validator = OpValidator::bundled(BundledSchema::AssetSwapOffer, genesis);
+        let opid = validator.add_transition(transfer);
+        assert_eq!(validator.validate(opid).failures, vec![
+            Failure::SchemaUnknownAssignmentType(opid, OS_LOT),
+            Failure::SchemaInputOccurrences(opid, OS_ASSET, OccurrencesMismatch {
+                min: 1,
+                max: u16::MAX,
+                found: 0
+            }),
+            Failure::ScriptFailure(opid, Some(ERRNO_NON_EQUAL_IN_OUT), None),
+        ]);
+    }
+
+    #[test]
+    fn take_unfilled() {
+        let offer = offer();
+        let (transition, opid, failures) = take(&offer, None);
+        assert_eq!(
+            AssetSwapOffer::check_take(&offer.genesis, &transition),
+            Err(TakeError::InvalidState(GS_FILL))
+        );
+        assert_eq!(failures, vec![
+            Failure::SchemaGlobalStateOccurrences(opid, GS_FILL, OccurrencesMismatch {
+                min: 1,
+                max: 1,
+                found: 0
+            }),
+            Failure::ScriptFailure(opid, Some(ERRNO_FILL_MISMATCH), None),
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "nia")]
+    fn counter_asset() {
+        use crate::NonInflatableAsset;
+
+        let counter = NonInflatableAsset::testnet(
+            "ssi:anonymous",
+            "CNTR",
+            "Counter-asset",
+            None,
+            Precision::Indivisible,
+            [(Method::TapretFirst, outpoint(5), 1000u64)],
+        )
+        .unwrap();
+        let counter_id = counter.genesis.contract_id();
+
+        let offer = offer_for(counter_id);
+        let terms = offer_terms(&offer);
+        assert_eq!(terms.counter_contract_id(), counter_id);
+
+        let fill = OfferFill {
+            taken: 400,
+            paid: 200,
+        };
+        let (transition, _, failures) = take(&offer, Some(fill));
+        assert_eq!(failures, vec![]);
+        assert_eq!(AssetSwapOffer::check_take(&offer.genesis, &transition), Ok(fill));
+
+        // Payment of the counter-asset to the maker, committed in the same
+        // witness transaction with the take.
+        let genesis = counter.genesis.clone();
+        let no = 0;
+        let input = *genesis.assignments[&OS_ASSET].as_fungible()[0]
+            .as_revealed_state()
+            .unwrap();
+        let payment: Transition = BundledSchema::NonInflatableAsset
+            .builder()
+            .transfer(counter_id)
+            .with(|builder| {
+                builder
+                    .add_asset_tag("assetOwner", input.tag)?
+                    .add_input(
+                        Opout::new(genesis.id(), OS_ASSET, no),
+                        PersistedState::Amount(
+                            input.value.as_u64().into(),
+                            input.blinding,
+                            input.tag,
+                        ),
+                    )?
+                    .add_fungible_state("assetOwner", seal(6), fill.paid)?
+                    .add_fungible_state("assetOwner", seal(7), 1000 - fill.paid)
+            })
+            .unwrap()
+            .complete_transition()
+            .unwrap();
+        assert_eq!(payment.contract_id, terms.counter_contract_id());
+        let paid = payment.assignments[&OS_ASSET]
+            .as_fungible()
+            .iter()
+            .filter_map(|assign| match assign {
+                Assign::Revealed { state, .. } => Some(state.value.as_u64()),
+                _ => None,
+            })
+            .any(|amount| amount == fill.paid);
+        assert!(paid);
+
+        let mut validator = OpValidator::bundled(BundledSchema::NonInflatableAsset, genesis);
+        let opid = validator.add_transition(payment);
+        assert_eq!(validator.validate(opid).failures, vec![]);
+    }
+}
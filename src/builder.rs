@@ -0,0 +1,379 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Construction of the bundled schemata and their interface implementations
+//! under a custom developer identity, timestamp and schema name, for running
+//! private forks of the schemata.
+//!
+//! These parameters don't affect validation rules, but change schema and
+//! interface implementation ids, so contracts issued under a fork are
+//! distinct from the contracts issued under the official schemata.
+//!
+//! The builder also produces schema variants which additionally require
+//! transfers to carry [`TransferMemo`] metadata, and builds transfers under
+//! the produced schemata with [`TransferBuilder`].
+
+use amplify::confinement::U16;
+use ifaces::{SchemaIssuer, LNPBP_IDENTITY};
+use rgbstd::containers::Kit;
+use rgbstd::interface::{BuilderError, IfaceClass, IfaceImpl, TransitionBuilder};
+use rgbstd::validation::Scripts;
+use rgbstd::{ContractId, Identity, Metadata, Schema, Transition};
+use strict_encoding::{FieldName, StrictSerialize};
+use strict_types::{TypeName, TypeSystem};
+
+#[cfg(feature = "aso")]
+use crate::aso::{aso_rgb20_with, aso_schema_with};
+#[cfg(feature = "cfa")]
+use crate::cfa::{cfa_rgb25_with, cfa_schema_with};
+#[cfg(feature = "lfa")]
+use crate::lfa::{lfa_rgb20_with, lfa_schema_with};
+use crate::memo::{declare_memo, memo_types, name_memo, FIELD_TRANSFER_MEMO, MT_TRANSFER_MEMO};
+#[cfg(feature = "nia")]
+use crate::nia::{nia_rgb20_with, nia_schema_with};
+#[cfg(feature = "uda")]
+use crate::uda::{uda_rgb21_with, uda_schema_with};
+use crate::{BundledSchema, TransferMemo};
+
+/// Timestamp of the official schemata and their interface implementations.
+pub const OFFICIAL_TIMESTAMP: i64 = 1713343888;
+
+/// Schema and interface implementation fields distinguishing forks and
+/// variants of the bundled schemata from the official ones.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SchemaParams {
+    pub developer: Identity,
+    pub timestamp: i64,
+    /// Overrides the official schema name, if set.
+    pub name: Option<TypeName>,
+    /// Requires transfers to carry [`TransferMemo`] metadata.
+    pub transfer_memo: bool,
+}
+
+impl Default for SchemaParams {
+    fn default() -> Self {
+        SchemaParams {
+            developer: Identity::from(LNPBP_IDENTITY),
+            timestamp: OFFICIAL_TIMESTAMP,
+            name: None,
+            transfer_memo: false,
+        }
+    }
+}
+
+impl SchemaParams {
+    #[cfg(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    ))]
+    pub(crate) fn name_or(&self, official: TypeName) -> TypeName {
+        self.name.clone().unwrap_or(official)
+    }
+}
+
+/// Builder of one of the bundled schemata with custom [`SchemaParams`].
+/// Without any parameters set it produces the official schema.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SchemaBuilder {
+    schema: BundledSchema,
+    params: SchemaParams,
+}
+
+impl SchemaBuilder {
+    /// Name of the transfer metadata with [`TransferMemo`] in the
+    /// schema variants produced with [`Self::transfer_memo`].
+    pub const TRANSFER_MEMO: &'static str = FIELD_TRANSFER_MEMO;
+
+    pub fn new(schema: BundledSchema) -> Self {
+        SchemaBuilder {
+            schema,
+            params: SchemaParams::default(),
+        }
+    }
+
+    pub fn developer(mut self, developer: Identity) -> Self {
+        self.params.developer = developer;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.params.timestamp = timestamp;
+        self
+    }
+
+    pub fn name(mut self, name: TypeName) -> Self {
+        self.params.name = Some(name);
+        self
+    }
+
+    /// Produces schema variant requiring transfers to carry a memo, named
+    /// [`Self::TRANSFER_MEMO`] in the interface implementation.
+    pub fn transfer_memo(mut self) -> Self {
+        self.params.transfer_memo = true;
+        self
+    }
+
+    pub fn params(&self) -> &SchemaParams { &self.params }
+
+    /// Detects whether the builder produces the official schema.
+    pub fn is_official(&self) -> bool { self.params == SchemaParams::default() }
+
+    pub fn schema(&self) -> Schema {
+        let mut schema = self.base_schema();
+        if self.params.transfer_memo {
+            declare_memo(&mut schema);
+        }
+        schema
+    }
+
+    /// Interface implementation for the schema produced by [`Self::schema`].
+    pub fn issue_impl(&self) -> IfaceImpl {
+        let mut iimpl = self.base_issue_impl();
+        if self.params.transfer_memo {
+            name_memo(&mut iimpl, &self.schema());
+        }
+        iimpl
+    }
+
+    /// Schema with the builder parameters, before the transfer memo is
+    /// declared.
+    fn base_schema(&self) -> Schema {
+        match self.schema {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => nia_schema_with(&self.params),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => uda_schema_with(&self.params),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => cfa_schema_with(&self.params),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => lfa_schema_with(&self.params),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => aso_schema_with(&self.params),
+        }
+    }
+
+    /// Interface implementation for [`Self::base_schema`].
+    fn base_issue_impl(&self) -> IfaceImpl {
+        match self.schema {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => nia_rgb20_with(&self.params),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => uda_rgb21_with(&self.params),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => cfa_rgb25_with(&self.params),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => lfa_rgb20_with(&self.params),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => aso_rgb20_with(&self.params),
+        }
+    }
+
+    /// Types used by the schema produced by the builder.
+    pub fn types(&self) -> TypeSystem {
+        let mut types = self.schema.types();
+        if self.params.transfer_memo {
+            memo_types(&mut types);
+        }
+        types
+    }
+
+    pub fn scripts(&self) -> Scripts { self.schema.scripts() }
+
+    /// Issuer of contracts under the schema produced by the builder, to be
+    /// used with `testnet_with` constructors of the interface issuers. The
+    /// interface features must match the interface of the bundled schema.
+    pub fn issuer<I: IfaceClass>(&self, features: I) -> SchemaIssuer<I> {
+        SchemaIssuer::new(self.schema(), self.issue_impl(), self.types(), self.scripts(), features)
+            .expect("schema types are absent from its type system")
+    }
+
+    /// Builder of a transfer of the contract issued under the schema produced
+    /// by the builder.
+    pub fn transfer(&self, contract_id: ContractId) -> TransferBuilder {
+        let iimpl = self.issue_impl();
+        let builder = TransitionBuilder::default_transition(
+            contract_id,
+            self.schema.iface(),
+            self.schema(),
+            iimpl.clone(),
+            self.types(),
+        )
+        .expect("interfaces of the bundled schemata define default transfer");
+        TransferBuilder {
+            builder,
+            iimpl,
+            metadata: none!(),
+            transfer_memo: self.params.transfer_memo,
+        }
+    }
+
+    /// Kit with the schema and interface implementation produced by the
+    /// builder, together with the interface, validation scripts and types.
+    pub fn kit(&self) -> Kit {
+        let mut kit = Kit::default();
+        kit.schemata.push(self.schema()).unwrap();
+        kit.ifaces.push(self.schema.iface()).unwrap();
+        kit.iimpls.push(self.issue_impl()).unwrap();
+        kit.scripts.extend(self.scripts().into_values()).unwrap();
+        kit.types = self.types();
+        kit
+    }
+}
+
+impl BundledSchema {
+    pub fn builder(self) -> SchemaBuilder { SchemaBuilder::new(self) }
+}
+
+/// Builder of a transfer of the contract issued under a schema produced by
+/// [`SchemaBuilder`].
+///
+/// Transition builder of RGB standard library doesn't put metadata into the
+/// completed transition, thus the metadata is kept by this builder and put
+/// into the transfer when it is completed. Transfers under the schema
+/// variants with memo carry [`TransferMemo::Empty`] unless a memo is added.
+#[derive(Clone, Debug)]
+pub struct TransferBuilder {
+    builder: TransitionBuilder,
+    iimpl: IfaceImpl,
+    metadata: Metadata,
+    transfer_memo: bool,
+}
+
+impl TransferBuilder {
+    /// Applies operations of the transition builder, like adding inputs and
+    /// owned state. Metadata must be added with [`Self::add_metadata`]
+    /// instead, otherwise it is dropped.
+    pub fn with(
+        mut self,
+        f: impl FnOnce(TransitionBuilder) -> Result<TransitionBuilder, BuilderError>,
+    ) -> Result<Self, BuilderError> {
+        self.builder = f(self.builder)?;
+        Ok(self)
+    }
+
+    pub fn add_metadata(
+        mut self,
+        name: impl Into<FieldName>,
+        value: impl StrictSerialize,
+    ) -> Result<Self, BuilderError> {
+        let name = name.into();
+        let serialized = value.to_strict_serialized::<U16>()?;
+        // checks that the metadata is known to the schema and has valid type
+        self.builder = self.builder.add_metadata(name.clone(), value)?;
+        let ty = self
+            .iimpl
+            .meta_type(&name)
+            .expect("metadata type is checked by the transition builder");
+        self.metadata.add_value(ty, serialized.into())?;
+        Ok(self)
+    }
+
+    /// Adds memo to the transfer under one of the schema variants with memo.
+    pub fn add_memo(self, memo: TransferMemo) -> Result<Self, BuilderError> {
+        self.add_metadata(SchemaBuilder::TRANSFER_MEMO, memo)
+    }
+
+    pub fn complete_transition(self) -> Result<Transition, BuilderError> {
+        let mut metadata = self.metadata;
+        if self.transfer_memo && metadata.get(&MT_TRANSFER_MEMO).is_none() {
+            let memo = TransferMemo::Empty
+                .to_strict_serialized::<U16>()
+                .expect("empty memo always fits into metadata value");
+            metadata.add_value(MT_TRANSFER_MEMO, memo.into())?;
+        }
+        let mut transition = self.builder.complete_transition()?;
+        transition.metadata = metadata;
+        Ok(transition)
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use std::str::FromStr;
+
+    use ifaces::IssuerWrapper;
+    use rgbstd::interface::IfaceClass;
+
+    use super::*;
+    use crate::{
+        lint, AssetSwapOffer, CollectibleFungibleAsset, LightningFungibleAsset, LintIssue,
+        NonInflatableAsset, UniqueDigitalAsset,
+    };
+
+    #[test]
+    fn official() {
+        for schema in BundledSchema::ALL {
+            let builder = schema.builder();
+            assert!(builder.is_official());
+            assert_eq!(builder.schema(), schema.schema());
+            assert_eq!(builder.issue_impl(), schema.issue_impl());
+        }
+    }
+
+    #[test]
+    fn fork() {
+        let developer = Identity::from_str("ssi:private-fork").unwrap();
+        for schema in BundledSchema::ALL {
+            let builder = schema
+                .builder()
+                .developer(developer.clone())
+                .timestamp(1720000000)
+                .name(tn!("PrivateAsset"));
+            assert!(!builder.is_official());
+
+            let forked = builder.schema();
+            let iimpl = builder.issue_impl();
+            assert_eq!(forked.developer, developer);
+            assert_eq!(forked.timestamp, 1720000000);
+            assert_eq!(forked.name, tn!("PrivateAsset"));
+            assert_eq!(iimpl.developer, developer);
+            assert_eq!(iimpl.timestamp, 1720000000);
+            assert_eq!(iimpl.schema_id, forked.schema_id());
+            assert_ne!(forked.schema_id(), schema.schema().schema_id());
+
+            let iface = match schema {
+                BundledSchema::NonInflatableAsset => NonInflatableAsset::FEATURES.iface(),
+                BundledSchema::UniqueDigitalAsset => UniqueDigitalAsset::FEATURES.iface(),
+                BundledSchema::CollectibleFungibleAsset => {
+                    CollectibleFungibleAsset::FEATURES.iface()
+                }
+                BundledSchema::LightningFungibleAsset => LightningFungibleAsset::FEATURES.iface(),
+                BundledSchema::AssetSwapOffer => AssetSwapOffer::FEATURES.iface(),
+            };
+            if let Err(err) = iimpl.check(&iface, &forked) {
+                panic!("{schema} fork interface implementation doesn't match: {err:?}");
+            }
+            assert!(lint(&forked, &iimpl, &schema.scripts())
+                .iter()
+                .all(|issue| matches!(issue, LintIssue::UnnamedErrno(..))));
+        }
+    }
+}
@@ -22,23 +22,34 @@
 //! Collectible Fungible Assets (CFA) schema implementing RGB25 fungible assets
 //! interface.
 
+use std::str::FromStr;
+
 use aluvm::library::LibSite;
 use amplify::confinement::Confined;
+use bp::dbc::Method;
+use ifaces::rgb20::IssuerError;
 use ifaces::rgb25::Rgb25;
-use ifaces::{IssuerWrapper, LNPBP_IDENTITY};
-use rgbstd::interface::{IfaceClass, IfaceImpl, NamedField, NamedVariant, VerNo};
+use ifaces::IssuerWrapper;
+use rgbstd::containers::ValidContract;
+use rgbstd::interface::{
+    ContractBuilder, IfaceClass, IfaceImpl, NamedField, NamedVariant, TxOutpoint, VerNo,
+};
 use rgbstd::schema::{
     FungibleType, GenesisSchema, GlobalStateSchema, Occurrences, Schema, TransitionSchema,
 };
-use rgbstd::stl::StandardTypes;
+use rgbstd::stl::{ContractTerms, Details, Name, RicardianContract, StandardTypes};
 use rgbstd::validation::Scripts;
-use rgbstd::{GlobalStateType, Identity, OwnedStateSchema};
+use rgbstd::{
+    Amount, AssetTag, BlindingFactor, GenesisSeal, GlobalStateType, OwnedStateSchema, Precision,
+};
 use strict_types::TypeSystem;
 
-use crate::nia::{nia_lib, FN_NIA_GENESIS_OFFSET, FN_NIA_TRANSFER_OFFSET};
+use crate::nia::{
+    issuer_identity, nia_lib, FungibleIssueError, FN_NIA_GENESIS_OFFSET, FN_NIA_TRANSFER_OFFSET,
+};
 use crate::{
-    ERRNO_ISSUED_MISMATCH, ERRNO_NON_EQUAL_IN_OUT, GS_ISSUED_SUPPLY, GS_TERMS, OS_ASSET,
-    TS_TRANSFER,
+    SchemaParams, ERRNO_ISSUED_MISMATCH, ERRNO_NON_EQUAL_IN_OUT, GS_ISSUED_SUPPLY, GS_TERMS,
+    OS_ASSET, TS_TRANSFER,
 };
 
 const GS_ART: GlobalStateType = GlobalStateType::with(3000);
@@ -46,7 +57,9 @@ const GS_NAME: GlobalStateType = GlobalStateType::with(3001);
 const GS_DETAILS: GlobalStateType = GlobalStateType::with(3004);
 const GS_PRECISION: GlobalStateType = GlobalStateType::with(3005);
 
-pub fn cfa_schema() -> Schema {
+pub fn cfa_schema() -> Schema { cfa_schema_with(&SchemaParams::default()) }
+
+pub(crate) fn cfa_schema_with(params: &SchemaParams) -> Schema {
     let types = StandardTypes::with(Rgb25::NONE.stl());
 
     let nia_id = nia_lib().id();
@@ -54,9 +67,9 @@ pub fn cfa_schema() -> Schema {
     Schema {
         ffv: zero!(),
         flags: none!(),
-        name: tn!("CollectibleFungibleAsset"),
-        timestamp: 1713343888,
-        developer: Identity::from(LNPBP_IDENTITY),
+        name: params.name_or(tn!("CollectibleFungibleAsset")),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
         meta_types: none!(),
         global_types: tiny_bmap! {
             GS_ART => GlobalStateSchema::once(types.get("RGBContract.Article")),
@@ -105,15 +118,17 @@ pub fn cfa_schema() -> Schema {
     }
 }
 
-pub fn cfa_rgb25() -> IfaceImpl {
-    let schema = cfa_schema();
+pub fn cfa_rgb25() -> IfaceImpl { cfa_rgb25_with(&SchemaParams::default()) }
+
+pub(crate) fn cfa_rgb25_with(params: &SchemaParams) -> IfaceImpl {
+    let schema = cfa_schema_with(params);
 
     IfaceImpl {
         version: VerNo::V1,
         schema_id: schema.schema_id(),
         iface_id: Rgb25::NONE.iface_id(),
-        timestamp: 1713343888,
-        developer: Identity::from(LNPBP_IDENTITY),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
         metadata: none!(),
         global_state: tiny_bset! {
             NamedField::with(GS_ART, fname!("art")),
@@ -156,8 +171,76 @@ impl IssuerWrapper for CollectibleFungibleAsset {
     }
 }
 
+impl CollectibleFungibleAsset {
+    /// Issues contract in a deterministic way, such that the same arguments
+    /// always produce the same contract id.
+    ///
+    /// Each allocation is provided with the seal closing method, outpoint and
+    /// blinding, followed by the allocated amount and its blinding factor.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet_det(
+        issuer: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        asset_tag: AssetTag,
+        allocations: impl IntoIterator<
+            Item = (Method, impl TxOutpoint, u64, impl Into<Amount>, BlindingFactor),
+        >,
+        timestamp: i64,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        let terms = ContractTerms {
+            text: RicardianContract::default(),
+            media: None,
+        };
+
+        let mut builder = ContractBuilder::deterministic(
+            issuer_identity(issuer)?,
+            Self::FEATURES.iface(),
+            Self::schema(),
+            Self::issue_impl(),
+            Self::types(),
+            Self::scripts(),
+        )
+        .add_global_state("name", Name::try_from(name.to_owned())?)
+        .and_then(|builder| builder.add_global_state("precision", precision))
+        .and_then(|builder| builder.add_global_state("terms", terms))
+        .and_then(|builder| builder.add_asset_tag("assetOwner", asset_tag))
+        .expect("invalid contract data");
+        if let Some(details) = details {
+            builder = builder
+                .add_global_state("details", Details::from_str(details)?)
+                .expect("invalid contract data");
+        }
+
+        let mut issued = Amount::ZERO;
+        for (method, beneficiary, seal_blinding, amount, amount_blinding) in allocations {
+            let amount = amount.into();
+            let beneficiary = beneficiary.map_to_xchain(|outpoint| {
+                GenesisSeal::with_blinding(method, outpoint.txid, outpoint.vout, seal_blinding)
+            });
+            issued
+                .checked_add_assign(amount)
+                .ok_or(IssuerError::AmountOverflow)?;
+            builder = builder
+                .add_fungible_state_det("assetOwner", beneficiary, amount, amount_blinding)
+                .expect("invalid contract data");
+        }
+
+        Ok(builder
+            .add_global_state("issuedSupply", issued)
+            .and_then(|builder| builder.issue_contract_det(timestamp))
+            .expect("invalid contract data"))
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use bp::{Outpoint, Txid};
+    use chrono::DateTime;
+    use rgbstd::containers::ConsignmentExt;
+    use rgbstd::AssignmentType;
+
     use super::*;
 
     #[test]
@@ -170,4 +253,64 @@ mod test {
             panic!("invalid CFA RGB25 interface implementation");
         }
     }
+
+    #[test]
+    fn testnet_det() {
+        let created_at = 1713261744;
+        let asset_tag = AssetTag::new_deterministic(
+            "contract_domain",
+            AssignmentType::with(0),
+            DateTime::from_timestamp(created_at, 0).unwrap(),
+            123456,
+        );
+        let outpoint = Outpoint::new(
+            Txid::from_str("8d54c98d4c29a1ec4fd90635f543f0f7a871a78eb6a6e706342f831d92e3ba19")
+                .unwrap(),
+            0,
+        );
+        let blinding = BlindingFactor::from_str(
+            "a3401bcceb26201b55978ff705fecf7d8a0a03598ebeccf2a947030b91a0ff53",
+        )
+        .unwrap();
+
+        let issue = || {
+            CollectibleFungibleAsset::testnet_det(
+                "ssi:anonymous",
+                "NAME",
+                Some("Details"),
+                Precision::try_from(2).unwrap(),
+                asset_tag,
+                [(Method::OpretFirst, outpoint, 654321, 999u64, blinding)],
+                created_at,
+            )
+            .unwrap()
+        };
+
+        let contract = issue();
+        assert_eq!(contract.schema_id(), CollectibleFungibleAsset::schema().schema_id());
+        assert_eq!(contract.contract_id(), issue().contract_id());
+        let overflow = CollectibleFungibleAsset::testnet_det(
+            "ssi:anonymous",
+            "NAME",
+            None,
+            Precision::try_from(2).unwrap(),
+            asset_tag,
+            [
+                (Method::OpretFirst, outpoint, 654321, u64::MAX, blinding),
+                (Method::OpretFirst, outpoint, 654322, 1u64, blinding),
+            ],
+            created_at,
+        );
+        assert_eq!(overflow.unwrap_err(), FungibleIssueError::Issuer(IssuerError::AmountOverflow));
+        let invalid_issuer = CollectibleFungibleAsset::testnet_det(
+            "",
+            "NAME",
+            None,
+            Precision::try_from(2).unwrap(),
+            asset_tag,
+            [(Method::OpretFirst, outpoint, 654321, 999u64, blinding)],
+            created_at,
+        );
+        assert_eq!(invalid_issuer.unwrap_err(), FungibleIssueError::InvalidIssuer(String::new()));
+    }
 }
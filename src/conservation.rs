@@ -0,0 +1,298 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Property tests checking that validators of the fungible schemata accept
+//! exactly the operations conserving asset amounts.
+//!
+//! The amounts allocated by ASO genesis are the offered lots, and the amounts
+//! spent by ASO transfers are the lots taken by `take` transitions.
+
+use amplify::confinement::{Confined, U16};
+use bp::dbc::Method;
+use bp::Txid;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use rgbstd::validation::Status;
+use rgbstd::{
+    Amount, Assign, BlindingFactor, Genesis, GenesisSeal, GlobalValues, GraphSeal, Operation,
+    RevealedData, RevealedValue, TypedAssigns, XChain,
+};
+use strict_encoding::StrictSerialize;
+
+use crate::testkit::{tag_outputs, take, transfer, Fixture};
+use crate::{
+    BundledSchema, OpValidator, SchemaErrno, ScriptFailure, ERRNO_FILL_MISMATCH,
+    ERRNO_ISSUED_MISMATCH, ERRNO_NON_EQUAL_IN_OUT, GS_ISSUED_SUPPLY, OS_ASSET, OS_LOT,
+};
+
+const SCHEMATA: [BundledSchema; 4] = [
+    BundledSchema::NonInflatableAsset,
+    BundledSchema::CollectibleFungibleAsset,
+    BundledSchema::LightningFungibleAsset,
+    BundledSchema::AssetSwapOffer,
+];
+
+/// Amounts biased towards zero and `u64::MAX`.
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0u64), Just(u64::MAX), 0u64..=8, (u64::MAX - 8)..=u64::MAX, any::<u64>()]
+}
+
+/// Splits total into given number of amounts, some of which may be zero.
+fn split(total: u64, parts: usize) -> impl Strategy<Value = Vec<u64>> {
+    vec(0..=total, parts - 1).prop_map(move |mut cuts| {
+        cuts.sort_unstable();
+        cuts.push(total);
+        let mut prev = 0;
+        cuts.into_iter()
+            .map(|cut| {
+                let amount = cut - prev;
+                prev = cut;
+                amount
+            })
+            .collect()
+    })
+}
+
+/// Genesis allocations with the reported issued supply, which is either equal
+/// to the sum of allocations or is off by one or random.
+fn genesis_case() -> impl Strategy<Value = (Vec<u64>, u64)> {
+    vec(amount(), 1..=4).prop_flat_map(|amounts| {
+        let exact = u64::try_from(sum(&amounts)).unwrap_or(u64::MAX);
+        let supply = prop_oneof![
+            Just(exact),
+            Just(exact.wrapping_add(1)),
+            Just(exact.wrapping_sub(1)),
+            amount(),
+        ];
+        (Just(amounts), supply)
+    })
+}
+
+/// Transfer input and output amounts, where outputs either split the same
+/// total as inputs, or a total which is off by one, or are random.
+fn transfer_case() -> impl Strategy<Value = (Vec<u64>, Vec<u64>)> {
+    (amount(), 1usize..=3, 1usize..=3).prop_flat_map(|(total, inputs, outputs)| {
+        let outputs = prop_oneof![
+            split(total, outputs),
+            split(total.wrapping_add(1), outputs),
+            split(total.wrapping_sub(1), outputs),
+            vec(amount(), outputs),
+        ];
+        (split(total, inputs), outputs)
+    })
+}
+
+fn sum(amounts: &[u64]) -> u128 { amounts.iter().map(|amount| *amount as u128).sum() }
+
+/// Random blinding factors which sum up to the sum of the `target` ones.
+fn balanced(count: usize, target: impl IntoIterator<Item = BlindingFactor>) -> Vec<BlindingFactor> {
+    let mut blindings = (1..count)
+        .map(|_| BlindingFactor::random())
+        .collect::<Vec<_>>();
+    let last = BlindingFactor::zero_balanced(target, blindings.iter().copied())
+        .expect("blinding factors can't be balanced");
+    blindings.push(last);
+    blindings
+}
+
+/// Error code of the transfer validation failing to conserve the amounts.
+fn transfer_errno(schema: BundledSchema) -> u8 {
+    match schema {
+        BundledSchema::AssetSwapOffer => ERRNO_FILL_MISMATCH,
+        _ => ERRNO_NON_EQUAL_IN_OUT,
+    }
+}
+
+/// Genesis of a fungible schema with the given allocations and reported
+/// issued supply, returned together with the blinding factors which the
+/// transfer outputs must balance.
+fn fungible_genesis(
+    schema: BundledSchema,
+    amounts: &[u64],
+    supply: u64,
+) -> (Genesis, Vec<BlindingFactor>) {
+    let mut genesis = Fixture::get(schema).genesis.clone();
+    let tag = *genesis
+        .asset_tags
+        .get(&OS_ASSET)
+        .expect("fungible asset tag");
+    let supply = Amount::from(supply).to_strict_serialized::<U16>().unwrap();
+    genesis
+        .globals
+        .insert(GS_ISSUED_SUPPLY, GlobalValues::with(supply.into()))
+        .unwrap();
+    let seal = |no: usize| {
+        GenesisSeal::with_blinding(Method::OpretFirst, Txid::coinbase(), no as u32, no as u64)
+    };
+
+    if schema == BundledSchema::AssetSwapOffer {
+        let assigns = amounts.iter().enumerate().map(|(no, amount)| {
+            let lot = Amount::from(*amount).to_strict_serialized::<U16>().unwrap();
+            let state = RevealedData::with_salt(lot, no as u128);
+            Assign::revealed(XChain::Bitcoin(seal(no)), state)
+        });
+        genesis
+            .assignments
+            .insert(OS_LOT, TypedAssigns::Structured(Confined::from_iter_checked(assigns)))
+            .unwrap();
+        return (genesis, vec![BlindingFactor::EMPTY]);
+    }
+
+    let blindings = balanced(amounts.len(), [BlindingFactor::EMPTY]);
+    let assigns = amounts
+        .iter()
+        .zip(&blindings)
+        .enumerate()
+        .map(|(no, (amount, blinding))| {
+            let state = RevealedValue::with_blinding(*amount, *blinding, tag);
+            Assign::revealed(XChain::Bitcoin(seal(no)), state)
+        });
+    genesis
+        .assignments
+        .insert(OS_ASSET, TypedAssigns::Fungible(Confined::from_iter_checked(assigns)))
+        .unwrap();
+    (genesis, blindings)
+}
+
+fn validate_genesis(schema: BundledSchema, amounts: &[u64], supply: u64) -> (OpValidator, Status) {
+    let (genesis, _) = fungible_genesis(schema, amounts, supply);
+    let opid = genesis.id();
+    let validator = Fixture::get(schema).validator(genesis);
+    let status = validator.validate(opid);
+    (validator, status)
+}
+
+fn validate_transfer(
+    schema: BundledSchema,
+    inputs: &[u64],
+    outputs: &[u64],
+) -> (OpValidator, Status) {
+    let supply = u64::try_from(sum(inputs)).expect("inputs must not overflow");
+    let (genesis, blindings) = fungible_genesis(schema, inputs, supply);
+    let mut transition = match schema {
+        BundledSchema::AssetSwapOffer => take(&genesis),
+        _ => transfer(&genesis, OS_ASSET),
+    };
+    let tag = *genesis
+        .asset_tags
+        .get(&OS_ASSET)
+        .expect("fungible asset tag");
+    let assigns = outputs
+        .iter()
+        .zip(balanced(outputs.len(), blindings))
+        .enumerate()
+        .map(|(no, (amount, blinding))| {
+            let seal = GraphSeal::with_blinded_vout(Method::OpretFirst, no as u32, no as u64);
+            let state = RevealedValue::with_blinding(*amount, blinding, tag);
+            Assign::revealed(XChain::Bitcoin(seal), state)
+        });
+    transition
+        .assignments
+        .insert(OS_ASSET, TypedAssigns::Fungible(Confined::from_iter_checked(assigns)))
+        .unwrap();
+    tag_outputs(schema, &mut transition);
+
+    let mut validator = Fixture::get(schema).validator(genesis);
+    let opid = validator.add_transition(transition);
+    let status = validator.validate(opid);
+    (validator, status)
+}
+
+/// Checks that validation by the validator succeeded if `valid` is set, or
+/// failed with the given error code otherwise.
+fn check(
+    schema: BundledSchema,
+    (validator, status): (OpValidator, Status),
+    valid: bool,
+    errno: u8,
+) -> Result<(), String> {
+    if valid {
+        return match status.failures.is_empty() {
+            true => Ok(()),
+            false => Err(format!("{schema}: valid operation rejected: {status}")),
+        };
+    }
+    let errnos = status
+        .failures
+        .iter()
+        .map(|failure| ScriptFailure::with(&validator, failure).and_then(|f| f.error_code()))
+        .collect::<Vec<_>>();
+    match errnos == vec![SchemaErrno::with(schema, errno)] {
+        true => Ok(()),
+        false => Err(format!("{schema}: invalid operation not rejected as expected: {status}")),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn genesis_supply((amounts, supply) in genesis_case()) {
+        let valid = sum(&amounts) == supply as u128;
+        for schema in SCHEMATA {
+            let outcome = validate_genesis(schema, &amounts, supply);
+            check(schema, outcome, valid, ERRNO_ISSUED_MISMATCH).map_err(TestCaseError::fail)?;
+        }
+    }
+
+    #[test]
+    fn transfer_conservation((inputs, outputs) in transfer_case()) {
+        let valid = sum(&inputs) == sum(&outputs);
+        for schema in SCHEMATA {
+            let outcome = validate_transfer(schema, &inputs, &outputs);
+            check(schema, outcome, valid, transfer_errno(schema)).map_err(TestCaseError::fail)?;
+        }
+    }
+}
+
+#[test]
+fn edge_cases() {
+    const MAX: u64 = u64::MAX;
+    let genesis: &[(&[u64], u64, bool)] = &[
+        (&[0], 0, true),
+        (&[MAX], MAX, true),
+        (&[MAX, 0, 0], MAX, true),
+        (&[MAX, 1], 0, false),
+        (&[MAX, 1], MAX, false),
+        (&[MAX - 1, 1], MAX, true),
+        (&[0], 1, false),
+    ];
+    let transfers: &[(&[u64], &[u64], bool)] = &[
+        (&[0], &[0, 0], true),
+        (&[MAX], &[MAX], true),
+        (&[MAX], &[MAX - 1, 1], true),
+        (&[MAX], &[MAX, 0, 0], true),
+        (&[MAX], &[MAX, 1], false),
+        (&[MAX], &[MAX, MAX], false),
+        (&[MAX - 1, 1], &[MAX - 1], false),
+        (&[0], &[1], false),
+    ];
+    for schema in SCHEMATA {
+        for (amounts, supply, valid) in genesis {
+            let outcome = validate_genesis(schema, amounts, *supply);
+            check(schema, outcome, *valid, ERRNO_ISSUED_MISMATCH).unwrap();
+        }
+        for (inputs, outputs, valid) in transfers {
+            let outcome = validate_transfer(schema, inputs, outputs);
+            check(schema, outcome, *valid, transfer_errno(schema)).unwrap();
+        }
+    }
+}
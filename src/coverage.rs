@@ -0,0 +1,280 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Coverage of validation libraries by the executed validation scripts.
+//!
+//! The coverage is recorded from the execution traces of the scripts, and the
+//! gaps are reported with the source of the instructions for the libraries of
+//! the bundled schemata, or with the instruction disassembly otherwise.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Formatter};
+
+use aluvm::isa::{ControlFlowOp, Instr};
+use aluvm::library::{Lib, LibId, LibSite};
+
+use crate::asm::AsmSource;
+use crate::lint::decode;
+use crate::{BundledSchema, ExecTrace, StepOutcome};
+
+/// Coverage gap of a library instruction.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Display)]
+pub enum Gap {
+    /// Instruction was never executed.
+    #[display("not executed")]
+    Uncovered,
+
+    /// `test` instruction never failed the validation. This is expected for
+    /// `test` following `pcvs` or `pcas`, since these instructions stop the
+    /// execution on failure by themselves.
+    #[display("never failed")]
+    NeverFailed,
+}
+
+/// Library instruction with a coverage gap.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CoverageGap {
+    /// Offset of the instruction within the library code.
+    pub pos: u16,
+    /// Source of the instruction as `<file>:<line>: <instruction>`, if the
+    /// library source is known, or the instruction disassembly otherwise.
+    pub source: String,
+    pub gap: Gap,
+}
+
+/// Coverage report for a single library.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LibCoverage {
+    pub lib: LibId,
+    /// Total number of the library instructions.
+    pub total: usize,
+    pub gaps: Vec<CoverageGap>,
+}
+
+impl LibCoverage {
+    /// Number of the library instructions which were executed.
+    pub fn covered(&self) -> usize {
+        self.total
+            - self
+                .gaps
+                .iter()
+                .filter(|gap| gap.gap == Gap::Uncovered)
+                .count()
+    }
+
+    /// Detects whether all library instructions were executed.
+    pub fn is_complete(&self) -> bool { self.covered() == self.total }
+}
+
+impl Display for LibCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}/{} instructions covered", self.lib, self.covered(), self.total)?;
+        for gap in &self.gaps {
+            writeln!(f, "  x{:04X} {:<64} {}", gap.pos, gap.source, gap.gap)?;
+        }
+        Ok(())
+    }
+}
+
+/// Instructions of validation libraries executed by the recorded scripts and
+/// the ones at which the execution has failed.
+#[derive(Clone, Debug)]
+pub struct ScriptCoverage {
+    libs: BTreeMap<LibId, Lib>,
+    executed: BTreeSet<LibSite>,
+    failed: BTreeSet<LibSite>,
+}
+
+impl ScriptCoverage {
+    /// Constructs coverage of the provided libraries.
+    pub fn new(libs: impl IntoIterator<Item = Lib>) -> Self {
+        ScriptCoverage {
+            libs: libs.into_iter().map(|lib| (lib.id(), lib)).collect(),
+            executed: none!(),
+            failed: none!(),
+        }
+    }
+
+    /// Constructs coverage of the libraries used by the bundled schemata.
+    pub fn bundled() -> Self {
+        Self::new(
+            BundledSchema::ALL
+                .into_iter()
+                .flat_map(|schema| schema.scripts().release().into_values()),
+        )
+    }
+
+    /// Records instructions executed by a validation script. Instructions of
+    /// the libraries which are not covered are ignored.
+    pub fn record(&mut self, trace: &ExecTrace) {
+        for step in &trace.steps {
+            if !self.libs.contains_key(&step.lib) {
+                continue;
+            }
+            self.executed.insert(step.site());
+            if matches!(step.outcome, StepOutcome::Halt | StepOutcome::Stop(false)) {
+                self.failed.insert(step.site());
+            }
+        }
+    }
+
+    /// Reports coverage of each of the libraries.
+    pub fn report(&self) -> Vec<LibCoverage> {
+        let sources = bundled_sources();
+        self.libs
+            .iter()
+            .map(|(id, lib)| {
+                let sources = sources
+                    .get(id)
+                    .map(|source| source_lines(source, lib))
+                    .unwrap_or_default();
+                let code = decode(lib);
+                let total = code.len();
+                let gaps = code
+                    .into_iter()
+                    .filter_map(|(pos, instr)| {
+                        let site = LibSite::with(pos, *id);
+                        let is_test =
+                            matches!(instr, Some(Instr::ControlFlow(ControlFlowOp::Test)));
+                        let gap = if !self.executed.contains(&site) {
+                            Gap::Uncovered
+                        } else if is_test && !self.failed.contains(&site) {
+                            Gap::NeverFailed
+                        } else {
+                            return None;
+                        };
+                        let source = sources.get(&pos).cloned().unwrap_or_else(|| {
+                            instr.map_or(s!("<invalid>"), |instr| instr.to_string())
+                        });
+                        Some(CoverageGap { pos, source, gap })
+                    })
+                    .collect();
+                LibCoverage {
+                    lib: *id,
+                    total,
+                    gaps,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for ScriptCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for coverage in self.report() {
+            Display::fmt(&coverage, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assembler sources of the libraries of the bundled schemata.
+fn bundled_sources() -> BTreeMap<LibId, AsmSource> {
+    let mut asm = vec![];
+    #[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
+    asm.push(crate::nia::nia_asm());
+    #[cfg(feature = "uda")]
+    asm.push(crate::uda::uda_asm());
+    #[cfg(feature = "lfa")]
+    asm.push(crate::lfa::lfa_asm());
+    #[cfg(feature = "aso")]
+    asm.push(crate::aso::aso_asm());
+    asm.into_iter()
+        .map(|asm| {
+            let lib = Lib::assemble(&asm.code).expect("bundled library");
+            (lib.id(), asm.source)
+        })
+        .collect()
+}
+
+/// Source of each library instruction by its offset.
+fn source_lines(source: &AsmSource, lib: &Lib) -> BTreeMap<u16, String> {
+    source
+        .instrs(lib)
+        .into_iter()
+        .map(|(pos, text, line)| {
+            let line = line.map(|line| line.to_string()).unwrap_or(s!("?"));
+            (pos, format!("{}:{line}: {text}", source.file))
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "nia"))]
+mod test {
+    use rgbstd::validation::Status;
+
+    use super::*;
+    use crate::nia::{nia_asm, nia_lib, FN_NIA_GENESIS_OFFSET};
+    use crate::TraceStep;
+
+    #[test]
+    fn uncovered_source() {
+        let lib = nia_lib();
+        let source = nia_asm().source;
+        let lines = source.lines().expect("crate source");
+        let text = std::fs::read_to_string(source.file).unwrap();
+        let line = text.lines().nth(lines[9] - 1).unwrap();
+        assert_eq!(line.trim(), "pcas    OS_ASSET;");
+
+        let report = ScriptCoverage::new([lib]).to_string();
+        let gap = format!("x001C src/nia.rs:{}: pcas OS_ASSET", lines[9]);
+        assert!(report.contains(&gap), "{report}");
+        assert!(report.contains("0/12 instructions covered"));
+    }
+
+    #[test]
+    fn record() {
+        let lib = nia_lib();
+        let id = lib.id();
+        let step = |pos, outcome| TraceStep {
+            lib: id,
+            pos,
+            instr: s!(""),
+            src: vec![],
+            dst: vec![],
+            registers: vec![],
+            st0: true,
+            outcome,
+        };
+        let trace = ExecTrace {
+            steps: vec![
+                step(FN_NIA_GENESIS_OFFSET, StepOutcome::Next),
+                step(0x1C, StepOutcome::Stop(false)),
+            ],
+            status: Status::new(),
+        };
+        let mut coverage = ScriptCoverage::new([lib]);
+        coverage.record(&trace);
+        let report = coverage.report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].covered(), 2);
+        assert!(report[0].gaps.iter().all(|gap| gap.gap == Gap::Uncovered));
+
+        let trace = ExecTrace {
+            steps: vec![step(0x1F, StepOutcome::Next)],
+            status: Status::new(),
+        };
+        coverage.record(&trace);
+        let report = coverage.report();
+        let gap = report[0].gaps.iter().find(|gap| gap.pos == 0x1F).unwrap();
+        assert_eq!(gap.gap, Gap::NeverFailed);
+    }
+}
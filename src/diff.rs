@@ -0,0 +1,918 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison of two versions of a schema, together with their validation
+//! scripts and interface implementations, classifying each change as breaking
+//! or compatible for the contracts already issued under the old version.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use aluvm::library::{Lib, LibId, LibSite};
+use amplify::Wrapper;
+use rgbstd::containers::{Kit, LoadError, UniversalFile};
+use rgbstd::interface::{IfaceId, IfaceImpl};
+use rgbstd::schema::{GlobalStateSchema, OpSchema, OwnedStateSchema};
+use rgbstd::validation::Scripts;
+use rgbstd::{AssignmentType, GlobalStateType, MetaType, Occurrences, Schema, ValencyType};
+use strict_types::{SemId, TypeName};
+
+use crate::lint::decode;
+use crate::{BundledSchema, ValidatorSite};
+
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum DiffError {
+    #[from]
+    #[from(std::io::Error)]
+    #[display(inner)]
+    Load(LoadError),
+
+    /// the file contains a contract or a transfer and not a kit.
+    NotKit,
+
+    /// the kit contains {0} schemata, while exactly one is required for the
+    /// comparison.
+    SchemaCount(usize),
+}
+
+/// Schema together with the validation scripts and interface implementations
+/// it is distributed with.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SchemaRelease {
+    pub schema: Schema,
+    pub iimpls: Vec<IfaceImpl>,
+    pub scripts: Scripts,
+}
+
+impl SchemaRelease {
+    /// Constructs release from a bare schema, without scripts and interface
+    /// implementations. Such release should be compared only with other bare
+    /// schemata, otherwise all libraries and implementations will be reported
+    /// as added or removed.
+    pub fn new(schema: Schema) -> Self {
+        SchemaRelease {
+            schema,
+            iimpls: vec![],
+            scripts: none!(),
+        }
+    }
+
+    pub fn bundled(schema: BundledSchema) -> Self {
+        SchemaRelease {
+            schema: schema.schema(),
+            iimpls: vec![schema.issue_impl()],
+            scripts: schema.scripts(),
+        }
+    }
+
+    /// Extracts release from a kit, which must contain a single schema.
+    pub fn with_kit(kit: Kit) -> Result<Self, DiffError> {
+        if kit.schemata.len() != 1 {
+            return Err(DiffError::SchemaCount(kit.schemata.len()));
+        }
+        let schema = kit.schemata.first().expect("single schema").clone();
+        let schema_id = schema.schema_id();
+        let iimpls = kit
+            .iimpls
+            .into_iter()
+            .filter(|iimpl| iimpl.schema_id == schema_id)
+            .collect();
+        let scripts =
+            Scripts::from_iter_checked(kit.scripts.into_iter().map(|lib| (lib.id(), lib)));
+        Ok(SchemaRelease {
+            schema,
+            iimpls,
+            scripts,
+        })
+    }
+
+    /// Loads release from a binary or an armored kit file.
+    pub fn load_kit(path: impl AsRef<Path>) -> Result<Self, DiffError> {
+        Self::load(&fs::read(path)?)
+    }
+
+    /// Loads release from a binary or an armored kit data.
+    pub fn load(data: &[u8]) -> Result<Self, DiffError> {
+        let kit = match data.starts_with(b"-----BEGIN") {
+            true => Kit::from_str(&String::from_utf8_lossy(data)).map_err(LoadError::from)?,
+            false => match UniversalFile::load(data)? {
+                UniversalFile::Kit(kit) => kit,
+                UniversalFile::Contract(_) | UniversalFile::Transfer(_) => {
+                    return Err(DiffError::NotKit)
+                }
+            },
+        };
+        Self::with_kit(kit)
+    }
+}
+
+/// Range of allowed number of occurrences of some state or metadata in an
+/// operation.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Bounds {
+    pub min: u16,
+    pub max: u16,
+}
+
+impl Bounds {
+    /// Metadata listed in operation schema must be present exactly once.
+    pub const ONCE: Bounds = Bounds { min: 1, max: 1 };
+
+    /// Valencies and redeemed valencies listed in operation schema are
+    /// optional.
+    pub const OPTIONAL: Bounds = Bounds { min: 0, max: 1 };
+
+    pub fn with(occurrences: &Occurrences) -> Self {
+        Bounds {
+            min: occurrences.min_value(),
+            max: occurrences.max_value(),
+        }
+    }
+
+    /// Checks whether all numbers of occurrences allowed by `other` are
+    /// allowed by `self` as well.
+    pub fn contains(self, other: Bounds) -> bool { self.min <= other.min && self.max >= other.max }
+}
+
+impl Display for Bounds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.min, self.max) {
+            (min, u16::MAX) => write!(f, "{min}.."),
+            (min, max) if min == max => write!(f, "{min}"),
+            (min, max) => write!(f, "{min}..={max}"),
+        }
+    }
+}
+
+/// Item of an operation schema.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+pub enum OpField {
+    #[display("metadata {0}")]
+    Metadata(MetaType),
+
+    #[display("global state {0}")]
+    Global(GlobalStateType),
+
+    #[display("input {0}")]
+    Input(AssignmentType),
+
+    #[display("redeemed valency {0}")]
+    Redeemed(ValencyType),
+
+    #[display("assignment {0}")]
+    Assignment(AssignmentType),
+
+    #[display("valency {0}")]
+    Valency(ValencyType),
+}
+
+/// Kind of the interface implementation field.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+pub enum IimplField {
+    #[display("metadata")]
+    Metadata,
+
+    #[display("global state")]
+    Global,
+
+    #[display("assignment")]
+    Assignment,
+
+    #[display("valency")]
+    Valency,
+
+    #[display("transition")]
+    Transition,
+
+    #[display("extension")]
+    Extension,
+
+    #[display("error")]
+    Error,
+}
+
+/// Change between two schema releases found by [`diff`].
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(doc_comments)]
+pub enum SchemaChange {
+    /// schema name changed from {0} to {1}.
+    Renamed(TypeName, TypeName),
+
+    /// metadata type {0} added.
+    MetaTypeAdded(MetaType),
+
+    /// metadata type {0} removed.
+    MetaTypeRemoved(MetaType),
+
+    /// metadata type {0} changed its semantic type from {1} to {2}.
+    MetaTypeChanged(MetaType, SemId, SemId),
+
+    /// global state type {0} added.
+    GlobalTypeAdded(GlobalStateType),
+
+    /// global state type {0} removed.
+    GlobalTypeRemoved(GlobalStateType),
+
+    /// global state type {0} changed its semantic type from {1} to {2}.
+    GlobalTypeChanged(GlobalStateType, SemId, SemId),
+
+    /// global state type {0} changed maximal number of items from {1} to {2}.
+    GlobalLimitChanged(GlobalStateType, u32, u32),
+
+    /// owned state type {0} added.
+    OwnedTypeAdded(AssignmentType),
+
+    /// owned state type {0} removed.
+    OwnedTypeRemoved(AssignmentType),
+
+    /// owned state type {0} changed from {1:?} to {2:?}.
+    OwnedTypeChanged(AssignmentType, OwnedStateSchema, OwnedStateSchema),
+
+    /// valency type {0} added.
+    ValencyTypeAdded(ValencyType),
+
+    /// valency type {0} removed.
+    ValencyTypeRemoved(ValencyType),
+
+    /// {0} added.
+    OperationAdded(ValidatorSite),
+
+    /// {0} removed.
+    OperationRemoved(ValidatorSite),
+
+    /// {0} now includes {1} (occurrences {2}).
+    FieldAdded(ValidatorSite, OpField, Bounds),
+
+    /// {0} no longer includes {1}.
+    FieldRemoved(ValidatorSite, OpField),
+
+    /// {0} changed occurrences of {1} from {2} to {3}.
+    OccurrencesChanged(ValidatorSite, OpField, Bounds, Bounds),
+
+    /// {0} validator {1} added.
+    ValidatorAdded(ValidatorSite, LibSite),
+
+    /// {0} validator {1} removed.
+    ValidatorRemoved(ValidatorSite, LibSite),
+
+    /// {0} validator changed from {1} to {2}.
+    ValidatorChanged(ValidatorSite, LibSite, LibSite),
+
+    /// library {0} added to the scripts.
+    LibAdded(LibId),
+
+    /// library {0} removed from the scripts.
+    LibRemoved(LibId),
+
+    /// implementation of interface {0} added.
+    IimplAdded(IfaceId),
+
+    /// implementation of interface {0} removed.
+    IimplRemoved(IfaceId),
+
+    /// {1} {2} is mapped to '{3}' field of interface {0}.
+    IimplFieldAdded(IfaceId, IimplField, u16, String),
+
+    /// {1} {2} is no longer mapped to '{3}' field of interface {0}.
+    IimplFieldRemoved(IfaceId, IimplField, u16, String),
+
+    /// {1} {2} mapping to interface {0} renamed from '{3}' to '{4}'.
+    IimplFieldRenamed(IfaceId, IimplField, u16, String, String),
+}
+
+impl SchemaChange {
+    /// Detects whether the change makes the new schema version incompatible
+    /// with contracts issued under the old one: i.e. whether some operations
+    /// valid under the old version may become invalid, or valid operations
+    /// change their validation rules or interface-level meaning.
+    ///
+    /// Validator changes are always considered breaking, since a script can't
+    /// be proven to accept the same set of operations.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            SchemaChange::Renamed(..)
+            | SchemaChange::MetaTypeAdded(_)
+            | SchemaChange::GlobalTypeAdded(_)
+            | SchemaChange::OwnedTypeAdded(_)
+            | SchemaChange::ValencyTypeAdded(_)
+            | SchemaChange::OperationAdded(_)
+            | SchemaChange::LibAdded(_)
+            | SchemaChange::LibRemoved(_)
+            | SchemaChange::IimplAdded(_)
+            | SchemaChange::IimplFieldAdded(..) => false,
+
+            SchemaChange::MetaTypeRemoved(_)
+            | SchemaChange::MetaTypeChanged(..)
+            | SchemaChange::GlobalTypeRemoved(_)
+            | SchemaChange::GlobalTypeChanged(..)
+            | SchemaChange::OwnedTypeRemoved(_)
+            | SchemaChange::OwnedTypeChanged(..)
+            | SchemaChange::ValencyTypeRemoved(_)
+            | SchemaChange::OperationRemoved(_)
+            | SchemaChange::FieldRemoved(..)
+            | SchemaChange::ValidatorAdded(..)
+            | SchemaChange::ValidatorRemoved(..)
+            | SchemaChange::ValidatorChanged(..)
+            | SchemaChange::IimplRemoved(_)
+            | SchemaChange::IimplFieldRemoved(..)
+            | SchemaChange::IimplFieldRenamed(..) => true,
+
+            SchemaChange::GlobalLimitChanged(_, old, new) => new < old,
+            SchemaChange::FieldAdded(_, _, bounds) => bounds.min > 0,
+            SchemaChange::OccurrencesChanged(_, _, old, new) => !new.contains(*old),
+        }
+    }
+}
+
+/// Line of a script disassembly diff.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+pub enum DiffLine {
+    #[display("  {0}")]
+    Same(String),
+
+    #[display("- {0}")]
+    Removed(String),
+
+    #[display("+ {0}")]
+    Added(String),
+}
+
+/// Disassembly diff of a validator library replaced in some operations.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ScriptDiff {
+    pub sites: Vec<ValidatorSite>,
+    pub old: LibId,
+    pub new: LibId,
+    pub lines: Vec<DiffLine>,
+}
+
+impl Display for ScriptDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let sites = self
+            .sites
+            .iter()
+            .map(ValidatorSite::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "--- {}", self.old)?;
+        writeln!(f, "+++ {}", self.new)?;
+        writeln!(f, "used by {sites}")?;
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of comparing two schema releases with [`diff`].
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct SchemaDiff {
+    pub changes: Vec<SchemaChange>,
+    pub scripts: Vec<ScriptDiff>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    pub fn is_breaking(&self) -> bool { self.changes.iter().any(SchemaChange::is_breaking) }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+}
+
+impl Display for SchemaDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for change in &self.changes {
+            let class = if change.is_breaking() { "breaking" } else { "compatible" };
+            writeln!(f, "{class:<10}  {change}")?;
+        }
+        let breaking = self.breaking().count();
+        writeln!(f, "\n{} changes, {breaking} of them breaking", self.changes.len())?;
+        for script in &self.scripts {
+            writeln!(f, "\n{script}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two releases of a schema, reporting changes in state types,
+/// operation layouts, validators, scripts and interface implementations.
+pub fn diff(old: &SchemaRelease, new: &SchemaRelease) -> SchemaDiff {
+    let mut diff = SchemaDiff::default();
+    let changes = &mut diff.changes;
+    let (old_schema, new_schema) = (&old.schema, &new.schema);
+
+    if old_schema.name != new_schema.name {
+        changes.push(SchemaChange::Renamed(old_schema.name.clone(), new_schema.name.clone()));
+    }
+
+    for (ty, pair) in match_keys(&*old_schema.meta_types, &*new_schema.meta_types) {
+        match pair {
+            (None, Some(_)) => changes.push(SchemaChange::MetaTypeAdded(*ty)),
+            (Some(_), None) => changes.push(SchemaChange::MetaTypeRemoved(*ty)),
+            (Some(old), Some(new)) if old != new => {
+                changes.push(SchemaChange::MetaTypeChanged(*ty, *old, *new))
+            }
+            _ => {}
+        }
+    }
+
+    for (ty, pair) in match_keys(&*old_schema.global_types, &*new_schema.global_types) {
+        match pair {
+            (None, Some(_)) => changes.push(SchemaChange::GlobalTypeAdded(*ty)),
+            (Some(_), None) => changes.push(SchemaChange::GlobalTypeRemoved(*ty)),
+            (Some(old), Some(new)) => changes.extend(global_changes(*ty, old, new)),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    for (ty, pair) in match_keys(&*old_schema.owned_types, &*new_schema.owned_types) {
+        match pair {
+            (None, Some(_)) => changes.push(SchemaChange::OwnedTypeAdded(*ty)),
+            (Some(_), None) => changes.push(SchemaChange::OwnedTypeRemoved(*ty)),
+            (Some(old), Some(new)) if old != new => {
+                changes.push(SchemaChange::OwnedTypeChanged(*ty, *old, *new))
+            }
+            _ => {}
+        }
+    }
+
+    let old_valencies = old_schema.valency_types.iter().map(|ty| (ty, ()));
+    let new_valencies = new_schema.valency_types.iter().map(|ty| (ty, ()));
+    for (ty, pair) in match_keys(old_valencies, new_valencies) {
+        match pair {
+            (None, Some(_)) => changes.push(SchemaChange::ValencyTypeAdded(*ty)),
+            (Some(_), None) => changes.push(SchemaChange::ValencyTypeRemoved(*ty)),
+            _ => {}
+        }
+    }
+
+    let mut replaced = BTreeMap::<(LibId, LibId), Vec<ValidatorSite>>::new();
+    for (site, pair) in match_keys(operations(old_schema), operations(new_schema)) {
+        let ((old_fields, old_validator), (new_fields, new_validator)) = match pair {
+            (None, Some(_)) => {
+                changes.push(SchemaChange::OperationAdded(site));
+                continue;
+            }
+            (Some(_), None) => {
+                changes.push(SchemaChange::OperationRemoved(site));
+                continue;
+            }
+            (Some(old), Some(new)) => (old, new),
+            (None, None) => unreachable!(),
+        };
+        for (field, pair) in match_keys(old_fields, new_fields) {
+            match pair {
+                (None, Some(bounds)) => changes.push(SchemaChange::FieldAdded(site, field, bounds)),
+                (Some(_), None) => changes.push(SchemaChange::FieldRemoved(site, field)),
+                (Some(old), Some(new)) if old != new => {
+                    changes.push(SchemaChange::OccurrencesChanged(site, field, old, new))
+                }
+                _ => {}
+            }
+        }
+        match (old_validator, new_validator) {
+            (None, Some(new)) => changes.push(SchemaChange::ValidatorAdded(site, new)),
+            (Some(old), None) => changes.push(SchemaChange::ValidatorRemoved(site, old)),
+            (Some(old), Some(new)) if old != new => {
+                changes.push(SchemaChange::ValidatorChanged(site, old, new));
+                if old.lib != new.lib {
+                    replaced.entry((old.lib, new.lib)).or_default().push(site);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (id, pair) in
+        match_keys(old.scripts.keys().map(|id| (id, ())), new.scripts.keys().map(|id| (id, ())))
+    {
+        match pair {
+            (None, Some(_)) => changes.push(SchemaChange::LibAdded(*id)),
+            (Some(_), None) => changes.push(SchemaChange::LibRemoved(*id)),
+            _ => {}
+        }
+    }
+
+    let old_iimpls = old.iimpls.iter().map(|iimpl| (iimpl.iface_id, iimpl));
+    let new_iimpls = new.iimpls.iter().map(|iimpl| (iimpl.iface_id, iimpl));
+    for (iface_id, pair) in match_keys(old_iimpls, new_iimpls) {
+        match pair {
+            (None, Some(_)) => changes.push(SchemaChange::IimplAdded(iface_id)),
+            (Some(_), None) => changes.push(SchemaChange::IimplRemoved(iface_id)),
+            (Some(old), Some(new)) => changes.extend(iimpl_changes(iface_id, old, new)),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    for ((old_lib, new_lib), sites) in replaced {
+        let (Some(old_code), Some(new_code)) =
+            (old.scripts.get(&old_lib), new.scripts.get(&new_lib))
+        else {
+            continue;
+        };
+        diff.scripts.push(ScriptDiff {
+            sites,
+            old: old_lib,
+            new: new_lib,
+            lines: diff_lines(&disassemble(old_code), &disassemble(new_code)),
+        });
+    }
+
+    diff
+}
+
+/// Matches entries of two collections by their keys.
+fn match_keys<K: Ord, V>(
+    old: impl IntoIterator<Item = (K, V)>,
+    new: impl IntoIterator<Item = (K, V)>,
+) -> BTreeMap<K, (Option<V>, Option<V>)> {
+    let mut pairs = BTreeMap::<K, (Option<V>, Option<V>)>::new();
+    for (key, value) in old {
+        pairs.entry(key).or_default().0 = Some(value);
+    }
+    for (key, value) in new {
+        pairs.entry(key).or_default().1 = Some(value);
+    }
+    pairs
+}
+
+fn global_changes(
+    ty: GlobalStateType,
+    old: &GlobalStateSchema,
+    new: &GlobalStateSchema,
+) -> Vec<SchemaChange> {
+    let mut changes = vec![];
+    if old.sem_id != new.sem_id {
+        changes.push(SchemaChange::GlobalTypeChanged(ty, old.sem_id, new.sem_id));
+    }
+    if old.max_items != new.max_items {
+        let (old, new) = (old.max_items.into_u32(), new.max_items.into_u32());
+        changes.push(SchemaChange::GlobalLimitChanged(ty, old, new));
+    }
+    changes
+}
+
+/// Layout of an operation schema: its items with the allowed number of
+/// occurrences and the validator entry point.
+type OpLayout = (BTreeMap<OpField, Bounds>, Option<LibSite>);
+
+fn operations(schema: &Schema) -> BTreeMap<ValidatorSite, OpLayout> {
+    let mut ops =
+        bmap! { ValidatorSite::Genesis => layout(&schema.genesis, schema.genesis.validator) };
+    for (ty, ext) in &schema.extensions {
+        ops.insert(ValidatorSite::Extension(*ty), layout(ext, ext.validator));
+    }
+    for (ty, transition) in &schema.transitions {
+        ops.insert(ValidatorSite::Transition(*ty), layout(transition, transition.validator));
+    }
+    ops
+}
+
+fn layout(op: &impl OpSchema, validator: Option<LibSite>) -> OpLayout {
+    let metadata = op
+        .metadata()
+        .iter()
+        .map(|ty| (OpField::Metadata(*ty), Bounds::ONCE));
+    let globals = op
+        .globals()
+        .iter()
+        .map(|(ty, occ)| (OpField::Global(*ty), Bounds::with(occ)));
+    let inputs = op
+        .inputs()
+        .into_iter()
+        .flatten()
+        .map(|(ty, occ)| (OpField::Input(*ty), Bounds::with(occ)));
+    let redeems = op
+        .redeems()
+        .into_iter()
+        .flatten()
+        .map(|ty| (OpField::Redeemed(*ty), Bounds::OPTIONAL));
+    let assignments = op
+        .assignments()
+        .iter()
+        .map(|(ty, occ)| (OpField::Assignment(*ty), Bounds::with(occ)));
+    let valencies = op
+        .valencies()
+        .iter()
+        .map(|ty| (OpField::Valency(*ty), Bounds::OPTIONAL));
+    let fields = metadata
+        .chain(globals)
+        .chain(inputs)
+        .chain(redeems)
+        .chain(assignments)
+        .chain(valencies)
+        .collect();
+    (fields, validator)
+}
+
+fn iimpl_fields(iimpl: &IfaceImpl) -> BTreeMap<(IimplField, u16), String> {
+    let named = |kind: IimplField, fields: Vec<(u16, String)>| {
+        fields.into_iter().map(move |(id, name)| ((kind, id), name))
+    };
+    let metadata = iimpl
+        .metadata
+        .iter()
+        .map(|f| (f.id.to_inner(), f.name.to_string()));
+    let globals = iimpl
+        .global_state
+        .iter()
+        .map(|f| (f.id.to_inner(), f.name.to_string()));
+    let assignments = iimpl
+        .assignments
+        .iter()
+        .map(|f| (f.id.to_inner(), f.name.to_string()));
+    let valencies = iimpl
+        .valencies
+        .iter()
+        .map(|f| (f.id.to_inner(), f.name.to_string()));
+    let transitions = iimpl
+        .transitions
+        .iter()
+        .map(|f| (f.id.to_inner(), f.name.to_string()));
+    let extensions = iimpl
+        .extensions
+        .iter()
+        .map(|f| (f.id.to_inner(), f.name.to_string()));
+    let errors = iimpl
+        .errors
+        .iter()
+        .map(|v| (v.id as u16, v.name.to_string()));
+    named(IimplField::Metadata, metadata.collect())
+        .chain(named(IimplField::Global, globals.collect()))
+        .chain(named(IimplField::Assignment, assignments.collect()))
+        .chain(named(IimplField::Valency, valencies.collect()))
+        .chain(named(IimplField::Transition, transitions.collect()))
+        .chain(named(IimplField::Extension, extensions.collect()))
+        .chain(named(IimplField::Error, errors.collect()))
+        .collect()
+}
+
+fn iimpl_changes(iface_id: IfaceId, old: &IfaceImpl, new: &IfaceImpl) -> Vec<SchemaChange> {
+    match_keys(iimpl_fields(old), iimpl_fields(new))
+        .into_iter()
+        .filter_map(|((kind, id), pair)| match pair {
+            (None, Some(name)) => Some(SchemaChange::IimplFieldAdded(iface_id, kind, id, name)),
+            (Some(name), None) => Some(SchemaChange::IimplFieldRemoved(iface_id, kind, id, name)),
+            (Some(old), Some(new)) if old != new => {
+                Some(SchemaChange::IimplFieldRenamed(iface_id, kind, id, old, new))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Disassembles library code without instruction offsets, such that
+/// insertions into the code don't change the rest of the listing.
+fn disassemble(lib: &Lib) -> Vec<String> {
+    decode(lib)
+        .into_iter()
+        .map(|(_, instr)| match instr {
+            Some(instr) => instr.to_string(),
+            None => s!("<undecodable bytecode>"),
+        })
+        .collect()
+}
+
+/// Computes line diff using the longest common subsequence.
+fn diff_lines(old: &[String], new: &[String]) -> Vec<DiffLine> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for (i, old_line) in old.iter().enumerate().rev() {
+        for (j, new_line) in new.iter().enumerate().rev() {
+            lcs[i][j] = match old_line == new_line {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = vec![];
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].clone()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].clone()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].clone()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().cloned().map(DiffLine::Removed));
+    lines.extend(new[j..].iter().cloned().map(DiffLine::Added));
+    lines
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use amplify::confinement::Confined;
+    use rgbstd::interface::NamedField;
+    use rgbstd::Occurrences;
+
+    use super::*;
+    use crate::uda::uda_lib;
+    use crate::{GS_ISSUED_SUPPLY, GS_TERMS, OS_ASSET, TS_TRANSFER};
+
+    #[test]
+    fn identical() {
+        for schema in BundledSchema::ALL {
+            let release = SchemaRelease::bundled(schema);
+            let diff = diff(&release, &release);
+            assert!(diff.is_empty(), "{schema}: {diff}");
+            assert!(!diff.is_breaking());
+        }
+        let kit = SchemaRelease::load_kit("schemata/NonInflatableAssets.rgb").unwrap();
+        let bundled = SchemaRelease::bundled(BundledSchema::NonInflatableAsset);
+        assert_eq!(diff(&kit, &bundled), SchemaDiff::default());
+    }
+
+    #[test]
+    fn occurrences() {
+        let old = SchemaRelease::bundled(BundledSchema::NonInflatableAsset);
+        let mut new = old.clone();
+        let genesis = &mut new.schema.genesis;
+        genesis
+            .globals
+            .insert(GS_ISSUED_SUPPLY, Occurrences::NoneOrOnce)
+            .unwrap();
+        genesis.globals.remove(&GS_TERMS).unwrap();
+        let transfer = new.schema.transitions.get_mut(&TS_TRANSFER).unwrap();
+        transfer
+            .assignments
+            .insert(OS_ASSET, Occurrences::OnceOrUpTo(2))
+            .unwrap();
+        let global = GlobalStateType::with(9999);
+        new.schema
+            .global_types
+            .insert(global, GlobalStateSchema::once(SemId::unit()))
+            .unwrap();
+        transfer
+            .globals
+            .insert(global, Occurrences::NoneOrMore)
+            .unwrap();
+
+        let diff = diff(&old, &new);
+        let site = ValidatorSite::Transition(TS_TRANSFER);
+        let expected = [
+            (SchemaChange::GlobalTypeAdded(global), false),
+            (
+                SchemaChange::OccurrencesChanged(
+                    ValidatorSite::Genesis,
+                    OpField::Global(GS_ISSUED_SUPPLY),
+                    Bounds::ONCE,
+                    Bounds::OPTIONAL,
+                ),
+                false,
+            ),
+            (SchemaChange::FieldRemoved(ValidatorSite::Genesis, OpField::Global(GS_TERMS)), true),
+            (
+                SchemaChange::FieldAdded(site, OpField::Global(global), Bounds {
+                    min: 0,
+                    max: u16::MAX,
+                }),
+                false,
+            ),
+            (
+                SchemaChange::OccurrencesChanged(
+                    site,
+                    OpField::Assignment(OS_ASSET),
+                    Bounds {
+                        min: 1,
+                        max: u16::MAX,
+                    },
+                    Bounds { min: 1, max: 2 },
+                ),
+                true,
+            ),
+        ];
+        assert_eq!(diff.changes.len(), expected.len(), "{diff}");
+        for (change, breaking) in expected {
+            assert!(diff.changes.contains(&change), "{change} is not reported:\n{diff}");
+            assert_eq!(change.is_breaking(), breaking, "{change}");
+        }
+        assert!(diff.is_breaking());
+        assert!(diff.scripts.is_empty());
+    }
+
+    #[test]
+    fn validator() {
+        let old = SchemaRelease::bundled(BundledSchema::NonInflatableAsset);
+        let mut new = old.clone();
+        let lib = uda_lib();
+        let lib_id = lib.id();
+        let transfer = new.schema.transitions.get_mut(&TS_TRANSFER).unwrap();
+        let old_site = transfer.validator.unwrap();
+        let new_site = LibSite::with(0, lib_id);
+        transfer.validator = Some(new_site);
+        new.scripts = Confined::from_checked(bmap! { lib_id => lib });
+
+        let diff = diff(&old, &new);
+        let site = ValidatorSite::Transition(TS_TRANSFER);
+        assert_eq!(diff.changes, vec![
+            SchemaChange::ValidatorChanged(site, old_site, new_site),
+            SchemaChange::LibAdded(lib_id),
+            SchemaChange::LibRemoved(old_site.lib),
+        ]);
+        assert!(diff.is_breaking());
+        assert!(!SchemaChange::LibRemoved(old_site.lib).is_breaking());
+
+        let [script] = diff.scripts.as_slice() else {
+            panic!("single script diff expected:\n{diff}");
+        };
+        assert_eq!(script.sites, vec![site]);
+        assert_eq!((script.old, script.new), (old_site.lib, lib_id));
+        assert!(script
+            .lines
+            .iter()
+            .any(|line| matches!(line, DiffLine::Removed(_))));
+        assert!(script
+            .lines
+            .iter()
+            .any(|line| matches!(line, DiffLine::Added(_))));
+        assert!(script
+            .lines
+            .iter()
+            .any(|line| matches!(line, DiffLine::Same(_))));
+    }
+
+    #[test]
+    fn iimpl() {
+        let old = SchemaRelease::bundled(BundledSchema::NonInflatableAsset);
+        let mut new = old.clone();
+        let iimpl = &mut new.iimpls[0];
+        let iface_id = iimpl.iface_id;
+        let fields = iimpl.global_state.iter().map(|field| match field.id {
+            GS_TERMS => NamedField::with(GS_TERMS, fname!("contractTerms")),
+            _ => field.clone(),
+        });
+        iimpl.global_state = Confined::from_iter_checked(fields);
+        let errors = iimpl.errors.iter().skip(1).cloned();
+        iimpl.errors = Confined::from_iter_checked(errors);
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.changes, vec![
+            SchemaChange::IimplFieldRenamed(
+                iface_id,
+                IimplField::Global,
+                GS_TERMS.to_inner(),
+                s!("terms"),
+                s!("contractTerms")
+            ),
+            SchemaChange::IimplFieldRemoved(
+                iface_id,
+                IimplField::Error,
+                old.iimpls[0].errors.first().unwrap().id as u16,
+                old.iimpls[0].errors.first().unwrap().name.to_string()
+            ),
+        ]);
+        assert!(diff.changes.iter().all(SchemaChange::is_breaking));
+    }
+
+    #[test]
+    fn lines() {
+        let lines = |s: &str| s.split(' ').map(str::to_owned).collect::<Vec<_>>();
+        let diff = diff_lines(&lines("a b c d"), &lines("a c e d"));
+        assert_eq!(diff, vec![
+            DiffLine::Same(s!("a")),
+            DiffLine::Removed(s!("b")),
+            DiffLine::Same(s!("c")),
+            DiffLine::Added(s!("e")),
+            DiffLine::Same(s!("d")),
+        ]);
+    }
+}
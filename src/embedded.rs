@@ -0,0 +1,87 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Kits of the bundled schemata from `schemata` directory, embedded into the
+//! library at compile time.
+//!
+//! The embedded kits are checked by the test suite to be the same as the kits
+//! constructed by [`BundledSchema::kit`], thus the `schemata` directory must be
+//! regenerated with `cargo run --features fs` after each change to the
+//! schemata.
+
+use rgbstd::containers::{FileContent, Kit, ValidKit};
+
+use crate::BundledSchema;
+
+impl BundledSchema {
+    /// Binary kit of the schema embedded into the library.
+    pub fn embedded_kit_data(self) -> &'static [u8] {
+        match self {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => {
+                include_bytes!("../schemata/NonInflatableAssets.rgb")
+            }
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => {
+                include_bytes!("../schemata/UniqueDigitalAsset.rgb")
+            }
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => {
+                include_bytes!("../schemata/CollectibleFungibleAsset.rgb")
+            }
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => {
+                include_bytes!("../schemata/LightningFungibleAsset.rgb")
+            }
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => include_bytes!("../schemata/AssetSwapOffer.rgb"),
+        }
+    }
+
+    /// Loads kit of the schema embedded into the library, such that it can be
+    /// imported into a stock without accessing `schemata` directory.
+    pub fn embedded_kit(self) -> ValidKit {
+        Kit::load(self.embedded_kit_data())
+            .expect("invalid embedded kit")
+            .validate()
+            .map_err(|(status, _)| status)
+            .expect("invalid embedded kit")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_constructed() {
+        for schema in BundledSchema::ALL {
+            let mut data = vec![];
+            schema.kit().save(&mut data).unwrap();
+            assert!(
+                data == schema.embedded_kit_data(),
+                "embedded kit of {schema} differs from the constructed one; regenerate `schemata` \
+                 directory with `cargo run --features fs`"
+            );
+            assert_eq!(*schema.embedded_kit(), schema.kit());
+        }
+    }
+}
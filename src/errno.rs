@@ -0,0 +1,532 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of error codes which AluVM validation scripts of the bundled
+//! schemata put into `a8[0]` register on failure.
+
+use rgbstd::validation::{ConsignmentApi, Failure, OpRef};
+use rgbstd::OpId;
+
+use crate::BundledSchema;
+#[cfg(feature = "lfa")]
+use crate::ERRNO_CHANNEL_OUTPUTS_MISMATCH;
+#[cfg(feature = "aso")]
+use crate::ERRNO_FILL_MISMATCH;
+#[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
+use crate::ERRNO_ISSUED_MISMATCH;
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use crate::ERRNO_NON_EQUAL_IN_OUT;
+#[cfg(feature = "uda")]
+use crate::ERRNO_NON_FRACTIONAL;
+
+/// Error codes returned by validation scripts, per schema.
+///
+/// The same numeric code may have different meaning in different schemata,
+/// thus each code is always bound to its schema.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[display(doc_comments)]
+pub enum SchemaErrno {
+    /// sum of NIA inputs is not equal to the sum of outputs.
+    #[cfg(feature = "nia")]
+    NiaNonEqualAmounts,
+
+    /// issued NIA supply doesn't match the sum of genesis allocations.
+    #[cfg(feature = "nia")]
+    NiaIssuedMismatch,
+
+    /// UDA transfer assigns token which is not known to the contract.
+    #[cfg(feature = "uda")]
+    UdaUnknownToken,
+
+    /// UDA token allocation is fractional, while the token must be owned as a
+    /// whole.
+    #[cfg(feature = "uda")]
+    UdaNonFractionalToken,
+
+    /// sum of CFA inputs is not equal to the sum of outputs.
+    #[cfg(feature = "cfa")]
+    CfaNonEqualAmounts,
+
+    /// issued CFA supply doesn't match the sum of genesis allocations.
+    #[cfg(feature = "cfa")]
+    CfaIssuedMismatch,
+
+    /// sum of LFA inputs is not equal to the sum of outputs.
+    #[cfg(feature = "lfa")]
+    LfaNonEqualAmounts,
+
+    /// issued LFA supply doesn't match the sum of genesis allocations.
+    #[cfg(feature = "lfa")]
+    LfaIssuedMismatch,
+
+    /// LFA channel transfer doesn't tag each of its asset outputs.
+    #[cfg(feature = "lfa")]
+    LfaChannelOutputsMismatch,
+
+    /// sum of ASO inputs is not equal to the sum of outputs.
+    #[cfg(feature = "aso")]
+    AsoNonEqualAmounts,
+
+    /// issued ASO supply doesn't match the sum of the offered lots.
+    #[cfg(feature = "aso")]
+    AsoIssuedMismatch,
+
+    /// ASO fill doesn't match the lots spent by the take or the take outputs.
+    #[cfg(feature = "aso")]
+    AsoFillMismatch,
+}
+
+impl SchemaErrno {
+    /// Error codes of each of the schemata enabled with cargo features.
+    pub const TABLES: &'static [(BundledSchema, &'static [SchemaErrno])] = &[
+        #[cfg(feature = "nia")]
+        (BundledSchema::NonInflatableAsset, &[
+            SchemaErrno::NiaNonEqualAmounts,
+            SchemaErrno::NiaIssuedMismatch,
+        ]),
+        #[cfg(feature = "uda")]
+        (BundledSchema::UniqueDigitalAsset, &[
+            SchemaErrno::UdaUnknownToken,
+            SchemaErrno::UdaNonFractionalToken,
+        ]),
+        #[cfg(feature = "cfa")]
+        (BundledSchema::CollectibleFungibleAsset, &[
+            SchemaErrno::CfaNonEqualAmounts,
+            SchemaErrno::CfaIssuedMismatch,
+        ]),
+        #[cfg(feature = "lfa")]
+        (BundledSchema::LightningFungibleAsset, &[
+            SchemaErrno::LfaNonEqualAmounts,
+            SchemaErrno::LfaIssuedMismatch,
+            SchemaErrno::LfaChannelOutputsMismatch,
+        ]),
+        #[cfg(feature = "aso")]
+        (BundledSchema::AssetSwapOffer, &[
+            SchemaErrno::AsoNonEqualAmounts,
+            SchemaErrno::AsoIssuedMismatch,
+            SchemaErrno::AsoFillMismatch,
+        ]),
+    ];
+
+    /// Number of error codes of the schemata enabled with cargo features.
+    pub const COUNT: usize = {
+        let mut count = 0;
+        let mut no = 0;
+        while no < Self::TABLES.len() {
+            count += Self::TABLES[no].1.len();
+            no += 1;
+        }
+        count
+    };
+
+    /// Error codes of all [`Self::TABLES`] in their order.
+    #[cfg(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    ))]
+    pub const ALL: [SchemaErrno; Self::COUNT] = {
+        let mut all = [Self::TABLES[0].1[0]; Self::COUNT];
+        let mut pos = 0;
+        let mut no = 0;
+        while no < Self::TABLES.len() {
+            let table = Self::TABLES[no].1;
+            let mut index = 0;
+            while index < table.len() {
+                all[pos] = table[index];
+                pos += 1;
+                index += 1;
+            }
+            no += 1;
+        }
+        all
+    };
+    #[cfg(not(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    )))]
+    pub const ALL: [SchemaErrno; 0] = [];
+
+    /// Schema defining the error code.
+    pub fn schema(self) -> BundledSchema {
+        match self {
+            #[cfg(feature = "nia")]
+            SchemaErrno::NiaNonEqualAmounts | SchemaErrno::NiaIssuedMismatch => {
+                BundledSchema::NonInflatableAsset
+            }
+            #[cfg(feature = "uda")]
+            SchemaErrno::UdaUnknownToken | SchemaErrno::UdaNonFractionalToken => {
+                BundledSchema::UniqueDigitalAsset
+            }
+            #[cfg(feature = "cfa")]
+            SchemaErrno::CfaNonEqualAmounts | SchemaErrno::CfaIssuedMismatch => {
+                BundledSchema::CollectibleFungibleAsset
+            }
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaNonEqualAmounts
+            | SchemaErrno::LfaIssuedMismatch
+            | SchemaErrno::LfaChannelOutputsMismatch => BundledSchema::LightningFungibleAsset,
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoNonEqualAmounts
+            | SchemaErrno::AsoIssuedMismatch
+            | SchemaErrno::AsoFillMismatch => BundledSchema::AssetSwapOffer,
+        }
+    }
+
+    /// Numeric code put by the validation script into `a8[0]` register.
+    pub fn errno(self) -> u8 {
+        match self {
+            #[cfg(feature = "nia")]
+            SchemaErrno::NiaNonEqualAmounts => ERRNO_NON_EQUAL_IN_OUT,
+            #[cfg(feature = "nia")]
+            SchemaErrno::NiaIssuedMismatch => ERRNO_ISSUED_MISMATCH,
+            #[cfg(feature = "uda")]
+            SchemaErrno::UdaUnknownToken => ERRNO_NON_EQUAL_IN_OUT,
+            #[cfg(feature = "uda")]
+            SchemaErrno::UdaNonFractionalToken => ERRNO_NON_FRACTIONAL,
+            #[cfg(feature = "cfa")]
+            SchemaErrno::CfaNonEqualAmounts => ERRNO_NON_EQUAL_IN_OUT,
+            #[cfg(feature = "cfa")]
+            SchemaErrno::CfaIssuedMismatch => ERRNO_ISSUED_MISMATCH,
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaNonEqualAmounts => ERRNO_NON_EQUAL_IN_OUT,
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaIssuedMismatch => ERRNO_ISSUED_MISMATCH,
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaChannelOutputsMismatch => ERRNO_CHANNEL_OUTPUTS_MISMATCH,
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoNonEqualAmounts => ERRNO_NON_EQUAL_IN_OUT,
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoIssuedMismatch => ERRNO_ISSUED_MISMATCH,
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoFillMismatch => ERRNO_FILL_MISMATCH,
+        }
+    }
+
+    /// Stable name of the error, matching the name under which the error is
+    /// declared in the interface implementation for the schema. Errors which
+    /// are not known to the interface are not declared by the implementation
+    /// and are named only here.
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "nia")]
+            SchemaErrno::NiaNonEqualAmounts => "nonEqualAmounts",
+            #[cfg(feature = "nia")]
+            SchemaErrno::NiaIssuedMismatch => "issuedMismatch",
+            #[cfg(feature = "uda")]
+            SchemaErrno::UdaUnknownToken => "unknownToken",
+            #[cfg(feature = "uda")]
+            SchemaErrno::UdaNonFractionalToken => "nonFractionalToken",
+            #[cfg(feature = "cfa")]
+            SchemaErrno::CfaNonEqualAmounts => "nonEqualAmounts",
+            #[cfg(feature = "cfa")]
+            SchemaErrno::CfaIssuedMismatch => "issuedMismatch",
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaNonEqualAmounts => "nonEqualAmounts",
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaIssuedMismatch => "issuedMismatch",
+            #[cfg(feature = "lfa")]
+            SchemaErrno::LfaChannelOutputsMismatch => "channelOutputsMismatch",
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoNonEqualAmounts => "nonEqualAmounts",
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoIssuedMismatch => "issuedMismatch",
+            #[cfg(feature = "aso")]
+            SchemaErrno::AsoFillMismatch => "fillMismatch",
+        }
+    }
+
+    /// Human-readable description of the error.
+    pub fn description(self) -> String { self.to_string() }
+
+    /// Error codes defined by a schema.
+    pub fn with_schema(schema: BundledSchema) -> impl Iterator<Item = SchemaErrno> {
+        Self::TABLES
+            .iter()
+            .filter(move |(bundled, _)| *bundled == schema)
+            .flat_map(|(_, table)| table.iter().copied())
+    }
+
+    /// Resolves numeric error code in the context of a schema.
+    pub fn with(schema: BundledSchema, errno: u8) -> Option<SchemaErrno> {
+        Self::with_schema(schema).find(|e| e.errno() == errno)
+    }
+}
+
+/// Failure of a validation script of one of the bundled schemata.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(doc_comments)]
+pub enum ScriptFailure {
+    /// operation {opid} was rejected by validation script: {error_code}
+    Rejected { opid: OpId, error_code: SchemaErrno },
+
+    /// operation {opid} was rejected by {schema} validation script with error
+    /// code {errno}, which is not known to the schema.
+    UnknownErrno {
+        opid: OpId,
+        schema: BundledSchema,
+        errno: u8,
+    },
+
+    /// operation {opid} was rejected by {schema} validation script without
+    /// providing an error code.
+    NoErrno { opid: OpId, schema: BundledSchema },
+}
+
+impl ScriptFailure {
+    /// Converts validation failure of an operation from the consignment into
+    /// a typed script failure.
+    ///
+    /// The error code is interpreted in the context of the bundled schema
+    /// which has provided the library of the failed operation validator. Thus,
+    /// failures of contracts under schema variants and forks, which reuse the
+    /// validation libraries of the bundled schemata, are resolved as well.
+    ///
+    /// Returns `None` if the failure is not caused by a validation script or
+    /// if the validation library is not one of the bundled schemata.
+    pub fn with(consignment: &impl ConsignmentApi, failure: &Failure) -> Option<Self> {
+        let Failure::ScriptFailure(opid, errno, _) = failure else {
+            return None;
+        };
+        let opid = *opid;
+        let schema = consignment.schema();
+        let validator = match consignment.operation(opid)? {
+            OpRef::Genesis(_) => schema.genesis.validator,
+            OpRef::Transition(transition) => {
+                schema
+                    .transitions
+                    .get(&transition.transition_type)?
+                    .validator
+            }
+            OpRef::Extension(extension) => {
+                schema.extensions.get(&extension.extension_type)?.validator
+            }
+        }?;
+        let schema = BundledSchema::with_validator(schema, validator.lib)?;
+        Some(match errno {
+            None => ScriptFailure::NoErrno { opid, schema },
+            Some(errno) => match SchemaErrno::with(schema, *errno) {
+                Some(error_code) => ScriptFailure::Rejected { opid, error_code },
+                None => ScriptFailure::UnknownErrno {
+                    opid,
+                    schema,
+                    errno: *errno,
+                },
+            },
+        })
+    }
+
+    /// Operation which has failed validation.
+    pub fn opid(&self) -> OpId {
+        match self {
+            ScriptFailure::Rejected { opid, .. }
+            | ScriptFailure::UnknownErrno { opid, .. }
+            | ScriptFailure::NoErrno { opid, .. } => *opid,
+        }
+    }
+
+    /// Typed error code, if it is known.
+    pub fn error_code(&self) -> Option<SchemaErrno> {
+        match self {
+            ScriptFailure::Rejected { error_code, .. } => Some(*error_code),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use std::str::FromStr;
+
+    use aluvm::library::{LibId, LibSite};
+    use amplify::ByteArray;
+    use rgbstd::{Identity, Operation};
+
+    use super::*;
+    use crate::testkit::genesis;
+    use crate::{OpValidator, SchemaBuilder};
+
+    #[test]
+    fn tables() {
+        assert_eq!(SchemaErrno::TABLES.len(), BundledSchema::COUNT);
+        let mut all = vec![];
+        for (schema, table) in SchemaErrno::TABLES {
+            assert!(table.iter().all(|errno| errno.schema() == *schema), "{schema}");
+            all.extend_from_slice(table);
+        }
+        assert_eq!(all, SchemaErrno::ALL);
+        assert_eq!(SchemaErrno::ALL.len(), SchemaErrno::COUNT);
+    }
+
+    #[test]
+    fn iimpl_errors() {
+        for schema in BundledSchema::ALL {
+            let iface = schema.iface();
+            let mut declared = schema
+                .issue_impl()
+                .errors
+                .iter()
+                .map(|variant| (variant.id, variant.name.to_string()))
+                .collect::<Vec<_>>();
+            declared.sort();
+            let mut registered = SchemaErrno::with_schema(schema)
+                .filter(|errno| {
+                    iface
+                        .errors
+                        .keys()
+                        .any(|name| name.as_str() == errno.name())
+                })
+                .map(|errno| (errno.errno(), errno.name().to_owned()))
+                .collect::<Vec<_>>();
+            registered.sort();
+            assert_eq!(declared, registered, "{schema} error registry mismatch");
+        }
+    }
+
+    #[test]
+    fn same_errno_per_schema() {
+        let nia = BundledSchema::NonInflatableAsset;
+        let uda = BundledSchema::UniqueDigitalAsset;
+        assert_eq!(
+            SchemaErrno::with(nia, ERRNO_NON_EQUAL_IN_OUT),
+            Some(SchemaErrno::NiaNonEqualAmounts)
+        );
+        assert_eq!(
+            SchemaErrno::with(uda, ERRNO_NON_EQUAL_IN_OUT),
+            Some(SchemaErrno::UdaUnknownToken)
+        );
+        assert_eq!(SchemaErrno::with(nia, ERRNO_NON_FRACTIONAL), None);
+    }
+
+    #[test]
+    fn failure() {
+        let uda = BundledSchema::UniqueDigitalAsset;
+        let validator = OpValidator::bundled(uda, genesis(uda));
+        let opid = validator.genesis().id();
+
+        let failure = Failure::ScriptFailure(opid, Some(ERRNO_NON_FRACTIONAL), None);
+        let error = ScriptFailure::with(&validator, &failure).unwrap();
+        assert_eq!(error, ScriptFailure::Rejected {
+            opid,
+            error_code: SchemaErrno::UdaNonFractionalToken
+        });
+        assert_eq!(error.error_code().unwrap().name(), "nonFractionalToken");
+
+        let failure = Failure::ScriptFailure(opid, Some(0xFF), None);
+        assert_eq!(
+            ScriptFailure::with(&validator, &failure),
+            Some(ScriptFailure::UnknownErrno {
+                opid,
+                schema: uda,
+                errno: 0xFF
+            })
+        );
+
+        let failure = Failure::ScriptFailure(opid, None, None);
+        assert_eq!(
+            ScriptFailure::with(&validator, &failure),
+            Some(ScriptFailure::NoErrno { opid, schema: uda })
+        );
+
+        assert_eq!(ScriptFailure::with(&validator, &Failure::Custom(s!("other"))), None);
+    }
+
+    #[test]
+    fn variants() {
+        let cfa = BundledSchema::CollectibleFungibleAsset;
+        let developer = Identity::from_str("ssi:private-fork").unwrap();
+        for builder in [
+            SchemaBuilder::new(cfa).developer(developer.clone()),
+            SchemaBuilder::new(cfa).transfer_memo(),
+        ] {
+            let validator = OpValidator::new(
+                builder.schema(),
+                builder.types(),
+                builder.scripts(),
+                genesis(cfa),
+            );
+            let opid = validator.genesis().id();
+            let failure = Failure::ScriptFailure(opid, Some(ERRNO_ISSUED_MISMATCH), None);
+            assert_eq!(
+                ScriptFailure::with(&validator, &failure),
+                Some(ScriptFailure::Rejected {
+                    opid,
+                    error_code: SchemaErrno::CfaIssuedMismatch
+                })
+            );
+        }
+
+        // LFA shares NIA validation library, global and owned state types, and
+        // its forks must not be mistaken for NIA ones.
+        let lfa = BundledSchema::LightningFungibleAsset;
+        let nia = BundledSchema::NonInflatableAsset;
+        for (origin, error_code) in
+            [(lfa, SchemaErrno::LfaIssuedMismatch), (nia, SchemaErrno::NiaIssuedMismatch)]
+        {
+            for builder in [
+                SchemaBuilder::new(origin).developer(developer.clone()),
+                SchemaBuilder::new(origin).transfer_memo(),
+            ] {
+                let validator = OpValidator::new(
+                    builder.schema(),
+                    builder.types(),
+                    builder.scripts(),
+                    genesis(origin),
+                );
+                let opid = validator.genesis().id();
+                let failure = Failure::ScriptFailure(opid, Some(ERRNO_ISSUED_MISMATCH), None);
+                assert_eq!(
+                    ScriptFailure::with(&validator, &failure),
+                    Some(ScriptFailure::Rejected { opid, error_code }),
+                    "{origin} fork"
+                );
+            }
+        }
+
+        // Schema validated by a library which is not one of the bundled ones.
+        let mut schema = cfa.schema();
+        schema.genesis.validator = Some(LibSite::with(0, LibId::from_byte_array([0xEE; 32])));
+        let validator = OpValidator::new(schema, cfa.types(), cfa.scripts(), genesis(cfa));
+        let opid = validator.genesis().id();
+        let failure = Failure::ScriptFailure(opid, Some(ERRNO_ISSUED_MISMATCH), None);
+        assert_eq!(ScriptFailure::with(&validator, &failure), None);
+    }
+}
@@ -0,0 +1,204 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Execution of schema validators on individual contract operations, without
+//! the need of resolving witness transactions.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::iter;
+use std::rc::Rc;
+
+use amplify::confinement::Confined;
+use bp::Txid;
+use rgbstd::containers::Consignment;
+use rgbstd::persistence::MemContract;
+use rgbstd::validation::{
+    CheckedConsignment, ConsignmentApi, EAnchor, Failure, OpRef, Scripts, Status,
+};
+use rgbstd::vm::{ContractStateEvolve, OrdOpRef, WitnessOrd, XWitnessId};
+use rgbstd::{BundleId, Genesis, OpId, Operation, Schema, Transition, TransitionBundle, XChain};
+use strict_types::TypeSystem;
+
+use crate::BundledSchema;
+
+/// Set of contract operations, validators of which can be run one by one.
+///
+/// Validation of an operation includes its conformance to the schema and
+/// execution of the schema validation script. Previous state for state
+/// transitions is taken from the genesis and transitions added to the set;
+/// witness transactions are not checked.
+#[derive(Clone, Debug)]
+pub struct OpValidator {
+    schema: Schema,
+    types: TypeSystem,
+    scripts: Scripts,
+    genesis: Genesis,
+    transitions: BTreeMap<OpId, Transition>,
+}
+
+impl OpValidator {
+    pub fn new(schema: Schema, types: TypeSystem, scripts: Scripts, genesis: Genesis) -> Self {
+        OpValidator {
+            schema,
+            types,
+            scripts,
+            genesis,
+            transitions: none!(),
+        }
+    }
+
+    /// Constructs validator for a contract genesis issued under one of the
+    /// bundled schemata.
+    pub fn bundled(schema: BundledSchema, genesis: Genesis) -> Self {
+        Self::new(schema.schema(), schema.types(), schema.scripts(), genesis)
+    }
+
+    /// Constructs validator for the genesis and all known state transitions
+    /// of a consignment.
+    pub fn with_consignment<const TRANSFER: bool>(consignment: &Consignment<TRANSFER>) -> Self {
+        let scripts = consignment
+            .scripts
+            .iter()
+            .map(|lib| (lib.id(), lib.clone()));
+        let mut validator = Self::new(
+            consignment.schema.clone(),
+            consignment.types.clone(),
+            Confined::from_iter_checked(scripts),
+            consignment.genesis.clone(),
+        );
+        for transition in consignment
+            .bundles
+            .iter()
+            .flat_map(|bundle| bundle.known_transitions())
+        {
+            validator.add_transition(transition.clone());
+        }
+        validator
+    }
+
+    /// Adds state transition to the set, returning its id.
+    pub fn add_transition(&mut self, transition: Transition) -> OpId {
+        let opid = transition.id();
+        self.transitions.insert(opid, transition);
+        opid
+    }
+
+    pub fn genesis(&self) -> &Genesis { &self.genesis }
+
+    /// Ids of the genesis and of all added state transitions.
+    pub fn opids(&self) -> impl Iterator<Item = OpId> + '_ {
+        iter::once(self.genesis.id()).chain(self.transitions.keys().copied())
+    }
+
+    /// Validates operation with the given id, which must be either the genesis
+    /// or one of the added transitions.
+    pub fn validate(&self, opid: OpId) -> Status {
+        let consignment = CheckedConsignment::new(self);
+        let contract_id = self.genesis.contract_id();
+        let state = Rc::new(RefCell::new(MemContract::init((&self.schema, contract_id))));
+
+        let genesis = OrdOpRef::Genesis(&self.genesis);
+        if opid == self.genesis.id() {
+            return self.schema.validate_state(&consignment, genesis, state);
+        }
+        let Some(transition) = self.transitions.get(&opid) else {
+            let mut status = Status::new();
+            status.add_failure(Failure::OperationAbsent(opid));
+            return status;
+        };
+        state
+            .borrow_mut()
+            .evolve_state(genesis)
+            .expect("genesis state always fits into memory");
+        let witness_id = XChain::Bitcoin(Txid::coinbase());
+        let op = OrdOpRef::Transition(transition, witness_id, WitnessOrd::Tentative);
+        self.schema.validate_state(&consignment, op, state)
+    }
+}
+
+impl ConsignmentApi for OpValidator {
+    fn schema(&self) -> &Schema { &self.schema }
+
+    fn types(&self) -> &TypeSystem { &self.types }
+
+    fn scripts(&self) -> &Scripts { &self.scripts }
+
+    fn operation(&self, opid: OpId) -> Option<OpRef<'_>> {
+        if opid == self.genesis.id() {
+            return Some(OpRef::Genesis(&self.genesis));
+        }
+        self.transitions.get(&opid).map(OpRef::Transition)
+    }
+
+    fn genesis(&self) -> &Genesis { &self.genesis }
+
+    fn bundle_ids<'iter>(&self) -> impl Iterator<Item = BundleId> + 'iter { iter::empty() }
+
+    fn bundle(&self, _bundle_id: BundleId) -> Option<&TransitionBundle> { None }
+
+    fn anchor(&self, _bundle_id: BundleId) -> Option<(XWitnessId, &EAnchor)> { None }
+
+    fn op_witness_id(&self, _opid: OpId) -> Option<XWitnessId> { None }
+}
+
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+pub(crate) mod test {
+    use super::*;
+    use crate::testkit::{genesis, scenarios, transfer};
+    use crate::{ScriptFailure, OS_ASSET};
+
+    #[test]
+    fn validate() {
+        for scenario in scenarios() {
+            let status = scenario.validator.validate(scenario.opid);
+            let errnos = status
+                .failures
+                .iter()
+                .filter_map(|failure| ScriptFailure::with(&scenario.validator, failure))
+                .map(|failure| failure.error_code())
+                .collect::<Vec<_>>();
+            match scenario.errno {
+                None => assert!(status.failures.is_empty(), "{}: {status}", scenario.name),
+                Some(errno) => assert_eq!(errnos, vec![Some(errno)], "{}: {status}", scenario.name),
+            }
+        }
+    }
+
+    #[test]
+    fn absent() {
+        let validator = OpValidator::bundled(
+            BundledSchema::NonInflatableAsset,
+            genesis(BundledSchema::NonInflatableAsset),
+        );
+        let opid = transfer(validator.genesis(), OS_ASSET).id();
+        let status = validator.validate(opid);
+        assert_eq!(status.failures, vec![Failure::OperationAbsent(opid)]);
+    }
+}
@@ -0,0 +1,602 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of the bundled schemata into a structured form, which can be
+//! serialized as JSON or YAML and consumed by non-Rust tooling.
+//!
+//! All state, operation and error types are given by their numeric ids
+//! together with the names from the interface implementation; strict types
+//! are given by their semantic ids, names and definitions, and validators
+//! with their library disassembly.
+
+use std::collections::BTreeSet;
+
+use aluvm::library::{Lib, LibSite};
+use amplify::Wrapper;
+#[cfg(any(feature = "nia", feature = "lfa", feature = "aso"))]
+use ifaces::Rgb20;
+#[cfg(feature = "uda")]
+use ifaces::Rgb21;
+#[cfg(feature = "cfa")]
+use ifaces::Rgb25;
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use rgbstd::interface::IfaceClass;
+use rgbstd::interface::IfaceImpl;
+use rgbstd::schema::{OpSchema, OwnedStateSchema};
+use rgbstd::stl::{bp_tx_stl, rgb_contract_stl};
+use rgbstd::{Occurrences, Schema};
+use strict_types::stl::std_stl;
+use strict_types::typesys::SystemBuilder;
+use strict_types::{SemId, SymbolicSys, TypeLib};
+
+use crate::lint::decode;
+#[cfg(any(feature = "lfa", feature = "aso"))]
+use crate::stl::schemata_stl;
+use crate::BundledSchema;
+
+/// Schema, its interface implementation, referenced strict types and
+/// validation scripts.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaExport {
+    pub name: String,
+    pub schema_id: String,
+    pub developer: String,
+    pub timestamp: i64,
+    pub interface: InterfaceExport,
+    pub meta_types: Vec<TypedStateExport>,
+    pub global_types: Vec<GlobalTypeExport>,
+    pub owned_types: Vec<OwnedTypeExport>,
+    pub valency_types: Vec<NamedId>,
+    pub genesis: OperationExport,
+    pub extensions: Vec<OperationExport>,
+    pub transitions: Vec<OperationExport>,
+    pub errors: Vec<NamedId>,
+    pub types: Vec<TypeExport>,
+    pub libs: Vec<LibExport>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceExport {
+    pub name: String,
+    pub iface_id: String,
+    pub impl_id: String,
+}
+
+/// Numeric id of a schema type with its name in the interface
+/// implementation, if the type is mapped to the interface.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedId {
+    pub id: u16,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedStateExport {
+    pub id: u16,
+    pub name: Option<String>,
+    pub sem_id: String,
+    pub type_name: Option<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalTypeExport {
+    #[serde(flatten)]
+    pub state: TypedStateExport,
+    pub max_items: u32,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnedTypeExport {
+    pub id: u16,
+    pub name: Option<String>,
+    /// One of `declarative`, `fungible`, `structured` or `attachment`.
+    pub state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sem_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+}
+
+/// Type of state or metadata in an operation with the allowed number of its
+/// occurrences; `max` is absent if unbounded.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OccurrencesExport {
+    pub id: u16,
+    pub name: Option<String>,
+    pub min: u16,
+    pub max: Option<u16>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationExport {
+    /// Operation type, absent for genesis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u16>,
+    pub name: Option<String>,
+    pub metadata: Vec<NamedId>,
+    pub globals: Vec<OccurrencesExport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<Vec<OccurrencesExport>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redeems: Option<Vec<NamedId>>,
+    pub assignments: Vec<OccurrencesExport>,
+    pub valencies: Vec<NamedId>,
+    pub validator: Option<ValidatorExport>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorExport {
+    pub lib_id: String,
+    pub pos: u16,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeExport {
+    pub sem_id: String,
+    pub name: Option<String>,
+    /// Strict type definition, referencing other types by their semantic ids.
+    pub definition: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibExport {
+    pub lib_id: String,
+    pub disassembly: Vec<String>,
+}
+
+impl SchemaExport {
+    pub fn bundled(bundled: BundledSchema) -> Self {
+        let schema = bundled.schema();
+        let iimpl = bundled.issue_impl();
+        let iface = bundled.iface();
+        let sys = symbols(bundled);
+        let names = Names(&iimpl);
+
+        let typed = |id: u16, name: Option<String>, sem_id: SemId| TypedStateExport {
+            id,
+            name,
+            sem_id: sem_id.to_string(),
+            type_name: sys.lookup(sem_id).map(|fqn| fqn.to_string()),
+        };
+
+        let meta_types = schema
+            .meta_types
+            .iter()
+            .map(|(ty, sem_id)| typed(ty.to_inner(), names.meta(ty.to_inner()), *sem_id))
+            .collect();
+        let global_types = schema
+            .global_types
+            .iter()
+            .map(|(ty, global)| GlobalTypeExport {
+                state: typed(ty.to_inner(), names.global(ty.to_inner()), global.sem_id),
+                max_items: global.max_items.into_u32(),
+            })
+            .collect();
+        let owned_types = schema
+            .owned_types
+            .iter()
+            .map(|(ty, owned)| {
+                let state = match owned {
+                    OwnedStateSchema::Declarative => "declarative",
+                    OwnedStateSchema::Fungible(_) => "fungible",
+                    OwnedStateSchema::Structured(_) => "structured",
+                    OwnedStateSchema::Attachment(_) => "attachment",
+                };
+                OwnedTypeExport {
+                    id: ty.to_inner(),
+                    name: names.assignment(ty.to_inner()),
+                    state,
+                    sem_id: owned.sem_id().map(|id| id.to_string()),
+                    type_name: owned
+                        .sem_id()
+                        .and_then(|id| sys.lookup(id))
+                        .map(|fqn| fqn.to_string()),
+                }
+            })
+            .collect();
+        let valency_types = schema
+            .valency_types
+            .iter()
+            .map(|ty| names.valency(ty.to_inner()))
+            .collect();
+
+        let genesis = operation(&schema.genesis, None, None, schema.genesis.validator, &names);
+        let extensions = schema
+            .extensions
+            .iter()
+            .map(|(ty, ext)| {
+                let id = ty.to_inner();
+                operation(ext, Some(id), names.extension(id), ext.validator, &names)
+            })
+            .collect();
+        let transitions = schema
+            .transitions
+            .iter()
+            .map(|(ty, transition)| {
+                let id = ty.to_inner();
+                operation(transition, Some(id), names.transition(id), transition.validator, &names)
+            })
+            .collect();
+        let errors = iimpl
+            .errors
+            .iter()
+            .map(|variant| NamedId {
+                id: variant.id as u16,
+                name: Some(variant.name.to_string()),
+            })
+            .collect();
+
+        let types = bundled
+            .types()
+            .extract(referenced_types(&schema))
+            .expect("schema references unknown types");
+        let types = types
+            .as_inner()
+            .iter()
+            .map(|(sem_id, ty)| TypeExport {
+                sem_id: sem_id.to_string(),
+                name: sys.lookup(*sem_id).map(|fqn| fqn.to_string()),
+                definition: ty
+                    .to_string()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+            .collect();
+        let libs = bundled
+            .scripts()
+            .values()
+            .map(|lib| LibExport {
+                lib_id: lib.id().to_string(),
+                disassembly: disassemble(lib),
+            })
+            .collect();
+
+        SchemaExport {
+            name: schema.name.to_string(),
+            schema_id: schema.schema_id().to_string(),
+            developer: schema.developer.to_string(),
+            timestamp: schema.timestamp,
+            interface: InterfaceExport {
+                name: iface.name.to_string(),
+                iface_id: iface.iface_id().to_string(),
+                impl_id: iimpl.impl_id().to_string(),
+            },
+            meta_types,
+            global_types,
+            owned_types,
+            valency_types,
+            genesis,
+            extensions,
+            transitions,
+            errors,
+            types,
+            libs,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("schema export is always serializable")
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("schema export is always serializable")
+    }
+}
+
+/// Type library of the interface implemented by a bundled schema.
+fn iface_stl(schema: BundledSchema) -> TypeLib {
+    match schema {
+        #[cfg(feature = "nia")]
+        BundledSchema::NonInflatableAsset => Rgb20::FIXED.stl(),
+        #[cfg(feature = "uda")]
+        BundledSchema::UniqueDigitalAsset => Rgb21::NONE.stl(),
+        #[cfg(feature = "cfa")]
+        BundledSchema::CollectibleFungibleAsset => Rgb25::NONE.stl(),
+        #[cfg(feature = "lfa")]
+        BundledSchema::LightningFungibleAsset => Rgb20::FIXED.stl(),
+        #[cfg(feature = "aso")]
+        BundledSchema::AssetSwapOffer => Rgb20::FIXED.stl(),
+    }
+}
+
+/// Type system of a bundled schema with type names.
+pub(crate) fn symbols(schema: BundledSchema) -> SymbolicSys {
+    let mut builder = SystemBuilder::new();
+    for lib in [std_stl(), bp_tx_stl(), rgb_contract_stl(), iface_stl(schema)] {
+        builder = builder.import(lib).expect("invalid standard type library");
+    }
+    #[cfg(feature = "lfa")]
+    if schema == BundledSchema::LightningFungibleAsset {
+        builder = builder
+            .import(schemata_stl())
+            .expect("invalid RGBSchemata type library");
+    }
+    #[cfg(feature = "aso")]
+    if schema == BundledSchema::AssetSwapOffer {
+        builder = builder
+            .import(schemata_stl())
+            .expect("invalid RGBSchemata type library");
+    }
+    builder
+        .finalize()
+        .expect("error in standard RGBContract type system")
+}
+
+/// Names of schema types in the interface implementation.
+struct Names<'iimpl>(&'iimpl IfaceImpl);
+
+impl Names<'_> {
+    fn meta(&self, id: u16) -> Option<String> {
+        let field = self.0.metadata.iter().find(|f| f.id.to_inner() == id);
+        field.map(|f| f.name.to_string())
+    }
+
+    fn global(&self, id: u16) -> Option<String> {
+        let field = self.0.global_state.iter().find(|f| f.id.to_inner() == id);
+        field.map(|f| f.name.to_string())
+    }
+
+    fn assignment(&self, id: u16) -> Option<String> {
+        let field = self.0.assignments.iter().find(|f| f.id.to_inner() == id);
+        field.map(|f| f.name.to_string())
+    }
+
+    fn valency(&self, id: u16) -> NamedId {
+        let field = self.0.valencies.iter().find(|f| f.id.to_inner() == id);
+        NamedId {
+            id,
+            name: field.map(|f| f.name.to_string()),
+        }
+    }
+
+    fn transition(&self, id: u16) -> Option<String> {
+        let field = self.0.transitions.iter().find(|f| f.id.to_inner() == id);
+        field.map(|f| f.name.to_string())
+    }
+
+    fn extension(&self, id: u16) -> Option<String> {
+        let field = self.0.extensions.iter().find(|f| f.id.to_inner() == id);
+        field.map(|f| f.name.to_string())
+    }
+}
+
+fn occurrences(id: u16, name: Option<String>, occ: &Occurrences) -> OccurrencesExport {
+    OccurrencesExport {
+        id,
+        name,
+        min: occ.min_value(),
+        max: Some(occ.max_value()).filter(|max| *max != u16::MAX),
+    }
+}
+
+fn operation(
+    op: &impl OpSchema,
+    id: Option<u16>,
+    name: Option<String>,
+    validator: Option<LibSite>,
+    names: &Names,
+) -> OperationExport {
+    let assignments = |schema: &rgbstd::schema::AssignmentsSchema| {
+        schema
+            .iter()
+            .map(|(ty, occ)| occurrences(ty.to_inner(), names.assignment(ty.to_inner()), occ))
+            .collect::<Vec<_>>()
+    };
+    OperationExport {
+        id,
+        name,
+        metadata: op
+            .metadata()
+            .iter()
+            .map(|ty| NamedId {
+                id: ty.to_inner(),
+                name: names.meta(ty.to_inner()),
+            })
+            .collect(),
+        globals: op
+            .globals()
+            .iter()
+            .map(|(ty, occ)| occurrences(ty.to_inner(), names.global(ty.to_inner()), occ))
+            .collect(),
+        inputs: op.inputs().map(assignments),
+        redeems: op.redeems().map(|redeems| {
+            redeems
+                .iter()
+                .map(|ty| names.valency(ty.to_inner()))
+                .collect()
+        }),
+        assignments: assignments(op.assignments()),
+        valencies: op
+            .valencies()
+            .iter()
+            .map(|ty| names.valency(ty.to_inner()))
+            .collect(),
+        validator: validator.map(|site| ValidatorExport {
+            lib_id: site.lib.to_string(),
+            pos: site.pos,
+        }),
+    }
+}
+
+fn referenced_types(schema: &Schema) -> BTreeSet<SemId> {
+    let meta = schema.meta_types.values().copied();
+    let globals = schema.global_types.values().map(|global| global.sem_id);
+    let owned = schema
+        .owned_types
+        .values()
+        .filter_map(OwnedStateSchema::sem_id);
+    meta.chain(globals).chain(owned).collect()
+}
+
+/// Disassembles library code in the same format as AluVM does.
+fn disassemble(lib: &Lib) -> Vec<String> {
+    decode(lib)
+        .into_iter()
+        .map(|(pos, instr)| match instr {
+            Some(instr) => format!("@x{pos:06X}: {instr}"),
+            None => format!("@x{pos:06X}: <undecodable bytecode>"),
+        })
+        .collect()
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{GS_NOMINAL, OS_ASSET, TS_TRANSFER};
+
+    #[test]
+    fn nia() {
+        let export = SchemaExport::bundled(BundledSchema::NonInflatableAsset);
+        let spec = &export.global_types[0].state;
+        assert_eq!(spec.id, GS_NOMINAL.to_inner());
+        assert_eq!(spec.name.as_deref(), Some("spec"));
+        assert_eq!(spec.type_name.as_deref(), Some("RGBContract.AssetSpec"));
+        assert_eq!(export.owned_types[0].id, OS_ASSET.to_inner());
+        assert_eq!(export.owned_types[0].state, "fungible");
+
+        let transfer = &export.transitions[0];
+        assert_eq!(transfer.id, Some(TS_TRANSFER.to_inner()));
+        assert_eq!(transfer.name.as_deref(), Some("transfer"));
+        let inputs = transfer.inputs.as_ref().unwrap();
+        assert_eq!((inputs[0].min, inputs[0].max), (1, None));
+        assert!(export.genesis.inputs.is_none());
+    }
+
+    #[test]
+    fn lfa() {
+        let export = SchemaExport::bundled(BundledSchema::LightningFungibleAsset);
+        let meta = &export.meta_types[0];
+        assert_eq!(meta.name.as_deref(), Some("channelOutputs"));
+        assert_eq!(meta.type_name.as_deref(), Some("RGBSchemata.ChannelOutputs"));
+        assert_eq!(export.transitions[0].metadata, vec![NamedId {
+            id: meta.id,
+            name: Some(s!("channelOutputs"))
+        }]);
+        assert!(export
+            .types
+            .iter()
+            .any(|ty| ty.name.as_deref() == Some("RGBSchemata.HtlcOutput")));
+    }
+
+    #[test]
+    fn aso() {
+        let export = SchemaExport::bundled(BundledSchema::AssetSwapOffer);
+        let offer = export
+            .global_types
+            .iter()
+            .find(|ty| ty.state.name.as_deref() == Some("offer"))
+            .unwrap();
+        assert_eq!(offer.state.type_name.as_deref(), Some("RGBSchemata.OfferTerms"));
+        let take = &export.transitions[1];
+        assert_eq!(take.name.as_deref(), Some("take"));
+        assert_eq!(take.globals[0].name.as_deref(), Some("fill"));
+        assert_eq!((take.globals[0].min, take.globals[0].max), (1, Some(1)));
+    }
+
+    #[test]
+    fn references() {
+        for schema in BundledSchema::ALL {
+            let export = SchemaExport::bundled(schema);
+            let types = export
+                .types
+                .iter()
+                .map(|ty| ty.sem_id.as_str())
+                .collect::<BTreeSet<_>>();
+            for ty in &export.types {
+                for word in ty
+                    .definition
+                    .split(|c: char| !c.is_alphanumeric() && !"$!-#:".contains(c))
+                {
+                    if word.starts_with("semid:") {
+                        assert!(types.contains(word), "{schema}: {word} is not exported");
+                    }
+                }
+            }
+            for global in &export.global_types {
+                assert!(types.contains(global.state.sem_id.as_str()));
+                assert!(global.state.type_name.is_some());
+            }
+
+            let validators = [&export.genesis]
+                .into_iter()
+                .chain(&export.transitions)
+                .filter_map(|op| op.validator.as_ref());
+            for validator in validators {
+                let lib = export
+                    .libs
+                    .iter()
+                    .find(|lib| lib.lib_id == validator.lib_id)
+                    .expect("validator library is not exported");
+                let entry = format!("@x{:06X}: ", validator.pos);
+                assert!(lib.disassembly.iter().any(|line| line.starts_with(&entry)));
+            }
+        }
+    }
+
+    #[test]
+    fn formats() {
+        for schema in BundledSchema::ALL {
+            let export = SchemaExport::bundled(schema);
+            let json = serde_json::from_str::<Value>(&export.to_json()).unwrap();
+            let yaml = serde_yaml::from_str::<Value>(&export.to_yaml()).unwrap();
+            assert_eq!(json, yaml);
+            assert_eq!(json["name"], schema.schema().name.to_string());
+        }
+    }
+}
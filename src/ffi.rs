@@ -0,0 +1,418 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! C API, available with `ffi` feature.
+//!
+//! All functions return [`SchemataStatus`] and write their results into the
+//! provided output pointers. Strings and byte buffers returned by the library
+//! are owned by the caller and must be released with [`schemata_string_free`]
+//! and [`schemata_bytes_free`]. In case of a failure, the error description
+//! can be read with [`schemata_error_message`].
+//!
+//! The C header `include/schemata.h` is generated with [cbindgen]:
+//!
+//! ```console
+//! $ cbindgen --config cbindgen.toml --output include/schemata.h
+//! ```
+//!
+//! [cbindgen]: https://github.com/mozilla/cbindgen
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use rgbstd::containers::ConsignmentExt;
+use rgbstd::containers::{FileContent, UniversalFile};
+
+use crate::BundledSchema;
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use crate::{IssueManifest, ManifestError};
+
+/// Status code returned by all functions of the C API.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SchemataStatus {
+    /// Operation succeeded.
+    Ok = 0,
+    /// One of the pointer arguments is null.
+    NullArgument = 1,
+    /// String argument is not a valid UTF-8.
+    InvalidUtf8 = 2,
+    /// Unknown bundled schema name.
+    UnknownSchema = 3,
+    /// Issuance parameters are not a valid JSON issuance manifest.
+    InvalidParams = 4,
+    /// Issuance parameters specify schema different from the one being
+    /// issued.
+    SchemaMismatch = 5,
+    /// Contract can't be issued with the provided parameters.
+    IssueFailed = 6,
+    /// Serialization of a kit or contract has failed.
+    Serialization = 7,
+}
+
+/// Byte buffer allocated by the library.
+#[repr(C)]
+#[derive(Debug)]
+pub struct SchemataBytes {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+struct Failure(SchemataStatus, String);
+
+impl Failure {
+    fn new(status: SchemataStatus, err: impl ToString) -> Self { Failure(status, err.to_string()) }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn status(result: Result<(), Failure>) -> SchemataStatus {
+    let (status, message) = match result {
+        Ok(()) => (SchemataStatus::Ok, String::new()),
+        Err(Failure(status, message)) => (status, message),
+    };
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, Failure> {
+    if s.is_null() {
+        return Err(Failure::new(SchemataStatus::NullArgument, "null string argument"));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|err| Failure::new(SchemataStatus::InvalidUtf8, err))
+}
+
+unsafe fn read_schema(schema: *const c_char) -> Result<BundledSchema, Failure> {
+    read_str(schema)?
+        .parse()
+        .map_err(|err| Failure::new(SchemataStatus::UnknownSchema, err))
+}
+
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), Failure> {
+    if out.is_null() {
+        return Err(Failure::new(SchemataStatus::NullArgument, "null output argument"));
+    }
+    *out = value;
+    Ok(())
+}
+
+fn c_string(s: String) -> Result<*mut c_char, Failure> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|err| Failure::new(SchemataStatus::Serialization, err))
+}
+
+/// Returns description of the last failure of a function called from the
+/// current thread, or an empty string if it has succeeded.
+///
+/// The returned string is owned by the library and remains valid until the
+/// next call to the library from the same thread.
+#[no_mangle]
+pub extern "C" fn schemata_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
+
+/// Writes binary kit of the bundled schema with the provided name into `out`.
+///
+/// # Safety
+///
+/// `schema` must be a null-terminated string and `out` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn schemata_kit(
+    schema: *const c_char,
+    out: *mut SchemataBytes,
+) -> SchemataStatus {
+    status((|| {
+        let mut data = vec![];
+        read_schema(schema)?
+            .kit()
+            .save(&mut data)
+            .map_err(|err| Failure::new(SchemataStatus::Serialization, err))?;
+        let len = data.len();
+        let data = Box::into_raw(data.into_boxed_slice());
+        write(out, SchemataBytes {
+            data: data as *mut u8,
+            len,
+        })
+        .inspect_err(|_| drop(Box::from_raw(data)))
+    })())
+}
+
+/// Writes ASCII-armored kit of the bundled schema with the provided name into
+/// `out`.
+///
+/// # Safety
+///
+/// `schema` must be a null-terminated string and `out` must be valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn schemata_kit_armored(
+    schema: *const c_char,
+    out: *mut *mut c_char,
+) -> SchemataStatus {
+    status((|| {
+        let armored = UniversalFile::Kit(read_schema(schema)?.kit()).to_string();
+        let armored = c_string(armored)?;
+        write(out, armored).inspect_err(|_| drop(CString::from_raw(armored)))
+    })())
+}
+
+#[cfg(any(feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso"))]
+unsafe fn issue(
+    schema: BundledSchema,
+    params: *const c_char,
+    contract_id: *mut *mut c_char,
+    contract: *mut *mut c_char,
+) -> SchemataStatus {
+    status((|| {
+        if contract_id.is_null() || contract.is_null() {
+            return Err(Failure::new(SchemataStatus::NullArgument, "null output argument"));
+        }
+        let manifest = IssueManifest::from_json_for(schema, read_str(params)?).map_err(|err| {
+            let status = match err {
+                ManifestError::SchemaMismatch(..) => SchemataStatus::SchemaMismatch,
+                _ => SchemataStatus::InvalidParams,
+            };
+            Failure::new(status, err)
+        })?;
+
+        let valid = manifest
+            .issue()
+            .map_err(|err| Failure::new(SchemataStatus::IssueFailed, err))?;
+        let id = c_string(valid.contract_id().to_string())?;
+        let armored = match c_string(valid.to_string()) {
+            Ok(armored) => armored,
+            Err(err) => {
+                drop(CString::from_raw(id));
+                return Err(err);
+            }
+        };
+        *contract_id = id;
+        *contract = armored;
+        Ok(())
+    })())
+}
+
+/// Issues NIA contract from JSON issuance parameters, writing its id and
+/// ASCII-armored contract into `contract_id` and `contract`.
+///
+/// The parameters follow the issuance manifest format, where `schema` field
+/// may be omitted.
+///
+/// # Safety
+///
+/// `params` must be a null-terminated string; `contract_id` and `contract`
+/// must be valid for writes.
+#[cfg(feature = "nia")]
+#[no_mangle]
+pub unsafe extern "C" fn schemata_issue_nia(
+    params: *const c_char,
+    contract_id: *mut *mut c_char,
+    contract: *mut *mut c_char,
+) -> SchemataStatus {
+    issue(BundledSchema::NonInflatableAsset, params, contract_id, contract)
+}
+
+/// Issues CFA contract from JSON issuance parameters, writing its id and
+/// ASCII-armored contract into `contract_id` and `contract`.
+///
+/// The parameters follow the issuance manifest format, where `schema` field
+/// may be omitted.
+///
+/// # Safety
+///
+/// `params` must be a null-terminated string; `contract_id` and `contract`
+/// must be valid for writes.
+#[cfg(feature = "cfa")]
+#[no_mangle]
+pub unsafe extern "C" fn schemata_issue_cfa(
+    params: *const c_char,
+    contract_id: *mut *mut c_char,
+    contract: *mut *mut c_char,
+) -> SchemataStatus {
+    issue(BundledSchema::CollectibleFungibleAsset, params, contract_id, contract)
+}
+
+/// Issues LFA contract from JSON issuance parameters, writing its id and
+/// ASCII-armored contract into `contract_id` and `contract`.
+///
+/// The parameters follow the issuance manifest format, where `schema` field
+/// may be omitted.
+///
+/// # Safety
+///
+/// `params` must be a null-terminated string; `contract_id` and `contract`
+/// must be valid for writes.
+#[cfg(feature = "lfa")]
+#[no_mangle]
+pub unsafe extern "C" fn schemata_issue_lfa(
+    params: *const c_char,
+    contract_id: *mut *mut c_char,
+    contract: *mut *mut c_char,
+) -> SchemataStatus {
+    issue(BundledSchema::LightningFungibleAsset, params, contract_id, contract)
+}
+
+/// Issues ASO contract from JSON issuance parameters, writing its id and
+/// ASCII-armored contract into `contract_id` and `contract`.
+///
+/// The parameters follow the issuance manifest format, where `schema` field
+/// may be omitted.
+///
+/// # Safety
+///
+/// `params` must be a null-terminated string; `contract_id` and `contract`
+/// must be valid for writes.
+#[cfg(feature = "aso")]
+#[no_mangle]
+pub unsafe extern "C" fn schemata_issue_aso(
+    params: *const c_char,
+    contract_id: *mut *mut c_char,
+    contract: *mut *mut c_char,
+) -> SchemataStatus {
+    issue(BundledSchema::AssetSwapOffer, params, contract_id, contract)
+}
+
+/// Issues UDA contract from JSON issuance parameters, writing its id and
+/// ASCII-armored contract into `contract_id` and `contract`.
+///
+/// The parameters follow the issuance manifest format, where `schema` field
+/// may be omitted. Paths to media files are relative to the current
+/// directory.
+///
+/// # Safety
+///
+/// `params` must be a null-terminated string; `contract_id` and `contract`
+/// must be valid for writes.
+#[cfg(feature = "uda")]
+#[no_mangle]
+pub unsafe extern "C" fn schemata_issue_uda(
+    params: *const c_char,
+    contract_id: *mut *mut c_char,
+    contract: *mut *mut c_char,
+) -> SchemataStatus {
+    issue(BundledSchema::UniqueDigitalAsset, params, contract_id, contract)
+}
+
+/// Releases string returned by the library. Does nothing for a null pointer.
+///
+/// # Safety
+///
+/// `s` must be either null or a string returned by the library which was not
+/// released yet.
+#[no_mangle]
+pub unsafe extern "C" fn schemata_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Releases byte buffer returned by the library. Does nothing for a buffer
+/// with null data.
+///
+/// # Safety
+///
+/// `bytes` must either have null data or be a buffer returned by the library
+/// which was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn schemata_bytes_free(bytes: SchemataBytes) {
+    if !bytes.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(bytes.data, bytes.len)));
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use std::slice;
+
+    use rgbstd::containers::Kit;
+
+    use super::*;
+
+    fn message() -> String {
+        unsafe { CStr::from_ptr(schemata_error_message()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn kit() {
+        let mut bytes = SchemataBytes {
+            data: ptr::null_mut(),
+            len: 0,
+        };
+        let status = unsafe { schemata_kit(c"CFA".as_ptr(), &mut bytes) };
+        assert_eq!(status, SchemataStatus::Ok);
+        assert_eq!(message(), "");
+        let data = unsafe { slice::from_raw_parts(bytes.data, bytes.len) };
+        assert_eq!(
+            Kit::load(data).unwrap().kit_id(),
+            BundledSchema::CollectibleFungibleAsset.kit().kit_id()
+        );
+        unsafe { schemata_bytes_free(bytes) };
+
+        let mut armored = ptr::null_mut();
+        let status = unsafe { schemata_kit_armored(c"RGB20".as_ptr(), &mut armored) };
+        assert_eq!(status, SchemataStatus::UnknownSchema);
+        assert_eq!(message(), "unknown bundled schema name 'RGB20'");
+        assert!(armored.is_null());
+    }
+
+    #[test]
+    fn mismatch() {
+        let (mut id, mut contract) = (ptr::null_mut(), ptr::null_mut());
+        let status =
+            unsafe { schemata_issue_cfa(cr#"{"schema": "NIA"}"#.as_ptr(), &mut id, &mut contract) };
+        assert_eq!(status, SchemataStatus::SchemaMismatch);
+        let status = unsafe { schemata_issue_cfa(c"[]".as_ptr(), &mut id, &mut contract) };
+        assert_eq!(status, SchemataStatus::InvalidParams);
+        let status = unsafe { schemata_issue_cfa(ptr::null(), &mut id, &mut contract) };
+        assert_eq!(status, SchemataStatus::NullArgument);
+        assert!(id.is_null() && contract.is_null());
+    }
+}
@@ -0,0 +1,94 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Golden vectors pinning identifiers of the bundled schemata and of the
+//! contracts issued under them, stored in `test/golden-vectors.yaml`.
+//!
+//! A mismatch means that the change breaks all contracts previously issued
+//! under the schema. If this is intended, the vectors must be regenerated
+//! with
+//!
+//! ```console
+//! $ RGB_SCHEMATA_BLESS=1 cargo test --all-features golden
+//! ```
+
+use std::collections::BTreeMap;
+use std::{env, fs};
+
+use rgbstd::containers::ConsignmentExt;
+
+use crate::testkit::sample_contract;
+use crate::BundledSchema;
+
+const GOLDEN_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test/golden-vectors.yaml");
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoldenVectors {
+    schema_id: String,
+    iimpl_id: String,
+    lib_ids: Vec<String>,
+    type_system_id: String,
+    sample_contract_id: String,
+}
+
+impl GoldenVectors {
+    fn with(schema: BundledSchema) -> Self {
+        GoldenVectors {
+            schema_id: schema.schema().schema_id().to_string(),
+            iimpl_id: schema.issue_impl().impl_id().to_string(),
+            lib_ids: schema.scripts().keys().map(|id| id.to_string()).collect(),
+            type_system_id: schema.types().id().to_string(),
+            sample_contract_id: sample_contract(schema).contract_id().to_string(),
+        }
+    }
+}
+
+#[test]
+fn golden_vectors() {
+    let current = BundledSchema::ALL
+        .into_iter()
+        .map(|schema| (schema, GoldenVectors::with(schema)))
+        .collect::<BTreeMap<_, _>>();
+
+    if env::var_os("RGB_SCHEMATA_BLESS").is_some() {
+        let yaml = serde_yaml::to_string(&current).unwrap();
+        fs::write(GOLDEN_FILE, yaml).unwrap();
+        return;
+    }
+
+    let golden = fs::read_to_string(GOLDEN_FILE).expect("golden vectors are absent");
+    let golden: BTreeMap<BundledSchema, GoldenVectors> = serde_yaml::from_str(&golden).unwrap();
+    for (schema, vectors) in current {
+        let Some(expected) = golden.get(&schema) else {
+            panic!("no golden vectors for {schema} schema");
+        };
+        if &vectors != expected {
+            panic!(
+                "{schema} schema doesn't match golden vectors, which invalidates all contracts \
+                 previously issued under it.\nExpected: {expected:#?}\nFound: {vectors:#?}\nIf \
+                 this change is intended, regenerate vectors with `RGB_SCHEMATA_BLESS=1 cargo \
+                 test --all-features golden`"
+            );
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! History of the schema definitions shipped by the releases of this crate,
+//! allowing to read contracts issued under the legacy schema versions.
+//!
+//! Each time a release changes a schema id or its interface implementation,
+//! kit of the new definition must be snapshotted into `schemata/history` and
+//! registered in `HISTORY`; otherwise the test suite fails. Entries are added
+//! only when a release is cut: schemata which were never shipped by a release
+//! have no history. Snapshots of the published releases are immutable, which
+//! is enforced by pinning their SHA-256 digests.
+//!
+//! Each snapshot is a byte copy of `schemata/<file>` from the `.crate` file of
+//! the release published on crates.io, which can be verified against the
+//! crate checksums in `RELEASES` and the kit digests in `HISTORY`.
+//!
+//! The history starts with 0.11.0-beta.7, the release which has first shipped
+//! the current NIA, UDA and CFA definitions; 0.11.0-beta.8 and 0.11.0-beta.9
+//! shipped kits with the same digests. Schemata of the earlier releases are not listed:
+//! they were built with rgb-std versions having different consensus encoding
+//! of schemata and contracts (up to 0.11.0-beta.5 schemata even had no kits),
+//! so neither their definitions can be decoded nor contracts issued under
+//! them can be validated by the rgb-std version used by this crate.
+
+use std::str::FromStr;
+
+use rgbstd::containers::{FileContent, Kit};
+use rgbstd::SchemaId;
+use sha2::{Digest, Sha256};
+use strict_types::TypeSystem;
+
+use crate::{BundledSchema, SchemaRelease};
+
+/// Crate releases published on crates.io since the start of the history,
+/// with SHA-256 checksums of their `.crate` files as recorded by the crates.io
+/// index.
+const RELEASES: &[(&str, &str)] = &[
+    ("0.11.0-beta.7", "4ac9426cdf865a06a1addd5e686c7e6388b70986ee48cf0282304e67436c638b"),
+    ("0.11.0-beta.8", "61aa7e109d0b8a73ff900c0af1cf256a9c4b14ded46fd3aa47540f461f0aa05d"),
+    ("0.11.0-beta.9", "839dfc30f8acd41b9b3d637e69be6c846f216472e2f8a4818118acd7d0bca3b7"),
+];
+
+/// Kit snapshot of a schema definition shipped by a crate release.
+struct HistoryEntry {
+    schema: BundledSchema,
+    release: &'static str,
+    schema_id: &'static str,
+    /// Hex-encoded SHA-256 digest of the kit file.
+    digest: &'static str,
+    kit: &'static [u8],
+}
+
+#[cfg(any(feature = "nia", feature = "uda", feature = "cfa"))]
+macro_rules! entry {
+    ($schema:ident, $release:literal, $file:literal, $id:literal, $digest:literal) => {
+        HistoryEntry {
+            schema: BundledSchema::$schema,
+            release: $release,
+            schema_id: $id,
+            digest: $digest,
+            kit: include_bytes!(concat!("../schemata/history/", $release, "/", $file)),
+        }
+    };
+}
+
+/// All historical schema definitions, ordered by release.
+const HISTORY: &[HistoryEntry] = &[
+    #[cfg(feature = "nia")]
+    entry!(
+        NonInflatableAsset,
+        "0.11.0-beta.7",
+        "NonInflatableAssets.rgb",
+        "rgb:sch:RDYhMTR!9gv8Y2GLv9UNBEK1hcrCmdLDFk9Qd5fnO8k#brave-dinner-banana",
+        "1e05033856ea8c5dbec7fff62eccc82341804b4db83f4b0161b38659ba317e30"
+    ),
+    #[cfg(feature = "uda")]
+    entry!(
+        UniqueDigitalAsset,
+        "0.11.0-beta.7",
+        "UniqueDigitalAsset.rgb",
+        "rgb:sch:$$bAmeZTo5kK3RJHgeUr06qG86vQ0ozgtug7Yi9zdZo#korea-trumpet-dexter",
+        "433ce297535d58b093e13d36b7c191185686fbd7fc8f739c18e8bda48d636e1b"
+    ),
+    #[cfg(feature = "cfa")]
+    entry!(
+        CollectibleFungibleAsset,
+        "0.11.0-beta.7",
+        "CollectibleFungibleAsset.rgb",
+        "rgb:sch:cJjPZfUpkOqIWhpCTqYJtFYzLfz$AB3JNxIEOJZYn28#circus-version-silence",
+        "82d1038a4498c5ff545e735dd336602b68376d0feb84a42d23fee13765314bec"
+    ),
+];
+
+/// Schema definition shipped by one of the crate releases.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SchemaVersion {
+    pub schema: BundledSchema,
+    /// Crate release which has first shipped the definition.
+    pub release: &'static str,
+    /// SHA-256 checksum of the published `.crate` file of the release.
+    pub crate_checksum: &'static str,
+    pub schema_id: SchemaId,
+    /// Schema together with its compatible interface implementations and
+    /// validation scripts.
+    pub definition: SchemaRelease,
+    pub types: TypeSystem,
+}
+
+impl HistoryEntry {
+    fn schema_id(&self) -> SchemaId {
+        SchemaId::from_str(self.schema_id).expect("invalid schema id in the history")
+    }
+
+    fn crate_checksum(&self) -> &'static str {
+        RELEASES
+            .iter()
+            .find(|(release, _)| *release == self.release)
+            .map(|(_, checksum)| *checksum)
+            .expect("schema history entry from an unknown release")
+    }
+
+    fn digest(&self) -> String {
+        Sha256::digest(self.kit)
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    fn load(&self) -> SchemaVersion {
+        assert_eq!(self.digest(), self.digest, "corrupted kit in the schema history");
+        let kit = Kit::load(self.kit).expect("invalid kit in the schema history");
+        let types = kit.types.clone();
+        let definition =
+            SchemaRelease::with_kit(kit).expect("kit in the schema history has no single schema");
+        SchemaVersion {
+            schema: self.schema,
+            release: self.release,
+            crate_checksum: self.crate_checksum(),
+            schema_id: self.schema_id(),
+            definition,
+            types,
+        }
+    }
+}
+
+impl BundledSchema {
+    /// Ids of the schema definitions shipped by the crate releases, from the
+    /// oldest to the latest one, together with the version of the release
+    /// which has first shipped them. Empty for schemata not released yet.
+    pub fn released_ids(self) -> Vec<(&'static str, SchemaId)> {
+        HISTORY
+            .iter()
+            .filter(|entry| entry.schema == self)
+            .map(|entry| (entry.release, entry.schema_id()))
+            .collect()
+    }
+
+    /// Schema definitions shipped by the crate releases, from the oldest to
+    /// the latest one.
+    pub fn history(self) -> Vec<SchemaVersion> {
+        HISTORY
+            .iter()
+            .filter(|entry| entry.schema == self)
+            .map(HistoryEntry::load)
+            .collect()
+    }
+}
+
+/// Finds historical definition of one of the bundled schemata with the given
+/// id, as used by a contract issued under some previous crate release.
+pub fn historic_schema(schema_id: SchemaId) -> Option<SchemaVersion> {
+    HISTORY
+        .iter()
+        .find(|entry| entry.schema_id() == schema_id)
+        .map(HistoryEntry::load)
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// Schemata not shipped by any release yet, which thus must not have
+    /// history entries.
+    const UNRELEASED: [BundledSchema; 2] =
+        [BundledSchema::LightningFungibleAsset, BundledSchema::AssetSwapOffer];
+
+    #[test]
+    fn immutable() {
+        for entry in HISTORY {
+            assert_eq!(
+                entry.digest(),
+                entry.digest,
+                "kit of {} from the published {} release was modified; snapshots of released \
+                 schemata must never change",
+                entry.schema,
+                entry.release
+            );
+        }
+    }
+
+    #[test]
+    fn snapshots() {
+        for entry in HISTORY {
+            assert_eq!(entry.crate_checksum().len(), 64);
+            let version = entry.load();
+            assert_eq!(
+                version.definition.schema.schema_id(),
+                version.schema_id,
+                "kit of {} from {} has a wrong schema id",
+                entry.schema,
+                entry.release
+            );
+            assert!(!version.definition.iimpls.is_empty());
+            assert!(!version.definition.scripts.is_empty());
+        }
+        let ids = HISTORY
+            .iter()
+            .map(HistoryEntry::schema_id)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(ids.len(), HISTORY.len(), "duplicated schema history entries");
+    }
+
+    #[test]
+    fn current() {
+        for schema in BundledSchema::ALL {
+            if UNRELEASED.contains(&schema) {
+                assert!(schema.released_ids().is_empty(), "{schema} was not released yet");
+                continue;
+            }
+            let schema_id = schema.schema().schema_id();
+            let (release, _) = *schema.released_ids().last().unwrap();
+            let version = historic_schema(schema_id).unwrap_or_else(|| {
+                panic!(
+                    "{schema} id {schema_id} is absent from the schema history; snapshot its kit \
+                     from the published crate into `schemata/history/{}` and register it together \
+                     with the crate checksum",
+                    env!("CARGO_PKG_VERSION")
+                )
+            });
+            assert_eq!(version.release, release, "{schema} id is not the latest in the history");
+            assert_eq!(version.schema, schema);
+            assert!(
+                version.definition.iimpls.contains(&schema.issue_impl()),
+                "{schema} interface implementation is absent from the schema history"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        let mut schema = BundledSchema::NonInflatableAsset.schema();
+        schema.timestamp += 1;
+        assert_eq!(historic_schema(schema.schema_id()), None);
+    }
+}
@@ -0,0 +1,808 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inspection of contracts issued under the bundled schemata, presenting
+//! their state through the interface the schema implements.
+//!
+//! Both contract and transfer consignments can be inspected. Witness
+//! transactions of the state transitions are resolved either with a
+//! user-provided resolver, or from the witness transactions embedded into the
+//! consignment itself, in which case the commitments are checked, but not
+//! whether the transactions are mined.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(feature = "uda")]
+use amplify::Wrapper;
+use chrono::DateTime;
+use ifaces::rgb21::TokenData;
+#[cfg(any(feature = "nia", feature = "lfa", feature = "aso"))]
+use ifaces::Rgb20;
+#[cfg(feature = "uda")]
+use ifaces::Rgb21;
+#[cfg(feature = "cfa")]
+use ifaces::Rgb25;
+use rgbstd::containers::{
+    Consignment, ConsignmentExt, ConsignmentParseError, Contract, LoadError, ToWitnessId, Transfer,
+    UniversalFile, ValidContract,
+};
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use rgbstd::interface::FilterIncludeAll;
+use rgbstd::interface::FungibleAllocation;
+use rgbstd::invoice::{Amount, Precision};
+use rgbstd::persistence::Stock;
+use rgbstd::stl::{AssetSpec, ContractTerms, Details, Name};
+use rgbstd::validation::{ResolveWitness, WitnessResolverError};
+use rgbstd::vm::{WitnessOrd, XWitnessTx};
+use rgbstd::{validation, Allocation, ContractId, Identity, SchemaId, XOutputSeal, XWitnessId};
+#[cfg(any(feature = "uda", feature = "aso"))]
+use strict_encoding::StrictDeserialize;
+
+use crate::{BundledSchema, OfferFill, OfferTerms};
+
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum InspectError {
+    #[from]
+    #[from(std::io::Error)]
+    #[display(inner)]
+    Load(LoadError),
+
+    #[from]
+    #[display(inner)]
+    Armor(ConsignmentParseError),
+
+    /// the file contains a kit and not a contract.
+    Kit,
+
+    /// contract uses schema {0}, which is not one of the bundled schemata.
+    UnknownSchema(SchemaId),
+
+    /// invalid contract.
+    ///
+    /// {0}
+    Invalid(validation::Status),
+}
+
+/// Interface-level view of a contract issued under one of the bundled
+/// schemata.
+#[derive(Clone, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractReport {
+    pub contract_id: ContractId,
+    pub schema: BundledSchema,
+    pub schema_id: SchemaId,
+    pub issuer: Identity,
+    pub issued_at: i64,
+    pub testnet: bool,
+    #[serde(flatten)]
+    pub state: InterfaceState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer: Option<OfferState>,
+}
+
+#[derive(Clone, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase", tag = "interface")]
+pub enum InterfaceState {
+    #[serde(rename = "RGB20", rename_all = "camelCase")]
+    Rgb20 {
+        spec: AssetSpec,
+        terms: ContractTerms,
+        issued_supply: Amount,
+        allocations: Vec<FungibleAllocation>,
+    },
+
+    #[serde(rename = "RGB21", rename_all = "camelCase")]
+    Rgb21 {
+        spec: AssetSpec,
+        terms: ContractTerms,
+        token: Box<TokenData>,
+        allocations: Vec<TokenAllocation>,
+    },
+
+    #[serde(rename = "RGB25", rename_all = "camelCase")]
+    Rgb25 {
+        name: Name,
+        details: Option<Details>,
+        precision: Precision,
+        terms: ContractTerms,
+        issued_supply: Amount,
+        allocations: Vec<FungibleAllocation>,
+    },
+}
+
+/// State of an asset swap offer: its terms and the fills recorded by the
+/// takers known to the consignment.
+#[derive(Clone, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferState {
+    pub terms: OfferTerms,
+    pub fills: Vec<OfferFill>,
+}
+
+#[derive(Clone, Debug)]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAllocation {
+    pub seal: XOutputSeal,
+    pub allocation: Allocation,
+    pub witness: Option<XWitnessId>,
+}
+
+/// Witness resolver using the witness transactions embedded into a
+/// consignment.
+///
+/// The resolver doesn't have access to a blockchain, so all the known witness
+/// transactions are reported as tentative, and the witnesses provided by their
+/// ids only can't be resolved.
+#[derive(Clone, Debug, Default)]
+pub struct EmbeddedWitnesses(BTreeMap<XWitnessId, XWitnessTx>);
+
+impl EmbeddedWitnesses {
+    pub fn with<const TRANSFER: bool>(consignment: &Consignment<TRANSFER>) -> Self {
+        Self(
+            consignment
+                .bundles
+                .iter()
+                .filter_map(|bundle| {
+                    let tx = bundle
+                        .pub_witness
+                        .maybe_map_ref(|witness| witness.tx().cloned())?;
+                    Some((bundle.pub_witness.to_witness_id(), tx))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ResolveWitness for EmbeddedWitnesses {
+    fn resolve_pub_witness(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<XWitnessTx, WitnessResolverError> {
+        self.0
+            .get(&witness_id)
+            .cloned()
+            .ok_or(WitnessResolverError::Unknown(witness_id))
+    }
+
+    fn resolve_pub_witness_ord(
+        &self,
+        witness_id: XWitnessId,
+    ) -> Result<WitnessOrd, WitnessResolverError> {
+        match self.0.contains_key(&witness_id) {
+            true => Ok(WitnessOrd::Tentative),
+            false => Err(WitnessResolverError::Unknown(witness_id)),
+        }
+    }
+}
+
+impl ContractReport {
+    /// Reads contract or transfer consignment from a file, which may be either
+    /// binary or ASCII-armored, and reports its state.
+    ///
+    /// Witness transactions are resolved with [`EmbeddedWitnesses`].
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, InspectError> {
+        Self::load(&fs::read(path)?)
+    }
+
+    /// Reads binary or ASCII-armored contract or transfer consignment and
+    /// reports its state.
+    ///
+    /// Witness transactions are resolved with [`EmbeddedWitnesses`].
+    pub fn load(data: &[u8]) -> Result<Self, InspectError> {
+        let contract = match data.starts_with(b"-----BEGIN") {
+            true => {
+                let armored = String::from_utf8_lossy(data);
+                match Contract::from_str(&armored) {
+                    Err(ConsignmentParseError::Type) => {
+                        Transfer::from_str(&armored)?.into_contract()
+                    }
+                    res => res?,
+                }
+            }
+            false => match UniversalFile::load(data)? {
+                UniversalFile::Contract(contract) => contract,
+                UniversalFile::Transfer(transfer) => transfer.into_contract(),
+                UniversalFile::Kit(_) => return Err(InspectError::Kit),
+            },
+        };
+        Self::with(contract)
+    }
+
+    /// Validates the contract, resolving its witness transactions with
+    /// [`EmbeddedWitnesses`], and reports its state.
+    pub fn with(contract: Contract) -> Result<Self, InspectError> {
+        let resolver = EmbeddedWitnesses::with(&contract);
+        Self::with_resolver(contract, resolver)
+    }
+
+    /// Validates contract or transfer consignment using the provided witness
+    /// resolver and reports its state.
+    pub fn with_resolver<const TRANSFER: bool>(
+        consignment: Consignment<TRANSFER>,
+        resolver: impl ResolveWitness,
+    ) -> Result<Self, InspectError> {
+        let contract = consignment.into_contract();
+        let testnet = contract.genesis.testnet;
+        let contract = contract
+            .validate(&resolver, testnet)
+            .map_err(|(status, _)| InspectError::Invalid(status))?;
+        Self::with_valid_resolved(contract, resolver)
+    }
+
+    /// Reports state of an already validated contract.
+    ///
+    /// Witness transactions are resolved with [`EmbeddedWitnesses`].
+    pub fn with_valid(contract: ValidContract) -> Result<Self, InspectError> {
+        let resolver = EmbeddedWitnesses::with(&contract);
+        Self::with_valid_resolved(contract, resolver)
+    }
+
+    fn with_valid_resolved(
+        contract: ValidContract,
+        resolver: impl ResolveWitness,
+    ) -> Result<Self, InspectError> {
+        let schema_id = contract.schema_id();
+        let schema = BundledSchema::from_schema_id(schema_id)
+            .ok_or(InspectError::UnknownSchema(schema_id))?;
+        let contract_id = contract.contract_id();
+        let genesis = contract.genesis();
+        let issuer = genesis.issuer.clone();
+        let issued_at = genesis.timestamp;
+        let testnet = genesis.testnet;
+        let offer = OfferState::with(schema, &contract);
+
+        let mut stock = Stock::in_memory();
+        stock
+            .import_contract(contract, resolver)
+            .expect("in-memory stock import of a valid contract");
+
+        let state = InterfaceState::with(schema, &stock, contract_id);
+
+        Ok(ContractReport {
+            contract_id,
+            schema,
+            schema_id,
+            issuer,
+            issued_at,
+            testnet,
+            state,
+            offer,
+        })
+    }
+}
+
+impl InterfaceState {
+    #[cfg(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    ))]
+    fn with(schema: BundledSchema, stock: &Stock, contract_id: ContractId) -> Self {
+        match schema {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => {
+                let contract = stock
+                    .contract_iface_class::<Rgb20>(contract_id)
+                    .expect("imported contract");
+                InterfaceState::Rgb20 {
+                    spec: contract.spec(),
+                    terms: contract.contract_terms(),
+                    issued_supply: contract.total_issued_supply(),
+                    allocations: contract.allocations(&FilterIncludeAll).collect(),
+                }
+            }
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => {
+                let contract = stock
+                    .contract_iface_class::<Rgb21>(contract_id)
+                    .expect("imported contract");
+                InterfaceState::Rgb21 {
+                    spec: contract.spec(),
+                    terms: contract.contract_terms(),
+                    token: Box::new(contract.token_data()),
+                    allocations: contract
+                        .allocations(&FilterIncludeAll)
+                        .map(|alloc| TokenAllocation {
+                            seal: alloc.seal,
+                            allocation: Allocation::from_strict_serialized(alloc.state.to_inner())
+                                .expect("RGB21 allocation"),
+                            witness: alloc.witness,
+                        })
+                        .collect(),
+                }
+            }
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => {
+                let contract = stock
+                    .contract_iface_class::<Rgb25>(contract_id)
+                    .expect("imported contract");
+                InterfaceState::Rgb25 {
+                    name: contract.name(),
+                    details: contract.details(),
+                    precision: contract.precision(),
+                    terms: contract.contract_terms(),
+                    issued_supply: contract.total_issued_supply(),
+                    allocations: contract.allocations(&FilterIncludeAll).collect(),
+                }
+            }
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => {
+                let contract = stock
+                    .contract_iface_class::<Rgb20>(contract_id)
+                    .expect("imported contract");
+                InterfaceState::Rgb20 {
+                    spec: contract.spec(),
+                    terms: contract.contract_terms(),
+                    issued_supply: contract.total_issued_supply(),
+                    allocations: contract.allocations(&FilterIncludeAll).collect(),
+                }
+            }
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => {
+                let contract = stock
+                    .contract_iface_class::<Rgb20>(contract_id)
+                    .expect("imported contract");
+                InterfaceState::Rgb20 {
+                    spec: contract.spec(),
+                    terms: contract.contract_terms(),
+                    issued_supply: contract.total_issued_supply(),
+                    allocations: contract.allocations(&FilterIncludeAll).collect(),
+                }
+            }
+        }
+    }
+
+    #[cfg(not(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    )))]
+    fn with(schema: BundledSchema, _: &Stock, _: ContractId) -> Self { match schema {} }
+}
+
+impl OfferState {
+    #[cfg(feature = "aso")]
+    fn with(schema: BundledSchema, contract: &Contract) -> Option<Self> {
+        use amplify::confinement::{Confined, U16};
+        use rgbstd::DataState;
+
+        use crate::{GS_FILL, GS_OFFER, TS_TAKE};
+
+        if schema != BundledSchema::AssetSwapOffer {
+            return None;
+        }
+        fn decode<T: StrictDeserialize>(data: &DataState) -> T {
+            let data: &[u8] = data.as_ref();
+            T::from_strict_serialized::<U16>(Confined::from_checked(data.to_vec()))
+                .expect("validated global state")
+        }
+
+        let terms = decode(contract.genesis.globals.get(&GS_OFFER)?.first()?);
+        let fills = contract
+            .bundles
+            .iter()
+            .flat_map(|bundle| bundle.anchored_bundles.bundles())
+            .flat_map(|bundle| bundle.known_transitions.values())
+            .filter(|transition| transition.transition_type == TS_TAKE)
+            .filter_map(|transition| transition.globals.get(&GS_FILL))
+            .flat_map(|fills| fills.iter().map(decode))
+            .collect();
+        Some(OfferState { terms, fills })
+    }
+
+    #[cfg(not(feature = "aso"))]
+    fn with(_: BundledSchema, _: &Contract) -> Option<Self> { None }
+}
+
+impl Display for ContractReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Contract:  {}", self.contract_id)?;
+        writeln!(f, "Schema:    {} ({})", self.schema, self.schema_id)?;
+        writeln!(f, "Issuer:    {}", self.issuer)?;
+        match DateTime::from_timestamp(self.issued_at, 0) {
+            Some(time) => writeln!(f, "Issued at: {}", time.to_rfc3339())?,
+            None => writeln!(f, "Issued at: {}", self.issued_at)?,
+        }
+        writeln!(f, "Network:   {}", if self.testnet { "testnet" } else { "mainnet" })?;
+        Display::fmt(&self.state, f)?;
+        if let Some(offer) = &self.offer {
+            Display::fmt(offer, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for OfferState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let counter_asset = ContractId::from(self.terms.counter_asset);
+        writeln!(f, "Offer:     {} of {counter_asset}", self.terms.counter_amount)?;
+        writeln!(f, "Fills:")?;
+        for fill in &self.fills {
+            writeln!(f, "  {} taken for {} paid", fill.taken, fill.paid)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for InterfaceState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fn fmt_spec(f: &mut Formatter<'_>, spec: &AssetSpec) -> fmt::Result {
+            writeln!(f, "Ticker:    {}", spec.ticker)?;
+            writeln!(f, "Name:      {}", spec.name)?;
+            if let Some(details) = &spec.details {
+                writeln!(f, "Details:   {details}")?;
+            }
+            writeln!(f, "Precision: {}", spec.precision.decimals())
+        }
+
+        fn fmt_terms(f: &mut Formatter<'_>, terms: &ContractTerms) -> fmt::Result {
+            writeln!(f, "Terms:     {}", terms.text)?;
+            if let Some(media) = &terms.media {
+                writeln!(f, "Media:     {} {}", media.ty, media.digest)?;
+            }
+            Ok(())
+        }
+
+        fn fmt_witness(witness: &Option<XWitnessId>) -> String {
+            witness
+                .as_ref()
+                .map(XWitnessId::to_string)
+                .unwrap_or_else(|| s!("~"))
+        }
+
+        match self {
+            InterfaceState::Rgb20 {
+                spec,
+                terms,
+                issued_supply,
+                allocations,
+            } => {
+                writeln!(f, "Interface: RGB20")?;
+                fmt_spec(f, spec)?;
+                fmt_terms(f, terms)?;
+                writeln!(f, "Supply:    {issued_supply}")?;
+                writeln!(f, "Allocations:")?;
+                for alloc in allocations {
+                    let witness = fmt_witness(&alloc.witness);
+                    writeln!(f, "  {} to {}, witness {witness}", alloc.state, alloc.seal)?;
+                }
+            }
+            InterfaceState::Rgb21 {
+                spec,
+                terms,
+                token,
+                allocations,
+            } => {
+                writeln!(f, "Interface: RGB21")?;
+                fmt_spec(f, spec)?;
+                fmt_terms(f, terms)?;
+                writeln!(f, "Token:     #{}", token.index)?;
+                if let Some(ticker) = &token.ticker {
+                    writeln!(f, "  ticker:  {ticker}")?;
+                }
+                if let Some(name) = &token.name {
+                    writeln!(f, "  name:    {name}")?;
+                }
+                if let Some(details) = &token.details {
+                    writeln!(f, "  details: {details}")?;
+                }
+                if let Some(preview) = &token.preview {
+                    writeln!(f, "  preview: {}, {} bytes", preview.ty, preview.data.len())?;
+                }
+                if let Some(media) = &token.media {
+                    writeln!(f, "  media:   {} {}", media.ty, media.digest)?;
+                }
+                for (no, attachment) in &token.attachments {
+                    writeln!(f, "  attachment #{no}: {} {}", attachment.ty, attachment.digest)?;
+                }
+                writeln!(f, "Allocations:")?;
+                for alloc in allocations {
+                    let witness = fmt_witness(&alloc.witness);
+                    writeln!(
+                        f,
+                        "  token #{}, fraction {} to {}, witness {witness}",
+                        alloc.allocation.token_index(),
+                        alloc.allocation.fraction(),
+                        alloc.seal
+                    )?;
+                }
+            }
+            InterfaceState::Rgb25 {
+                name,
+                details,
+                precision,
+                terms,
+                issued_supply,
+                allocations,
+            } => {
+                writeln!(f, "Interface: RGB25")?;
+                writeln!(f, "Name:      {name}")?;
+                if let Some(details) = details {
+                    writeln!(f, "Details:   {details}")?;
+                }
+                writeln!(f, "Precision: {}", precision.decimals())?;
+                fmt_terms(f, terms)?;
+                writeln!(f, "Supply:    {issued_supply}")?;
+                writeln!(f, "Allocations:")?;
+                for alloc in allocations {
+                    let witness = fmt_witness(&alloc.witness);
+                    writeln!(f, "  {} to {}, witness {witness}", alloc.state, alloc.seal)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use amplify::confinement::{Confined, NonEmptyOrdMap};
+    use bp::dbc::opret::OpretProof;
+    use bp::dbc::{Anchor, Method};
+    use bp::seals::txout::{CloseMethod, ExplicitSeal};
+    use bp::{LockTime, Outpoint, Sats, ScriptPubkey, SeqNo, Tx, TxIn, TxOut, TxVer, Txid, Vout};
+    use commit_verify::mpc::{self, MerkleBlock, MerkleTree, MultiSource};
+    use commit_verify::{CommitId, EmbedCommitVerify, TryCommitVerify};
+    use rgbstd::containers::{AnchorSet, BuilderSeal, BundleDichotomy, Fascia, PubWitness};
+    use rgbstd::interface::IfaceClass;
+    use rgbstd::{GraphSeal, InputMap, Operation, TransitionBundle, XChain, XOutputSeal};
+
+    use super::*;
+    use crate::dumb::NoResolver;
+    use crate::{AssetSwapOffer, NonInflatableAsset};
+
+    const TXID: &str = "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5";
+
+    fn prevout() -> Outpoint { Outpoint::new(Txid::from_str(TXID).unwrap(), 0) }
+
+    /// Transfers the whole supply of an RGB20 contract allocated to
+    /// [`prevout`] to the second output of a witness transaction, which
+    /// commits to the state transition with an opret commitment. Transitions
+    /// other than `transfer` are provided with the offer `fill`.
+    fn transfer(
+        contract: ValidContract,
+        transition: &'static str,
+        fill: Option<OfferFill>,
+    ) -> (Transfer, EmbeddedWitnesses) {
+        let prevout = prevout();
+        let contract_id = contract.contract_id();
+        let mut stock = Stock::in_memory();
+        stock.import_contract(contract, NoResolver).unwrap();
+
+        let mut builder = stock
+            .transition_builder(contract_id, Rgb20::FIXED.iface().iface_id(), Some(transition))
+            .unwrap();
+        for (opout, state) in stock
+            .contract_assignments_for(contract_id, [XChain::Bitcoin(prevout)])
+            .unwrap()
+            .into_values()
+            .flatten()
+        {
+            builder = builder.add_input(opout, state).unwrap();
+        }
+        if let Some(fill) = fill {
+            builder = builder.add_global_state("fill", fill).unwrap();
+        }
+        let seal = GraphSeal::new_random_vout(Method::OpretFirst, 1);
+        let transition = builder
+            .add_fungible_state("assetOwner", BuilderSeal::from(XChain::Bitcoin(seal)), 1000u64)
+            .unwrap()
+            .complete_transition()
+            .unwrap();
+        let opid = transition.id();
+        let bundle = TransitionBundle {
+            close_method: CloseMethod::OpretFirst,
+            input_map: InputMap::with(Vout::from_u32(0), opid),
+            known_transitions: Confined::from_checked(bmap! { opid => transition }),
+        };
+
+        let tree = MerkleTree::try_commit(&MultiSource {
+            min_depth: mpc::MPC_MINIMAL_DEPTH,
+            messages: Confined::from_checked(bmap! {
+                mpc::ProtocolId::from(contract_id) => mpc::Message::from(bundle.bundle_id()),
+            }),
+            static_entropy: Some(0),
+        })
+        .unwrap();
+        let mut tx = Tx {
+            version: TxVer::V2,
+            inputs: Confined::from_checked(vec![TxIn {
+                prev_output: prevout,
+                sig_script: none!(),
+                sequence: SeqNo::ZERO,
+                witness: none!(),
+            }]),
+            outputs: Confined::from_checked(vec![
+                TxOut {
+                    value: Sats::ZERO,
+                    script_pubkey: ScriptPubkey::from_unsafe(vec![0x6a]),
+                },
+                TxOut {
+                    value: Sats::from(1000u64),
+                    script_pubkey: none!(),
+                },
+            ]),
+            lock_time: LockTime::ZERO,
+        };
+        let proof: OpretProof = tx.embed_commit(&tree.commit_id()).unwrap();
+        let txid = tx.txid();
+        let fascia = Fascia {
+            witness: XChain::Bitcoin(PubWitness::with(tx.clone())),
+            anchor: AnchorSet::Opret(Anchor::new(MerkleBlock::from(tree), proof)),
+            bundles: NonEmptyOrdMap::with_key_value(contract_id, BundleDichotomy::single(bundle)),
+        };
+        let witnesses = EmbeddedWitnesses(bmap! { XChain::Bitcoin(txid) => XChain::Bitcoin(tx) });
+        stock.consume_fascia(fascia, &witnesses).unwrap();
+
+        let output =
+            XOutputSeal::Bitcoin(ExplicitSeal::new(Method::OpretFirst, Outpoint::new(txid, 1)));
+        (stock.transfer(contract_id, [output], None).unwrap(), witnesses)
+    }
+
+    #[test]
+    fn examples() {
+        for (file, schema) in [
+            ("rgb20-example", BundledSchema::NonInflatableAsset),
+            ("rgb21-example", BundledSchema::UniqueDigitalAsset),
+            ("rgb25-example", BundledSchema::CollectibleFungibleAsset),
+        ] {
+            for ext in ["rgb", "rgba"] {
+                let path = format!("{}/test/{file}.{ext}", env!("CARGO_MANIFEST_DIR"));
+                let report = ContractReport::load_file(&path).unwrap();
+                assert_eq!(report.schema, schema);
+                assert_eq!(report.schema_id, schema.schema().schema_id());
+            }
+        }
+    }
+
+    #[test]
+    fn rgb20_json() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test/rgb20-example.rgb");
+        let report = ContractReport::load_file(path).unwrap();
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["interface"], "RGB20");
+        assert_eq!(json["schema"], "NonInflatableAsset");
+        assert_eq!(json["spec"]["ticker"], "TEST");
+        assert_eq!(json["allocations"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn transfers() {
+        let contract = NonInflatableAsset::testnet(
+            "ssi:anonymous",
+            "TEST",
+            "Test asset",
+            None,
+            Precision::Indivisible,
+            [(Method::OpretFirst, prevout(), 1000u64)],
+        )
+        .unwrap();
+        let (transfer, witnesses) = transfer(contract, "transfer", None);
+        assert!(!transfer.bundles.is_empty());
+
+        let report = ContractReport::with_resolver(transfer.clone(), witnesses).unwrap();
+        let InterfaceState::Rgb20 { allocations, .. } = &report.state else {
+            panic!("NIA must be reported through RGB20");
+        };
+        let transferred = allocations
+            .iter()
+            .find(|alloc| alloc.witness.is_some())
+            .expect("transferred allocation");
+        assert_eq!(transferred.state, Amount::from(1000u64));
+        assert_eq!(transferred.seal.as_reduced_unsafe().vout, Vout::from_u32(1));
+
+        let armored = ContractReport::load(transfer.to_string().as_bytes()).unwrap();
+        assert_eq!(armored.contract_id, report.contract_id);
+
+        let mut unresolved = transfer;
+        unresolved.bundles = Confined::from_checked(
+            unresolved
+                .bundles
+                .into_iter()
+                .map(|mut bundle| {
+                    bundle.pub_witness = bundle
+                        .pub_witness
+                        .map(|witness| PubWitness::new(witness.txid()));
+                    bundle
+                })
+                .collect(),
+        );
+        assert!(matches!(ContractReport::load(unresolved.to_string().as_bytes()),
+            Err(InspectError::Invalid(status)) if !status.failures.is_empty()));
+    }
+
+    #[test]
+    fn offer() {
+        let terms = OfferTerms::new(ContractId::from([0x11; 32]), 500u64);
+        let contract = AssetSwapOffer::testnet(
+            "ssi:anonymous",
+            "SWAP",
+            "Offered asset",
+            None,
+            Precision::Indivisible,
+            ContractTerms {
+                text: none!(),
+                media: None,
+            },
+            terms,
+            [(Method::OpretFirst, prevout(), 1000u64)],
+        )
+        .unwrap();
+
+        let report = ContractReport::with_valid(contract.clone()).unwrap();
+        let offer = report
+            .offer
+            .as_ref()
+            .expect("ASO report must contain offer");
+        assert_eq!(offer.terms, terms);
+        assert!(offer.fills.is_empty());
+
+        let fill = OfferFill {
+            taken: 1000,
+            paid: 500,
+        };
+        let (transfer, _) = transfer(contract, AssetSwapOffer::TAKE, Some(fill));
+        let report = ContractReport::load(transfer.to_string().as_bytes()).unwrap();
+        assert_eq!(report.offer.as_ref().unwrap().fills, vec![fill]);
+        assert!(report.to_string().contains("1000 taken for 500 paid"));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["offer"]["fills"][0]["paid"], 500);
+
+        let report = ContractReport::load_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/test/rgb20-example.rgb"
+        ))
+        .unwrap();
+        assert!(report.offer.is_none());
+    }
+
+    #[test]
+    fn kit() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/schemata/NonInflatableAssets.rgb");
+        assert!(matches!(ContractReport::load_file(path), Err(InspectError::Kit)));
+    }
+}
@@ -0,0 +1,401 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lightning Fungible Assets (LFA) schema implementing RGB20 fungible assets
+//! interface, which is suitable for routing assets over Lightning channels.
+//!
+//! State transfers of the schema tag each of the asset allocations with the
+//! role it plays in a channel transaction: balance of the local or remote node
+//! or an HTLC. The validation library of the schema checks that each of the
+//! asset outputs of a channel transfer is tagged and validates the amounts with
+//! `pcvs`, like the NIA library does; genesis is validated with the NIA
+//! library. Matching the tags with the outputs of the channel transactions is
+//! up to the channel implementation.
+
+use aluvm::isa::opcodes::INSTR_PUTA;
+use aluvm::library::{Lib, LibSite};
+use amplify::confinement::{Confined, U16};
+use bp::dbc::Method;
+use ifaces::{IssuerWrapper, Rgb20, Rgb20Wrapper};
+use rgbstd::containers::ValidContract;
+use rgbstd::interface::{IfaceClass, IfaceImpl, NamedField, NamedVariant, TxOutpoint, VerNo};
+use rgbstd::persistence::MemContract;
+use rgbstd::schema::{
+    FungibleType, GenesisSchema, GlobalStateSchema, Occurrences, OwnedStateSchema, Schema,
+    TransitionSchema,
+};
+use rgbstd::stl::StandardTypes;
+use rgbstd::validation::Scripts;
+use rgbstd::vm::opcodes::INSTR_PCVS;
+use rgbstd::{Amount, AssetTag, BlindingFactor, MetaType, MetadataError, Precision, Transition};
+use strict_encoding::StrictSerialize;
+use strict_types::TypeSystem;
+
+use crate::asm::{schemata_asm, Asm};
+use crate::nia::{issuer_identity, nia_lib, FungibleIssueError, FN_NIA_GENESIS_OFFSET};
+use crate::stl::{schemata_stl, schemata_types};
+use crate::{
+    ChannelOutputs, SchemaParams, ERRNO_CHANNEL_OUTPUTS_MISMATCH, ERRNO_ISSUED_MISMATCH,
+    ERRNO_NON_EQUAL_IN_OUT, GS_ISSUED_SUPPLY, GS_NOMINAL, GS_TERMS, OS_ASSET, TS_TRANSFER,
+};
+
+pub(crate) const MT_CHANNEL_OUTPUTS: MetaType = MetaType::with(4100);
+
+pub(crate) fn lfa_asm() -> Asm {
+    schemata_asm! {
+        // SUBROUTINE Transfer validation
+        // Set errno
+        put     a8[0],ERRNO_CHANNEL_OUTPUTS_MISMATCH;
+        // Read channel output tags into s16[0]
+        ldm     MT_CHANNEL_OUTPUTS,s16[0];
+        // Extract the number of the tags from the length prefix of the list into a16[1]
+        put     a16[0],0;
+        extr    s16[0],a16[1],a16[0];
+        // Transfers which don't belong to a channel have no tags
+        eq.e    a16[0],a16[1];
+        jif     FN_LFA_AMOUNTS_OFFSET;
+        // Otherwise, each of the asset outputs must be tagged
+        cns     OS_ASSET,a16[2];
+        eq.e    a16[1],a16[2];
+        test;
+
+        // SUBROUTINE Amounts validation
+        // Set errno
+        put     a8[0],ERRNO_NON_EQUAL_IN_OUT;
+        // Checking that the sum of pedersen commitments in inputs is equal to the sum in outputs.
+        pcvs    OS_ASSET;
+        test;
+        ret;
+    }
+}
+
+pub(crate) fn lfa_lib() -> Lib {
+    Lib::assemble(&lfa_asm().code).expect("wrong lightning fungible asset script")
+}
+pub(crate) const FN_LFA_TRANSFER_OFFSET: u16 = 0;
+pub(crate) const FN_LFA_AMOUNTS_OFFSET: u16 = 4 + 3 + 4 + 4 + 3 + 3 + 4 + 3 + 1;
+
+fn lfa_schema() -> Schema { lfa_schema_with(&SchemaParams::default()) }
+
+pub(crate) fn lfa_schema_with(params: &SchemaParams) -> Schema {
+    let types = StandardTypes::with(schemata_stl());
+
+    let nia_id = nia_lib().id();
+
+    let alu_lib = lfa_lib();
+    let lfa_id = alu_lib.id();
+    let code = alu_lib.code.as_ref();
+    assert_eq!(code[FN_LFA_TRANSFER_OFFSET as usize], INSTR_PUTA);
+    assert_eq!(code[FN_LFA_AMOUNTS_OFFSET as usize], INSTR_PUTA);
+    assert_eq!(code[FN_LFA_AMOUNTS_OFFSET as usize + 4], INSTR_PCVS);
+
+    Schema {
+        ffv: zero!(),
+        flags: none!(),
+        name: params.name_or(tn!("LightningFungibleAsset")),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
+        meta_types: tiny_bmap! {
+            MT_CHANNEL_OUTPUTS => types.get("RGBSchemata.ChannelOutputs"),
+        },
+        global_types: tiny_bmap! {
+            GS_NOMINAL => GlobalStateSchema::once(types.get("RGBContract.AssetSpec")),
+            GS_TERMS => GlobalStateSchema::once(types.get("RGBContract.ContractTerms")),
+            GS_ISSUED_SUPPLY => GlobalStateSchema::once(types.get("RGBContract.Amount")),
+        },
+        owned_types: tiny_bmap! {
+            OS_ASSET => OwnedStateSchema::Fungible(FungibleType::Unsigned64Bit),
+        },
+        valency_types: none!(),
+        genesis: GenesisSchema {
+            metadata: none!(),
+            globals: tiny_bmap! {
+                GS_NOMINAL => Occurrences::Once,
+                GS_TERMS => Occurrences::Once,
+                GS_ISSUED_SUPPLY => Occurrences::Once,
+            },
+            assignments: tiny_bmap! {
+                OS_ASSET => Occurrences::OnceOrMore,
+            },
+            valencies: none!(),
+            validator: Some(LibSite::with(FN_NIA_GENESIS_OFFSET, nia_id)),
+        },
+        extensions: none!(),
+        transitions: tiny_bmap! {
+            TS_TRANSFER => TransitionSchema {
+                metadata: tiny_bset![MT_CHANNEL_OUTPUTS],
+                globals: none!(),
+                inputs: tiny_bmap! {
+                    OS_ASSET => Occurrences::OnceOrMore
+                },
+                assignments: tiny_bmap! {
+                    OS_ASSET => Occurrences::OnceOrMore
+                },
+                valencies: none!(),
+                validator: Some(LibSite::with(FN_LFA_TRANSFER_OFFSET, lfa_id))
+            }
+        },
+        reserved: none!(),
+    }
+}
+
+fn lfa_rgb20() -> IfaceImpl { lfa_rgb20_with(&SchemaParams::default()) }
+
+pub(crate) fn lfa_rgb20_with(params: &SchemaParams) -> IfaceImpl {
+    let schema = lfa_schema_with(params);
+    let iface = Rgb20::FIXED;
+
+    IfaceImpl {
+        version: VerNo::V1,
+        schema_id: schema.schema_id(),
+        iface_id: iface.iface_id(),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
+        metadata: tiny_bset! {
+            NamedField::with(MT_CHANNEL_OUTPUTS, fname!("channelOutputs")),
+        },
+        global_state: tiny_bset! {
+            NamedField::with(GS_NOMINAL, fname!("spec")),
+            NamedField::with(GS_TERMS, fname!("terms")),
+            NamedField::with(GS_ISSUED_SUPPLY, fname!("issuedSupply")),
+        },
+        assignments: tiny_bset! {
+            NamedField::with(OS_ASSET, fname!("assetOwner")),
+        },
+        valencies: none!(),
+        transitions: tiny_bset! {
+            NamedField::with(TS_TRANSFER, fname!("transfer")),
+        },
+        extensions: none!(),
+        errors: tiny_bset![
+            NamedVariant::with(ERRNO_ISSUED_MISMATCH, vname!("issuedMismatch")),
+            NamedVariant::with(ERRNO_NON_EQUAL_IN_OUT, vname!("nonEqualAmounts")),
+        ],
+    }
+}
+
+#[derive(Default)]
+pub struct LightningFungibleAsset;
+
+impl IssuerWrapper for LightningFungibleAsset {
+    const FEATURES: Rgb20 = Rgb20::FIXED;
+    type IssuingIface = Rgb20;
+
+    fn schema() -> Schema { lfa_schema() }
+    fn issue_impl() -> IfaceImpl { lfa_rgb20() }
+
+    fn types() -> TypeSystem { schemata_types(["RGBSchemata.ChannelOutputs"]) }
+
+    fn scripts() -> Scripts {
+        let nia = nia_lib();
+        let lfa = lfa_lib();
+        Confined::from_checked(bmap! { nia.id() => nia, lfa.id() => lfa })
+    }
+}
+
+impl ChannelOutputs {
+    /// Attaches the tags to a completed LFA transfer. The transition builder of
+    /// RGB standard library doesn't put metadata into the completed
+    /// transition, thus the tags are attached to it afterwards. Errors if the
+    /// transfer is already tagged.
+    pub fn attach(&self, transition: &mut Transition) -> Result<(), MetadataError> {
+        let value = self
+            .to_strict_serialized::<U16>()
+            .expect("channel outputs always fit into metadata value");
+        transition
+            .metadata
+            .add_value(MT_CHANNEL_OUTPUTS, value.into())
+    }
+}
+
+impl LightningFungibleAsset {
+    /// Name of the transfer metadata with [`crate::ChannelOutputs`] tags.
+    pub const CHANNEL_OUTPUTS: &'static str = "channelOutputs";
+
+    pub fn testnet(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        allocations: impl IntoIterator<Item = (Method, impl TxOutpoint, impl Into<Amount>)>,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        issuer_identity(issuer)?;
+        let mut issuer =
+            Rgb20Wrapper::<MemContract>::testnet::<Self>(issuer, ticker, name, details, precision)?;
+        for (method, beneficiary, amount) in allocations {
+            issuer = issuer.allocate(method, beneficiary, amount)?;
+        }
+        Ok(issuer.issue_contract().expect("invalid contract data"))
+    }
+
+    /// Issues contract in a deterministic way, such that the same arguments
+    /// always produce the same contract id.
+    ///
+    /// Each allocation is provided with the seal closing method, outpoint and
+    /// blinding, followed by the allocated amount and its blinding factor.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet_det(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        asset_tag: AssetTag,
+        allocations: impl IntoIterator<
+            Item = (Method, impl TxOutpoint, u64, impl Into<Amount>, BlindingFactor),
+        >,
+        timestamp: i64,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        issuer_identity(issuer)?;
+        let mut issuer = Rgb20Wrapper::<MemContract>::testnet_det::<Self>(
+            issuer, ticker, name, details, precision, asset_tag,
+        )?;
+        for (method, beneficiary, seal_blinding, amount, amount_blinding) in allocations {
+            issuer =
+                issuer.allocate_det(method, beneficiary, seal_blinding, amount, amount_blinding)?;
+        }
+        Ok(issuer
+            .issue_contract_det(timestamp)
+            .expect("invalid contract data"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn iimpl_check() {
+        let iface = Rgb20::FIXED.iface();
+        if let Err(err) = lfa_rgb20().check(&iface, &lfa_schema()) {
+            for e in err {
+                eprintln!("{e}");
+            }
+            panic!("invalid LFA RGB20 interface implementation");
+        }
+    }
+
+    #[test]
+    fn types() {
+        let types = LightningFungibleAsset::types();
+        let lib = StandardTypes::with(schemata_stl());
+        assert!(types.get(lib.get("RGBSchemata.ChannelOutputs")).is_some());
+        assert!(types.get(lib.get("RGBSchemata.TransferMemo")).is_none());
+        assert!(types.get(lib.get("RGBSchemata.OfferTerms")).is_none());
+    }
+
+    #[cfg(all(
+        feature = "serde",
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "aso"
+    ))]
+    mod transfer {
+        use amplify::confinement::SmallBlob;
+        use amplify::Bytes32;
+        use rgbstd::validation::Failure;
+        use rgbstd::{OpId, Transition};
+
+        use super::*;
+        use crate::exec::OpValidator;
+        use crate::testkit::{genesis, tag_outputs, transfer};
+        use crate::{BundledSchema, ChannelOutput, ChannelOutputs, HtlcDirection, HtlcOutput};
+
+        const SCHEMA: BundledSchema = BundledSchema::LightningFungibleAsset;
+
+        fn validate(f: impl FnOnce(&mut Transition)) -> (OpId, Vec<Failure>) {
+            let genesis = genesis(SCHEMA);
+            let mut transition = transfer(&genesis, OS_ASSET);
+            f(&mut transition);
+            let mut validator = OpValidator::bundled(SCHEMA, genesis);
+            let opid = validator.add_transition(transition);
+            (opid, validator.validate(opid).failures)
+        }
+
+        #[test]
+        fn local() {
+            let (_, failures) = validate(|transition| tag_outputs(SCHEMA, transition));
+            assert_eq!(failures, vec![]);
+        }
+
+        #[test]
+        fn htlc() {
+            let (_, failures) = validate(|transition| {
+                let htlc = HtlcOutput {
+                    direction: HtlcDirection::Offered,
+                    payment_hash: Bytes32::from_array([0xAB; 32]),
+                    cltv_expiry: 840_000,
+                };
+                let outputs = ChannelOutputs::from_iter_checked([ChannelOutput::Htlc(htlc)]);
+                outputs.attach(transition).unwrap();
+            });
+            assert_eq!(failures, vec![]);
+        }
+
+        #[test]
+        fn untagged() {
+            let (opid, failures) = validate(|_| {});
+            assert_eq!(failures, vec![
+                Failure::SchemaNoMetadata(opid, MT_CHANNEL_OUTPUTS),
+                Failure::ScriptFailure(opid, Some(ERRNO_CHANNEL_OUTPUTS_MISMATCH), None),
+            ]);
+        }
+
+        #[test]
+        fn off_channel() {
+            let (_, failures) = validate(|transition| {
+                ChannelOutputs::default().attach(transition).unwrap();
+            });
+            assert_eq!(failures, vec![]);
+        }
+
+        #[test]
+        fn tag_mismatch() {
+            let (opid, failures) = validate(|transition| {
+                let outputs = ChannelOutputs::from_iter_checked([
+                    ChannelOutput::ToLocal,
+                    ChannelOutput::ToRemote,
+                ]);
+                outputs.attach(transition).unwrap();
+            });
+            assert_eq!(failures, vec![Failure::ScriptFailure(
+                opid,
+                Some(ERRNO_CHANNEL_OUTPUTS_MISMATCH),
+                None
+            )]);
+        }
+
+        #[test]
+        fn malformed() {
+            let (opid, failures) = validate(|transition| {
+                let value = SmallBlob::from_checked(vec![1, 0, 3]);
+                transition
+                    .metadata
+                    .add_value(MT_CHANNEL_OUTPUTS, value.into())
+                    .unwrap();
+            });
+            let sem_id = lfa_schema().meta_types[&MT_CHANNEL_OUTPUTS];
+            assert_eq!(failures, vec![Failure::SchemaInvalidMetadata(opid, sem_id)]);
+        }
+    }
+}
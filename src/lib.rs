@@ -23,15 +23,164 @@
 extern crate amplify;
 #[macro_use]
 extern crate strict_types;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
+#[cfg(any(feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso"))]
+mod asm;
+#[cfg(feature = "aso")]
+mod aso;
+mod builder;
+#[cfg(feature = "cfa")]
 mod cfa;
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod conservation;
+#[cfg(all(
+    feature = "log",
+    any(feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso")
+))]
+mod coverage;
+mod diff;
+mod embedded;
+mod errno;
+mod exec;
+#[cfg(feature = "serde")]
+mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod history;
+#[cfg(feature = "serde")]
+mod inspect;
+#[cfg(feature = "lfa")]
+mod lfa;
+mod lint;
+#[cfg(feature = "serde")]
+mod manifest;
+#[cfg(any(
+    feature = "uda",
+    all(
+        feature = "serde",
+        any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso")
+    )
+))]
+mod media;
+mod memo;
+#[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
 mod nia;
+mod sign;
+mod stl;
+#[doc(hidden)]
+#[cfg(any(
+    feature = "testkit",
+    all(test, feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso")
+))]
+pub mod testkit;
+#[cfg(feature = "log")]
+mod trace;
+#[cfg(feature = "serde")]
+mod typegen;
+#[cfg(feature = "uda")]
 mod uda;
+pub mod wasm;
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod golden;
+
+use std::cmp::Reverse;
 
+use aluvm::library::LibId;
+#[cfg(feature = "aso")]
+pub use aso::{AssetSwapOffer, TakeError};
+pub use builder::{SchemaBuilder, SchemaParams, TransferBuilder, OFFICIAL_TIMESTAMP};
+#[cfg(feature = "cfa")]
 pub use cfa::CollectibleFungibleAsset;
+#[cfg(all(
+    feature = "log",
+    any(feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso")
+))]
+pub use coverage::{CoverageGap, Gap, LibCoverage, ScriptCoverage};
+pub use diff::{
+    diff, Bounds, DiffError, DiffLine, IimplField, OpField, SchemaChange, SchemaDiff,
+    SchemaRelease, ScriptDiff,
+};
+pub use errno::{SchemaErrno, ScriptFailure};
+pub use exec::OpValidator;
+#[cfg(feature = "serde")]
+pub use export::{
+    GlobalTypeExport, InterfaceExport, LibExport, NamedId, OccurrencesExport, OperationExport,
+    OwnedTypeExport, SchemaExport, TypeExport, TypedStateExport, ValidatorExport,
+};
+pub use history::{historic_schema, SchemaVersion};
+#[cfg(feature = "uda")]
+use ifaces::rgb21::Rgb21;
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use ifaces::IssuerWrapper;
+#[cfg(any(feature = "nia", feature = "lfa", feature = "aso"))]
+use ifaces::Rgb20;
+#[cfg(feature = "cfa")]
+use ifaces::Rgb25;
+#[cfg(feature = "serde")]
+pub use inspect::{
+    ContractReport, EmbeddedWitnesses, InspectError, InterfaceState, OfferState, TokenAllocation,
+};
+#[cfg(feature = "lfa")]
+pub use lfa::LightningFungibleAsset;
+pub use lint::{lint, LintIssue, ValidatorSite};
+#[cfg(feature = "serde")]
+pub use manifest::{
+    IssueManifest, ManifestAllocation, ManifestError, ManifestMedia, ManifestOffer, ManifestTerms,
+    ManifestToken,
+};
+#[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
+pub use nia::FungibleIssueError;
+#[cfg(feature = "nia")]
 pub use nia::NonInflatableAsset;
-use rgbstd::{AssignmentType, GlobalStateType, TransitionType};
-pub use uda::UniqueDigitalAsset;
+use rgbstd::containers::Kit;
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use rgbstd::interface::IfaceClass;
+use rgbstd::interface::{Iface, IfaceImpl};
+use rgbstd::validation::Scripts;
+use rgbstd::{AssignmentType, GlobalStateType, Schema, SchemaId, TransitionType};
+pub use sign::{sign_kit, verify_kit, DeveloperKey, SignError, VerifyError};
+pub use stl::{
+    schemata_stl, ChannelOutput, ChannelOutputs, HtlcDirection, HtlcOutput, OfferFill, OfferTerms,
+    PaymentRef, StructuredMemo, TransferMemo, LIB_ID_RGB_SCHEMATA, LIB_NAME_RGB_SCHEMATA,
+};
+use strict_types::TypeSystem;
+#[cfg(feature = "log")]
+pub use trace::{ExecTrace, StepOutcome, TraceError, TraceStep};
+#[cfg(feature = "serde")]
+pub use typegen::{state_json_schema, state_typescript};
+#[cfg(feature = "uda")]
+pub use uda::{UdaIssueError, UniqueDigitalAsset};
 
 // RGB20
 pub const GS_NOMINAL: GlobalStateType = GlobalStateType::with(2000);
@@ -43,13 +192,219 @@ pub const GS_TOKENS: GlobalStateType = GlobalStateType::with(2102);
 pub const GS_ENGRAVINGS: GlobalStateType = GlobalStateType::with(2103);
 pub const GS_ATTACH: GlobalStateType = GlobalStateType::with(2104);
 
+// Asset swap offers
+pub const GS_OFFER: GlobalStateType = GlobalStateType::with(2200);
+pub const GS_FILL: GlobalStateType = GlobalStateType::with(2201);
+
 pub const OS_ASSET: AssignmentType = AssignmentType::with(4000);
+pub const OS_LOT: AssignmentType = AssignmentType::with(4200);
 
 pub const TS_TRANSFER: TransitionType = TransitionType::with(10000);
+pub const TS_TAKE: TransitionType = TransitionType::with(10100);
 
 pub const ERRNO_NON_EQUAL_IN_OUT: u8 = 0;
 pub const ERRNO_ISSUED_MISMATCH: u8 = 1;
 pub const ERRNO_NON_FRACTIONAL: u8 = 10;
+pub const ERRNO_CHANNEL_OUTPUTS_MISMATCH: u8 = 20;
+pub const ERRNO_FILL_MISMATCH: u8 = 30;
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[display(Debug)]
+pub enum BundledSchema {
+    #[cfg(feature = "nia")]
+    #[cfg_attr(feature = "serde", serde(alias = "NIA"))]
+    NonInflatableAsset,
+    #[cfg(feature = "uda")]
+    #[cfg_attr(feature = "serde", serde(alias = "UDA"))]
+    UniqueDigitalAsset,
+    #[cfg(feature = "cfa")]
+    #[cfg_attr(feature = "serde", serde(alias = "CFA"))]
+    CollectibleFungibleAsset,
+    #[cfg(feature = "lfa")]
+    #[cfg_attr(feature = "serde", serde(alias = "LFA"))]
+    LightningFungibleAsset,
+    #[cfg(feature = "aso")]
+    #[cfg_attr(feature = "serde", serde(alias = "ASO"))]
+    AssetSwapOffer,
+}
+
+impl BundledSchema {
+    /// Number of schemata enabled with cargo features.
+    pub const COUNT: usize = cfg!(feature = "nia") as usize
+        + cfg!(feature = "uda") as usize
+        + cfg!(feature = "cfa") as usize
+        + cfg!(feature = "lfa") as usize
+        + cfg!(feature = "aso") as usize;
+
+    pub const ALL: [BundledSchema; Self::COUNT] = [
+        #[cfg(feature = "nia")]
+        BundledSchema::NonInflatableAsset,
+        #[cfg(feature = "uda")]
+        BundledSchema::UniqueDigitalAsset,
+        #[cfg(feature = "cfa")]
+        BundledSchema::CollectibleFungibleAsset,
+        #[cfg(feature = "lfa")]
+        BundledSchema::LightningFungibleAsset,
+        #[cfg(feature = "aso")]
+        BundledSchema::AssetSwapOffer,
+    ];
+
+    /// Detects which of the bundled schemata has the provided id.
+    pub fn from_schema_id(schema_id: SchemaId) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|schema| schema.schema().schema_id() == schema_id)
+    }
+
+    /// Detects which of the bundled schemata provides the validation library
+    /// with the given id to the schema.
+    ///
+    /// If the schema itself is not one of the bundled schemata, it is treated
+    /// as their variant or fork. Several bundled schemata may share the same
+    /// library (for instance, LFA and ASO reuse NIA validators), thus among
+    /// the schemata providing the library the one closest to the fork is
+    /// selected: first by having the same global and owned state types, then
+    /// by the number of shared metadata and transition types, and finally by
+    /// the smaller number of its metadata types missing from the fork.
+    pub fn with_validator(schema: &Schema, lib: LibId) -> Option<Self> {
+        if let Some(bundled) = Self::from_schema_id(schema.schema_id()) {
+            return Some(bundled);
+        }
+        Self::ALL
+            .into_iter()
+            .filter(|bundled| bundled.scripts().contains_key(&lib))
+            .enumerate()
+            .max_by_key(|(no, bundled)| {
+                let origin = bundled.schema();
+                let state = origin.global_types == schema.global_types
+                    && origin.owned_types == schema.owned_types;
+                let shared_meta = origin
+                    .meta_types
+                    .keys()
+                    .filter(|ty| schema.meta_types.contains_key(*ty))
+                    .count();
+                let missing_meta = origin.meta_types.len() - shared_meta;
+                let shared_transitions = origin
+                    .transitions
+                    .keys()
+                    .filter(|ty| schema.transitions.contains_key(*ty))
+                    .count();
+                (state, shared_meta, shared_transitions, Reverse(missing_meta), Reverse(*no))
+            })
+            .map(|(_, bundled)| bundled)
+    }
+
+    pub fn schema(self) -> Schema {
+        match self {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => NonInflatableAsset::schema(),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => UniqueDigitalAsset::schema(),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => CollectibleFungibleAsset::schema(),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => LightningFungibleAsset::schema(),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => AssetSwapOffer::schema(),
+        }
+    }
+
+    pub fn issue_impl(self) -> IfaceImpl {
+        match self {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => NonInflatableAsset::issue_impl(),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => UniqueDigitalAsset::issue_impl(),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => CollectibleFungibleAsset::issue_impl(),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => LightningFungibleAsset::issue_impl(),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => AssetSwapOffer::issue_impl(),
+        }
+    }
+
+    pub fn types(self) -> TypeSystem {
+        match self {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => NonInflatableAsset::types(),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => UniqueDigitalAsset::types(),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => CollectibleFungibleAsset::types(),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => LightningFungibleAsset::types(),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => AssetSwapOffer::types(),
+        }
+    }
+
+    pub fn scripts(self) -> Scripts {
+        match self {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => NonInflatableAsset::scripts(),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => UniqueDigitalAsset::scripts(),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => CollectibleFungibleAsset::scripts(),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => LightningFungibleAsset::scripts(),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => AssetSwapOffer::scripts(),
+        }
+    }
+
+    /// Interface implemented by the schema.
+    pub fn iface(self) -> Iface {
+        match self {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => Rgb20::FIXED.iface(),
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => Rgb21::NONE.iface(),
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => Rgb25::NONE.iface(),
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => Rgb20::FIXED.iface(),
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => Rgb20::FIXED.iface(),
+        }
+    }
+
+    /// Constructs kit with the schema, its interface, interface implementation,
+    /// validation scripts and types.
+    pub fn kit(self) -> Kit { self.builder().kit() }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display("unknown bundled schema name '{0}'")]
+pub struct UnknownSchema(pub String);
+
+mod _from_str {
+    use std::str::FromStr;
+
+    use super::*;
+
+    impl FromStr for BundledSchema {
+        type Err = UnknownSchema;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                #[cfg(feature = "nia")]
+                "NonInflatableAsset" | "NIA" => Ok(Self::NonInflatableAsset),
+                #[cfg(feature = "uda")]
+                "UniqueDigitalAsset" | "UDA" => Ok(Self::UniqueDigitalAsset),
+                #[cfg(feature = "cfa")]
+                "CollectibleFungibleAsset" | "CFA" => Ok(Self::CollectibleFungibleAsset),
+                #[cfg(feature = "lfa")]
+                "LightningFungibleAsset" | "LFA" => Ok(Self::LightningFungibleAsset),
+                #[cfg(feature = "aso")]
+                "AssetSwapOffer" | "ASO" => Ok(Self::AssetSwapOffer),
+                _ => Err(UnknownSchema(s.to_owned())),
+            }
+        }
+    }
+}
 
 pub mod dumb {
     use rgbstd::validation::{ResolveWitness, WitnessResolverError};
@@ -0,0 +1,327 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Static checks of schema consistency with its validation scripts and
+//! interface implementation, which are not covered by [`IfaceImpl::check`].
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use aluvm::isa::{Bytecode, Instr, PutOp};
+use aluvm::library::{Cursor, Lib, LibId, LibSite, Read};
+use aluvm::reg::{Reg32, RegA};
+use rgbstd::interface::IfaceImpl;
+use rgbstd::persistence::MemContract;
+use rgbstd::validation::Scripts;
+use rgbstd::vm::{ContractOp, RgbIsa};
+use rgbstd::{AssignmentType, ExtensionType, GlobalStateType, Schema, TransitionType};
+
+use crate::{BundledSchema, SchemaErrno};
+
+/// Operation which validator is checked by the lint.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display)]
+pub enum ValidatorSite {
+    #[display("genesis")]
+    Genesis,
+
+    #[display("extension {0}")]
+    Extension(ExtensionType),
+
+    #[display("transition {0}")]
+    Transition(TransitionType),
+}
+
+/// Inconsistency found by [`lint`].
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+#[display(doc_comments)]
+pub enum LintIssue {
+    /// {0} validator uses library {1}, which is absent from the schema scripts.
+    UnknownLib(ValidatorSite, LibId),
+
+    /// {0} validator entry point {1} lies outside of the library code segment.
+    OutOfBounds(ValidatorSite, LibSite),
+
+    /// {0} validator entry point {1} doesn't match an instruction boundary.
+    NotInstructionBoundary(ValidatorSite, LibSite),
+
+    /// library {0} contains undecodable bytecode at offset {1:#06X}.
+    InvalidBytecode(LibId, u16),
+
+    /// instruction at {0}:{1:#06X} accesses global state {2}, which is not
+    /// defined by the schema.
+    UnknownGlobalType(LibId, u16, GlobalStateType),
+
+    /// instruction at {0}:{1:#06X} accesses owned state {2}, which is not
+    /// defined by the schema.
+    UnknownOwnedType(LibId, u16, AssignmentType),
+
+    /// instruction at {0}:{1:#06X} sets error code {2}, which is not declared
+    /// by the interface implementation.
+    UndeclaredErrno(LibId, u16, u8),
+
+    /// instruction at {0}:{1:#06X} sets error code {2}, which is not known to
+    /// the interface and thus can't be declared by the interface implementation.
+    UnnamedErrno(LibId, u16, SchemaErrno),
+
+    /// global state {0} has no occurrence entry in genesis or any of the
+    /// extensions or state transitions.
+    NoOccurrences(GlobalStateType),
+}
+
+/// Runs static checks of the schema against its validation scripts and the
+/// interface implementation, returning all found issues.
+///
+/// Checks that:
+/// - every validator entry point lies inside its library at an instruction boundary;
+/// - every state type read or verified by the scripts is defined by the schema;
+/// - every error code `put` into `a8[0]` register is declared in the interface implementation
+///   errors; codes which are registered in [`SchemaErrno`] for a bundled library under a name not
+///   known to the interface can't be declared and are reported as [`LintIssue::UnnamedErrno`];
+/// - every global state type has an occurrence entry in some operation.
+pub fn lint(schema: &Schema, iimpl: &IfaceImpl, scripts: &Scripts) -> Vec<LintIssue> {
+    let mut issues = vec![];
+
+    let validators = schema
+        .genesis
+        .validator
+        .map(|site| (ValidatorSite::Genesis, site))
+        .into_iter()
+        .chain(schema.extensions.iter().filter_map(|(ty, ext)| {
+            ext.validator
+                .map(|site| (ValidatorSite::Extension(*ty), site))
+        }))
+        .chain(schema.transitions.iter().filter_map(|(ty, transition)| {
+            transition
+                .validator
+                .map(|site| (ValidatorSite::Transition(*ty), site))
+        }));
+    for (op, site) in validators {
+        let Some(lib) = scripts.get(&site.lib) else {
+            issues.push(LintIssue::UnknownLib(op, site.lib));
+            continue;
+        };
+        if site.pos as usize >= lib.code.as_ref().len() {
+            issues.push(LintIssue::OutOfBounds(op, site));
+        } else if !decode(lib).iter().any(|(pos, _)| *pos == site.pos) {
+            issues.push(LintIssue::NotInstructionBoundary(op, site));
+        }
+    }
+
+    let errnos = iimpl
+        .errors
+        .iter()
+        .map(|variant| variant.id)
+        .collect::<BTreeSet<_>>();
+    for (id, lib) in scripts.iter() {
+        // Error codes of the bundled library which can't be declared, since the
+        // interface doesn't know them.
+        let unnamed = BundledSchema::with_validator(schema, *id)
+            .map(|bundled| {
+                let iface = bundled.iface();
+                SchemaErrno::with_schema(bundled)
+                    .filter(|errno| {
+                        !iface
+                            .errors
+                            .keys()
+                            .any(|name| name.as_str() == errno.name())
+                    })
+                    .map(|errno| (errno.errno(), errno))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .unwrap_or_default();
+        let code = decode(lib);
+        if let Some((pos, None)) = code.last() {
+            issues.push(LintIssue::InvalidBytecode(*id, *pos));
+        }
+        for (pos, instr) in code
+            .into_iter()
+            .filter_map(|(pos, instr)| Some((pos, instr?)))
+        {
+            match instr {
+                Instr::Put(PutOp::PutA(RegA::A8, Reg32::Reg0, val)) => {
+                    if let Some(errno) = val.map(u8::from) {
+                        if errnos.contains(&errno) {
+                            continue;
+                        }
+                        issues.push(match unnamed.get(&errno) {
+                            Some(unnamed) => LintIssue::UnnamedErrno(*id, pos, *unnamed),
+                            None => LintIssue::UndeclaredErrno(*id, pos, errno),
+                        });
+                    }
+                }
+                Instr::ExtensionCodes(RgbIsa::Contract(op)) => match op {
+                    ContractOp::CnG(ty, _)
+                    | ContractOp::CnC(ty, _)
+                    | ContractOp::LdG(ty, _, _)
+                    | ContractOp::LdC(ty, _, _)
+                        if !schema.global_types.contains_key(&ty) =>
+                    {
+                        issues.push(LintIssue::UnknownGlobalType(*id, pos, ty))
+                    }
+                    ContractOp::CnP(ty, _)
+                    | ContractOp::CnS(ty, _)
+                    | ContractOp::LdP(ty, _, _)
+                    | ContractOp::LdS(ty, _, _)
+                    | ContractOp::LdF(ty, _, _)
+                    | ContractOp::Pcvs(ty)
+                    | ContractOp::Pcas(ty)
+                    | ContractOp::Pcps(ty)
+                        if !schema.owned_types.contains_key(&ty) =>
+                    {
+                        issues.push(LintIssue::UnknownOwnedType(*id, pos, ty))
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    for ty in schema.global_types.keys() {
+        let occurs = schema.genesis.globals.contains_key(ty)
+            || schema
+                .extensions
+                .values()
+                .any(|ext| ext.globals.contains_key(ty))
+            || schema
+                .transitions
+                .values()
+                .any(|transition| transition.globals.contains_key(ty));
+        if !occurs {
+            issues.push(LintIssue::NoOccurrences(*ty));
+        }
+    }
+
+    issues
+}
+
+/// Decodes library code into instructions with their offsets. If the code
+/// can't be decoded, the last item has no instruction and points to the
+/// undecodable byte.
+pub(crate) fn decode(lib: &Lib) -> Vec<(u16, Option<Instr<RgbIsa<MemContract>>>)> {
+    let mut code = vec![];
+    let mut reader = Cursor::with(&lib.code, &lib.data, &lib.libs);
+    while !reader.is_eof() {
+        let pos = reader.offset().0;
+        match Instr::<RgbIsa<MemContract>>::decode(&mut reader) {
+            Ok(instr) => code.push((pos, Some(instr))),
+            Err(_) => {
+                code.push((pos, None));
+                break;
+            }
+        }
+    }
+    code
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use amplify::confinement::Confined;
+    use rgbstd::schema::GlobalStateSchema;
+    use strict_types::SemId;
+
+    use super::*;
+    use crate::{BundledSchema, ERRNO_ISSUED_MISMATCH, GS_ISSUED_SUPPLY, TS_TRANSFER};
+
+    #[test]
+    fn bundled() {
+        for schema in BundledSchema::ALL {
+            let issues = lint(&schema.schema(), &schema.issue_impl(), &schema.scripts());
+            let unnamed = issues
+                .iter()
+                .filter_map(|issue| match issue {
+                    LintIssue::UnnamedErrno(_, _, errno) => Some(*errno),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(unnamed.len(), issues.len(), "{schema} lint issues: {issues:#?}");
+            let expected = match schema {
+                BundledSchema::LightningFungibleAsset => {
+                    vec![SchemaErrno::LfaChannelOutputsMismatch]
+                }
+                BundledSchema::AssetSwapOffer => vec![SchemaErrno::AsoFillMismatch],
+                _ => vec![],
+            };
+            assert_eq!(unnamed, expected, "{schema}");
+        }
+    }
+
+    #[test]
+    fn issues() {
+        let bundled = BundledSchema::NonInflatableAsset;
+        let mut schema = bundled.schema();
+        let mut iimpl = bundled.issue_impl();
+        let scripts = bundled.scripts();
+        let lib_id = *scripts.keys().next().unwrap();
+
+        let transfer = schema.transitions.get_mut(&TS_TRANSFER).unwrap();
+        transfer.validator = Some(LibSite::with(1, lib_id));
+        schema.genesis.validator = Some(LibSite::with(0xFFF, lib_id));
+        schema.genesis.globals.remove(&GS_ISSUED_SUPPLY).unwrap();
+        schema
+            .global_types
+            .insert(GlobalStateType::with(9999), GlobalStateSchema::once(SemId::unit()))
+            .unwrap();
+        let errors = iimpl
+            .errors
+            .iter()
+            .filter(|variant| variant.id != ERRNO_ISSUED_MISMATCH)
+            .cloned();
+        iimpl.errors = Confined::from_iter_checked(errors);
+
+        let issues = lint(&schema, &iimpl, &scripts);
+        assert!(issues.contains(&LintIssue::NotInstructionBoundary(
+            ValidatorSite::Transition(TS_TRANSFER),
+            LibSite::with(1, lib_id)
+        )));
+        assert!(issues.contains(&LintIssue::OutOfBounds(
+            ValidatorSite::Genesis,
+            LibSite::with(0xFFF, lib_id)
+        )));
+        assert!(issues
+            .iter()
+            .any(|issue| matches!(issue, LintIssue::UndeclaredErrno(_, _, ERRNO_ISSUED_MISMATCH))));
+        assert!(issues.contains(&LintIssue::NoOccurrences(GS_ISSUED_SUPPLY)));
+        assert!(issues.contains(&LintIssue::NoOccurrences(GlobalStateType::with(9999))));
+    }
+
+    #[test]
+    fn unknown_types() {
+        let bundled = BundledSchema::UniqueDigitalAsset;
+        let mut schema = bundled.schema();
+        schema.global_types = none!();
+        schema.genesis.globals = none!();
+        schema.owned_types = none!();
+        let issues = lint(&schema, &bundled.issue_impl(), &bundled.scripts());
+        assert!(issues
+            .iter()
+            .any(|issue| matches!(issue, LintIssue::UnknownGlobalType(..))));
+        assert!(issues
+            .iter()
+            .any(|issue| matches!(issue, LintIssue::UnknownOwnedType(..))));
+    }
+}
@@ -19,37 +19,244 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::io;
+use std::error::Error;
 use std::io::stdout;
+use std::path::Path;
+use std::str::FromStr;
+use std::{env, fs, io, process};
 
-use ifaces::rgb21::Rgb21;
-use ifaces::{IssuerWrapper, Rgb20, Rgb25};
+use bp::secp256k1::{Keypair, SecretKey, SECP256K1};
 use rgbstd::containers::{FileContent, Kit};
-use rgbstd::interface::IfaceClass;
 use rgbstd::persistence::MemContract;
 use rgbstd::vm::RgbIsa;
-use schemata::{CollectibleFungibleAsset, NonInflatableAsset, UniqueDigitalAsset};
+use schemata::{
+    sign_kit, verify_kit, BundledSchema, ContractReport, DeveloperKey, IssueManifest, SchemaExport,
+    SchemaRelease,
+};
 
-fn main() -> io::Result<()> {
-    nia()?;
-    uda()?;
-    cfa()?;
+const USAGE: &str = "Usage:
+    rgb-schemata                           compile all schemata into `schemata` directory
+    rgb-schemata issue <MANIFEST> [<OUT>]  issue contract from YAML or JSON manifest, saving it
+                                           into <OUT> file (armored if it has `rgba` extension)
+                                           or printing armored contract to STDOUT
+    rgb-schemata inspect-contract <FILE> [--json]
+                                           print interface-level state of a contract issued
+                                           under one of the bundled schemata
+    rgb-schemata trace <CONSIGNMENT> [<OPID>]
+                                           run validation script on the genesis or the given
+                                           operation from a contract or transfer consignment,
+                                           printing each executed instruction with register state
+                                           from AluVM log (requires `log` feature)
+    rgb-schemata coverage <CONSIGNMENT>...
+                                           run validation scripts on all operations of the
+                                           consignments, reporting instructions of their libraries
+                                           which were never executed or never failed the
+                                           validation (requires `log` feature)
+    rgb-schemata diff <OLD> <NEW>          compare two schema versions, given as kit files or
+                                           bundled schema names, classifying each change as
+                                           breaking or compatible for existing contracts
+    rgb-schemata export --format json|yaml|json-schema|typescript [<SCHEMA>]
+                                           print definition of all or the given bundled schema
+                                           with its types and validator disassembly, or JSON
+                                           Schema / TypeScript declarations of its interface state
+    rgb-schemata sign <KIT> <KEY> <OUT>    sign schemata and interface implementations of a kit
+                                           file with the developer secret key from <KEY> file in
+                                           hex; if <KIT> is a bundled schema name, its kit is
+                                           built with the developer identity of the key
+    rgb-schemata verify <KIT>              check that all schemata and interface implementations
+                                           of a kit file are signed by their developers";
 
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => {
+            nia()?;
+            uda()?;
+            cfa()?;
+            lfa()?;
+            aso()?;
+        }
+        ["issue", manifest] => issue(manifest, None)?,
+        ["issue", manifest, output] => issue(manifest, Some(output))?,
+        ["inspect-contract", file] => inspect(file, false)?,
+        ["inspect-contract", file, "--json"] | ["inspect-contract", "--json", file] => {
+            inspect(file, true)?
+        }
+        #[cfg(all(feature = "log", unix))]
+        ["trace", file] => trace(file, None)?,
+        #[cfg(all(feature = "log", unix))]
+        ["trace", file, opid] => trace(file, Some(opid))?,
+        #[cfg(all(feature = "log", unix))]
+        ["coverage", files @ ..] if !files.is_empty() => coverage(files)?,
+        ["diff", old, new] => diff(old, new)?,
+        ["export", "--format", format] => export(format, None)?,
+        ["export", "--format", format, schema] => export(format, Some(schema))?,
+        ["sign", kit, key, output] => sign(kit, key, output)?,
+        ["verify", kit] => verify(kit)?,
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(1);
+        }
+    }
+
+    Ok(())
+}
+
+fn issue(manifest: &str, output: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let contract = IssueManifest::load(manifest)?.issue()?;
+    match output.map(Path::new) {
+        None => println!("{contract}"),
+        Some(path) if path.extension().is_some_and(|ext| ext == "rgba") => {
+            contract.save_armored(path)?
+        }
+        Some(path) => contract.save_file(path)?,
+    }
+    Ok(())
+}
+
+fn inspect(file: &str, json: bool) -> Result<(), Box<dyn Error>> {
+    let report = ContractReport::load_file(file)?;
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(&report)?),
+        false => print!("{report}"),
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "log", unix))]
+fn load_validator(file: &str) -> Result<schemata::OpValidator, Box<dyn Error>> {
+    use rgbstd::containers::{Contract, Transfer, UniversalFile};
+    use schemata::OpValidator;
+
+    let data = fs::read(file)?;
+    Ok(match data.starts_with(b"-----BEGIN") {
+        true => {
+            let s = String::from_utf8_lossy(&data);
+            match Contract::from_str(&s) {
+                Ok(contract) => OpValidator::with_consignment(&contract),
+                Err(_) => OpValidator::with_consignment(&Transfer::from_str(&s)?),
+            }
+        }
+        false => match UniversalFile::load(data.as_slice())? {
+            UniversalFile::Contract(contract) => OpValidator::with_consignment(&contract),
+            UniversalFile::Transfer(transfer) => OpValidator::with_consignment(&transfer),
+            UniversalFile::Kit(_) => {
+                return Err("kit files don't contain contract operations".into())
+            }
+        },
+    })
+}
+
+#[cfg(all(feature = "log", unix))]
+fn trace(file: &str, opid: Option<&str>) -> Result<(), Box<dyn Error>> {
+    use rgbstd::{OpId, Operation};
+
+    let validator = load_validator(file)?;
+    let opid = match opid {
+        Some(opid) => OpId::from_str(opid)?,
+        None => validator.genesis().id(),
+    };
+    print!("{}", validator.trace(opid)?);
+    Ok(())
+}
+
+#[cfg(all(feature = "log", unix))]
+fn coverage(files: &[&str]) -> Result<(), Box<dyn Error>> {
+    use rgbstd::validation::ConsignmentApi;
+    use schemata::ScriptCoverage;
+
+    let validators = files
+        .iter()
+        .map(|file| load_validator(file))
+        .collect::<Result<Vec<_>, _>>()?;
+    let libs = validators
+        .iter()
+        .flat_map(|validator| validator.scripts().values().cloned());
+    let mut coverage = ScriptCoverage::new(libs);
+    for validator in &validators {
+        for opid in validator.opids() {
+            coverage.record(&validator.trace(opid)?);
+        }
+    }
+    print!("{coverage}");
+    Ok(())
+}
+
+fn diff(old: &str, new: &str) -> Result<(), Box<dyn Error>> {
+    let load = |arg: &str| match arg.parse::<BundledSchema>() {
+        Ok(schema) => Ok(SchemaRelease::bundled(schema)),
+        Err(_) => SchemaRelease::load_kit(arg),
+    };
+    print!("{}", schemata::diff(&load(old)?, &load(new)?));
+    Ok(())
+}
+
+fn export(format: &str, schema: Option<&str>) -> Result<(), Box<dyn Error>> {
+    if matches!(format, "json-schema" | "typescript") {
+        let schemata = match schema {
+            Some(schema) => vec![schema.parse()?],
+            None => BundledSchema::ALL.to_vec(),
+        };
+        for schema in schemata {
+            match format {
+                "json-schema" => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&schemata::state_json_schema(schema))?
+                ),
+                _ => print!("{}", schemata::state_typescript(schema)),
+            }
+        }
+        return Ok(());
+    }
+    let exports = match schema {
+        Some(schema) => vec![SchemaExport::bundled(schema.parse()?)],
+        None => BundledSchema::ALL
+            .into_iter()
+            .map(SchemaExport::bundled)
+            .collect(),
+    };
+    match (format, exports.as_slice()) {
+        ("json", [export]) => println!("{}", export.to_json()),
+        ("yaml", [export]) => print!("{}", export.to_yaml()),
+        ("json", exports) => println!("{}", serde_json::to_string_pretty(exports)?),
+        ("yaml", exports) => print!("{}", serde_yaml::to_string(exports)?),
+        (format, _) => return Err(format!("unknown export format '{format}'").into()),
+    }
+    Ok(())
+}
+
+fn sign(kit: &str, key: &str, output: &str) -> Result<(), Box<dyn Error>> {
+    let secret = SecretKey::from_str(fs::read_to_string(key)?.trim())?;
+    let keypair = Keypair::from_secret_key(SECP256K1, &secret);
+    let developer = DeveloperKey::from(keypair);
+    let mut kit = match kit.parse::<BundledSchema>() {
+        Ok(schema) => schema.builder().developer(developer.identity()).kit(),
+        Err(_) => Kit::load_file(kit)?,
+    };
+    sign_kit(&mut kit, &keypair)?;
+    match Path::new(output) {
+        path if path.extension().is_some_and(|ext| ext == "rgba") => kit.save_armored(path)?,
+        path => kit.save_file(path)?,
+    }
+    eprintln!("Kit {} is signed by {developer}", kit.kit_id());
+    Ok(())
+}
+
+fn verify(kit: &str) -> Result<(), Box<dyn Error>> {
+    let kit = Kit::load_file(kit)?;
+    for developer in verify_kit(&kit)? {
+        println!("Signed by {developer}");
+    }
     Ok(())
 }
 
 fn nia() -> io::Result<()> {
-    let schema = NonInflatableAsset::schema();
-    let iimpl = NonInflatableAsset::issue_impl();
-    let lib = NonInflatableAsset::scripts();
-    let types = NonInflatableAsset::types();
-
-    let mut kit = Kit::default();
-    kit.schemata.push(schema).unwrap();
-    kit.ifaces.push(Rgb20::FIXED.iface()).unwrap();
-    kit.iimpls.push(iimpl).unwrap();
-    kit.scripts.extend(lib.into_values()).unwrap();
-    kit.types = types;
+    let kit = BundledSchema::NonInflatableAsset.kit();
 
     kit.save_file("schemata/NonInflatableAssets.rgb")?;
     kit.save_armored("schemata/NonInflatableAssets.rgba")?;
@@ -59,17 +266,7 @@ fn nia() -> io::Result<()> {
 }
 
 fn uda() -> io::Result<()> {
-    let schema = UniqueDigitalAsset::schema();
-    let iimpl = UniqueDigitalAsset::issue_impl();
-    let lib = UniqueDigitalAsset::scripts();
-    let types = UniqueDigitalAsset::types();
-
-    let mut kit = Kit::default();
-    kit.schemata.push(schema).unwrap();
-    kit.ifaces.push(Rgb21::NONE.iface()).unwrap();
-    kit.iimpls.push(iimpl).unwrap();
-    kit.scripts.extend(lib.into_values()).unwrap();
-    kit.types = types;
+    let kit = BundledSchema::UniqueDigitalAsset.kit();
 
     kit.save_file("schemata/UniqueDigitalAsset.rgb")?;
     kit.save_armored("schemata/UniqueDigitalAsset.rgba")?;
@@ -79,17 +276,7 @@ fn uda() -> io::Result<()> {
 }
 
 fn cfa() -> io::Result<()> {
-    let schema = CollectibleFungibleAsset::schema();
-    let iimpl = CollectibleFungibleAsset::issue_impl();
-    let lib = CollectibleFungibleAsset::scripts();
-    let types = CollectibleFungibleAsset::types();
-
-    let mut kit = Kit::default();
-    kit.schemata.push(schema).unwrap();
-    kit.ifaces.push(Rgb25::NONE.iface()).unwrap();
-    kit.iimpls.push(iimpl).unwrap();
-    kit.scripts.extend(lib.into_values()).unwrap();
-    kit.types = types;
+    let kit = BundledSchema::CollectibleFungibleAsset.kit();
 
     kit.save_file("schemata/CollectibleFungibleAsset.rgb")?;
     kit.save_armored("schemata/CollectibleFungibleAsset.rgba")?;
@@ -98,10 +285,31 @@ fn cfa() -> io::Result<()> {
     Ok(())
 }
 
+fn lfa() -> io::Result<()> {
+    let kit = BundledSchema::LightningFungibleAsset.kit();
+
+    kit.save_file("schemata/LightningFungibleAsset.rgb")?;
+    kit.save_armored("schemata/LightningFungibleAsset.rgba")?;
+    print_lib(&kit);
+
+    Ok(())
+}
+
+fn aso() -> io::Result<()> {
+    let kit = BundledSchema::AssetSwapOffer.kit();
+
+    kit.save_file("schemata/AssetSwapOffer.rgb")?;
+    kit.save_armored("schemata/AssetSwapOffer.rgba")?;
+    print_lib(&kit);
+
+    Ok(())
+}
+
 fn print_lib(kit: &Kit) {
-    let alu_lib = kit.scripts.first().unwrap();
-    eprintln!("{alu_lib}");
-    alu_lib
-        .print_disassemble::<RgbIsa<MemContract>>(stdout())
-        .unwrap();
+    for alu_lib in &kit.scripts {
+        eprintln!("{alu_lib}");
+        alu_lib
+            .print_disassemble::<RgbIsa<MemContract>>(stdout())
+            .unwrap();
+    }
 }
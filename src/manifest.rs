@@ -0,0 +1,741 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative issuance manifests, allowing to issue contracts under any of
+//! the bundled schemata from a YAML or JSON description.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+use bp::dbc::Method;
+use bp::Outpoint;
+use ifaces::rgb20::IssuerError;
+#[cfg(any(feature = "nia", feature = "lfa"))]
+use ifaces::Rgb20Wrapper;
+#[cfg(feature = "cfa")]
+use ifaces::Rgb25Wrapper;
+use rgbstd::containers::ValidContract;
+use rgbstd::interface::BuilderError;
+#[cfg(feature = "cfa")]
+use rgbstd::invoice::Amount;
+use rgbstd::invoice::Precision;
+#[cfg(any(feature = "nia", feature = "cfa", feature = "lfa"))]
+use rgbstd::persistence::MemContract;
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use rgbstd::stl::Attachment;
+#[cfg(any(feature = "uda", feature = "aso"))]
+use rgbstd::stl::{ContractTerms, RicardianContract};
+use rgbstd::{ContractId, Identity};
+use serde::Deserialize;
+use strict_encoding::InvalidRString;
+
+#[cfg(any(
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+use crate::media::attachment;
+use crate::BundledSchema;
+#[cfg(feature = "cfa")]
+use crate::CollectibleFungibleAsset;
+#[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
+use crate::FungibleIssueError;
+#[cfg(feature = "lfa")]
+use crate::LightningFungibleAsset;
+#[cfg(feature = "nia")]
+use crate::NonInflatableAsset;
+#[cfg(feature = "aso")]
+use crate::{AssetSwapOffer, OfferTerms};
+#[cfg(feature = "uda")]
+use crate::{UdaIssueError, UniqueDigitalAsset};
+
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum ManifestError {
+    /// unable to read {1:?}: {0}
+    Io(io::Error, PathBuf),
+
+    #[from]
+    #[display(inner)]
+    Yaml(serde_yaml::Error),
+
+    #[from]
+    #[display(inner)]
+    Json(serde_json::Error),
+
+    #[from]
+    #[display(inner)]
+    InvalidString(InvalidRString),
+
+    #[from]
+    #[display(inner)]
+    Issuer(IssuerError),
+
+    #[from]
+    #[display(inner)]
+    Builder(BuilderError),
+
+    #[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
+    #[from]
+    #[display(inner)]
+    Fungible(FungibleIssueError),
+
+    #[cfg(feature = "uda")]
+    #[from]
+    #[display(inner)]
+    Uda(UdaIssueError),
+
+    /// {0} issuance manifest must specify `{1}`.
+    MissingField(BundledSchema, &'static str),
+
+    /// {0} issuance manifest must not specify `{1}`.
+    UnexpectedField(BundledSchema, &'static str),
+
+    /// {0} issuance manifest must contain a single allocation.
+    SingleAllocation(BundledSchema),
+
+    /// issuance manifest for {1} can't be used to issue {0}.
+    SchemaMismatch(BundledSchema, BundledSchema),
+
+    /// invalid issuer identity '{0}'; it must be a non-empty string of
+    /// printable ASCII characters.
+    InvalidIssuer(String),
+
+    /// total amount allocated by the issuance manifest exceeds 2^64.
+    AmountOverflow,
+}
+
+/// Issuance manifest describing a contract genesis in terms of its interface.
+///
+/// Paths to the media files are resolved relative to the manifest file
+/// location when loaded with [`IssueManifest::load`], or relative to the
+/// current directory otherwise.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IssueManifest {
+    pub schema: BundledSchema,
+    #[serde(default = "IssueManifest::default_issuer")]
+    pub issuer: String,
+    #[serde(default)]
+    pub ticker: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub precision: Option<Precision>,
+    #[serde(default)]
+    pub terms: Option<ManifestTerms>,
+    pub allocations: Vec<ManifestAllocation>,
+    #[serde(default)]
+    pub token: Option<ManifestToken>,
+    #[serde(default)]
+    pub offer: Option<ManifestOffer>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestTerms {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub media: Option<ManifestMedia>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestAllocation {
+    #[serde(default = "ManifestAllocation::default_method")]
+    pub method: Method,
+    pub outpoint: Outpoint,
+    #[serde(default)]
+    pub amount: Option<u64>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestToken {
+    pub index: u32,
+    #[serde(default)]
+    pub preview: Option<ManifestMedia>,
+    #[serde(default)]
+    pub attachments: Vec<ManifestMedia>,
+}
+
+/// Terms of an asset swap offer: the amount of the counter-asset asked for
+/// the whole issued supply.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestOffer {
+    pub counter_asset: ContractId,
+    pub counter_amount: u64,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestMedia {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub file: PathBuf,
+}
+
+impl ManifestAllocation {
+    fn default_method() -> Method { Method::TapretFirst }
+}
+
+#[cfg(any(feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso"))]
+impl ManifestMedia {
+    fn read(&self) -> Result<(&str, Vec<u8>), ManifestError> {
+        let data = fs::read(&self.file).map_err(|err| ManifestError::Io(err, self.file.clone()))?;
+        Ok((self.ty.as_str(), data))
+    }
+
+    fn attachment(&self) -> Result<Attachment, ManifestError> {
+        let (ty, data) = self.read()?;
+        Ok(attachment(ty, &data)?)
+    }
+}
+
+impl IssueManifest {
+    fn default_issuer() -> String { s!("ssi:anonymous") }
+
+    /// Loads manifest from a file, which is parsed as JSON if it has `json`
+    /// extension and as YAML otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let data =
+            fs::read_to_string(path).map_err(|err| ManifestError::Io(err, path.to_owned()))?;
+        let mut manifest = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json(&data)?,
+            _ => Self::from_yaml(&data)?,
+        };
+        if let Some(dir) = path.parent() {
+            manifest.rebase(dir);
+        }
+        Ok(manifest)
+    }
+
+    pub fn from_yaml(s: &str) -> Result<Self, ManifestError> {
+        let manifest = serde_yaml::from_str::<Self>(s)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, ManifestError> {
+        let manifest = serde_json::from_str::<Self>(s)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Parses JSON manifest for issuing contract under the given schema, in
+    /// which case `schema` field may be omitted.
+    pub fn from_json_for(schema: BundledSchema, s: &str) -> Result<Self, ManifestError> {
+        let mut value = serde_json::from_str::<serde_json::Value>(s)?;
+        if let Some(fields) = value.as_object_mut() {
+            if let Some(requested) = fields.get("schema") {
+                let requested = BundledSchema::deserialize(requested.clone())?;
+                if requested != schema {
+                    return Err(ManifestError::SchemaMismatch(schema, requested));
+                }
+            }
+            fields.insert(s!("schema"), serde_json::Value::String(schema.to_string()));
+        }
+        let manifest = serde_json::from_value::<Self>(value)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Checks the parts of the manifest which are not covered by its syntax
+    /// and otherwise would be detected only by the issuers: the issuer
+    /// identity and the total allocated amount.
+    pub fn validate(&self) -> Result<(), ManifestError> {
+        Identity::from_str(&self.issuer)
+            .map_err(|_| ManifestError::InvalidIssuer(self.issuer.clone()))?;
+        self.allocations
+            .iter()
+            .try_fold(0u64, |sum, alloc| sum.checked_add(alloc.amount.unwrap_or_default()))
+            .ok_or(ManifestError::AmountOverflow)?;
+        Ok(())
+    }
+
+    fn rebase(&mut self, dir: &Path) {
+        let media = self
+            .terms
+            .iter_mut()
+            .flat_map(|terms| terms.media.as_mut())
+            .chain(self.token.iter_mut().flat_map(|token| {
+                token
+                    .preview
+                    .as_mut()
+                    .into_iter()
+                    .chain(&mut token.attachments)
+            }));
+        for media in media {
+            media.file = dir.join(&media.file);
+        }
+    }
+
+    /// Issues the contract described by the manifest using the issuer of the
+    /// manifest schema.
+    #[allow(clippy::result_large_err)]
+    pub fn issue(&self) -> Result<ValidContract, ManifestError> {
+        self.validate()?;
+        #[cfg(any(
+            feature = "nia",
+            feature = "uda",
+            feature = "cfa",
+            feature = "lfa",
+            feature = "aso"
+        ))]
+        let text = self.terms.as_ref().and_then(|terms| terms.text.as_deref());
+        #[cfg(any(
+            feature = "nia",
+            feature = "uda",
+            feature = "cfa",
+            feature = "lfa",
+            feature = "aso"
+        ))]
+        #[cfg(any(
+            feature = "nia",
+            feature = "uda",
+            feature = "cfa",
+            feature = "lfa",
+            feature = "aso"
+        ))]
+        let media = self
+            .terms
+            .as_ref()
+            .and_then(|terms| terms.media.as_ref())
+            .map(ManifestMedia::attachment)
+            .transpose()?;
+        #[cfg(any(feature = "nia", feature = "cfa", feature = "lfa", feature = "aso"))]
+        let precision = self.precision.unwrap_or_default();
+
+        match self.schema {
+            #[cfg(feature = "nia")]
+            BundledSchema::NonInflatableAsset => {
+                self.forbid(self.token.is_some(), "token")?;
+                self.forbid(self.offer.is_some(), "offer")?;
+                let ticker = self.require(self.ticker.as_deref(), "ticker")?;
+                let mut issuer = Rgb20Wrapper::<MemContract>::testnet::<NonInflatableAsset>(
+                    &self.issuer,
+                    ticker,
+                    &self.name,
+                    self.details.as_deref(),
+                    precision,
+                )?
+                .add_terms(text.unwrap_or_default(), media)?;
+                for alloc in &self.allocations {
+                    let amount = self.require(alloc.amount, "amount")?;
+                    issuer = issuer.allocate(alloc.method, alloc.outpoint, amount)?;
+                }
+                Ok(issuer.issue_contract()?)
+            }
+            #[cfg(feature = "lfa")]
+            BundledSchema::LightningFungibleAsset => {
+                self.forbid(self.token.is_some(), "token")?;
+                self.forbid(self.offer.is_some(), "offer")?;
+                let ticker = self.require(self.ticker.as_deref(), "ticker")?;
+                let mut issuer = Rgb20Wrapper::<MemContract>::testnet::<LightningFungibleAsset>(
+                    &self.issuer,
+                    ticker,
+                    &self.name,
+                    self.details.as_deref(),
+                    precision,
+                )?
+                .add_terms(text.unwrap_or_default(), media)?;
+                for alloc in &self.allocations {
+                    let amount = self.require(alloc.amount, "amount")?;
+                    issuer = issuer.allocate(alloc.method, alloc.outpoint, amount)?;
+                }
+                Ok(issuer.issue_contract()?)
+            }
+            #[cfg(feature = "aso")]
+            BundledSchema::AssetSwapOffer => {
+                self.forbid(self.token.is_some(), "token")?;
+                let ticker = self.require(self.ticker.as_deref(), "ticker")?;
+                let offer = self.require(self.offer.as_ref(), "offer")?;
+                let terms = ContractTerms {
+                    text: match text {
+                        Some(text) => text.parse()?,
+                        None => RicardianContract::default(),
+                    },
+                    media,
+                };
+                let allocations = self
+                    .allocations
+                    .iter()
+                    .map(|alloc| {
+                        let amount = self.require(alloc.amount, "amount")?;
+                        Ok((alloc.method, alloc.outpoint, amount))
+                    })
+                    .collect::<Result<Vec<_>, ManifestError>>()?;
+                Ok(AssetSwapOffer::testnet(
+                    &self.issuer,
+                    ticker,
+                    &self.name,
+                    self.details.as_deref(),
+                    precision,
+                    terms,
+                    OfferTerms::new(offer.counter_asset, offer.counter_amount),
+                    allocations,
+                )?)
+            }
+            #[cfg(feature = "cfa")]
+            BundledSchema::CollectibleFungibleAsset => {
+                self.forbid(self.token.is_some(), "token")?;
+                self.forbid(self.offer.is_some(), "offer")?;
+                self.forbid(self.ticker.is_some(), "ticker")?;
+                let mut issuer = Rgb25Wrapper::<MemContract>::testnet::<CollectibleFungibleAsset>(
+                    &self.issuer,
+                    &self.name,
+                    precision,
+                )?
+                .add_terms(text.unwrap_or_default(), media)?;
+                if let Some(details) = &self.details {
+                    issuer = issuer.add_details(details)?;
+                }
+                for alloc in &self.allocations {
+                    let amount = self.require(alloc.amount, "amount")?;
+                    issuer = issuer.allocate(alloc.method, alloc.outpoint, Amount::from(amount))?;
+                }
+                Ok(issuer.issue_contract()?)
+            }
+            #[cfg(feature = "uda")]
+            BundledSchema::UniqueDigitalAsset => {
+                self.forbid(self.precision.is_some(), "precision")?;
+                self.forbid(self.offer.is_some(), "offer")?;
+                let ticker = self.require(self.ticker.as_deref(), "ticker")?;
+                let token = self.require(self.token.as_ref(), "token")?;
+                let [alloc] = self.allocations.as_slice() else {
+                    return Err(ManifestError::SingleAllocation(self.schema));
+                };
+                self.forbid(alloc.amount.is_some_and(|amount| amount != 1), "amount")?;
+
+                let preview = token
+                    .preview
+                    .as_ref()
+                    .map(ManifestMedia::read)
+                    .transpose()?;
+                let attachments = token
+                    .attachments
+                    .iter()
+                    .map(ManifestMedia::read)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let token_data = UniqueDigitalAsset::token_data(
+                    ticker,
+                    &self.name,
+                    self.details.as_deref(),
+                    token.index,
+                    preview.as_ref().map(|(ty, data)| (*ty, data.as_slice())),
+                    attachments.iter().map(|(ty, data)| (*ty, data.as_slice())),
+                )?;
+                let terms = ContractTerms {
+                    text: match text {
+                        Some(text) => text.parse()?,
+                        None => RicardianContract::default(),
+                    },
+                    media,
+                };
+                Ok(UniqueDigitalAsset::issue(
+                    &self.issuer,
+                    terms,
+                    token_data,
+                    alloc.method,
+                    alloc.outpoint,
+                )?)
+            }
+        }
+    }
+
+    #[cfg(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    ))]
+    fn require<T>(&self, value: Option<T>, field: &'static str) -> Result<T, ManifestError> {
+        value.ok_or(ManifestError::MissingField(self.schema, field))
+    }
+
+    #[cfg(any(
+        feature = "nia",
+        feature = "uda",
+        feature = "cfa",
+        feature = "lfa",
+        feature = "aso"
+    ))]
+    fn forbid(&self, present: bool, field: &'static str) -> Result<(), ManifestError> {
+        if present {
+            return Err(ManifestError::UnexpectedField(self.schema, field));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use ifaces::IssuerWrapper;
+    use rgbstd::containers::ConsignmentExt;
+    use rgbstd::interface::IfaceWrapper;
+    use rgbstd::persistence::Stock;
+
+    use super::*;
+    use crate::dumb::NoResolver;
+
+    #[test]
+    fn nia_yaml() {
+        let manifest = IssueManifest::from_yaml(
+            r#"
+schema: NonInflatableAsset
+ticker: TEST
+name: Test asset
+precision: centiMicro
+terms:
+  text: Issued for testing purposes only
+allocations:
+  - method: tapretFirst
+    outpoint: 14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1
+    amount: 100000
+  - outpoint: 14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:2
+    amount: 500
+"#,
+        )
+        .unwrap();
+        let contract = manifest.issue().unwrap();
+        let contract_id = contract.contract_id();
+
+        let mut stock = Stock::in_memory();
+        stock.import_contract(contract, NoResolver).unwrap();
+        let contract = stock
+            .contract_iface_class::<ifaces::Rgb20>(contract_id)
+            .unwrap();
+        assert_eq!(contract.total_issued_supply(), Amount::from(100500u64));
+        assert_eq!(contract.spec().ticker.as_str(), "TEST");
+        assert_eq!(contract.schema_id(), NonInflatableAsset::schema().schema_id());
+    }
+
+    #[test]
+    fn cfa_json() {
+        let manifest = IssueManifest::from_json(
+            r#"{
+  "schema": "CFA",
+  "name": "Test asset",
+  "details": "Some details",
+  "allocations": [{
+    "outpoint": "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1",
+    "amount": 100
+  }]
+}"#,
+        )
+        .unwrap();
+        let contract = manifest.issue().unwrap();
+        assert_eq!(contract.schema_id(), CollectibleFungibleAsset::schema().schema_id());
+    }
+
+    #[test]
+    fn lfa_json() {
+        let manifest = IssueManifest::from_json(
+            r#"{
+  "schema": "LFA",
+  "ticker": "TEST",
+  "name": "Test asset",
+  "allocations": [{
+    "outpoint": "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1",
+    "amount": 100
+  }]
+}"#,
+        )
+        .unwrap();
+        let contract = manifest.issue().unwrap();
+        assert_eq!(contract.schema_id(), LightningFungibleAsset::schema().schema_id());
+
+        let report = crate::ContractReport::with_valid(contract).unwrap();
+        assert_eq!(report.schema, BundledSchema::LightningFungibleAsset);
+        assert!(matches!(report.state, crate::InterfaceState::Rgb20 { .. }));
+    }
+
+    #[test]
+    fn aso_yaml() {
+        let yaml = r#"
+schema: ASO
+ticker: SWAP
+name: Offered asset
+offer:
+  counterAsset: rgb:pOIzGFyQ-mA!yQq2-QH8vB5!-5fAplY!-x2lW!vz-JHDbYPg
+  counterAmount: 500
+allocations:
+  - outpoint: 14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1
+    amount: 1000
+"#;
+        let manifest = IssueManifest::from_yaml(yaml).unwrap();
+        let contract = manifest.issue().unwrap();
+        assert_eq!(contract.schema_id(), AssetSwapOffer::schema().schema_id());
+
+        let mut manifest = manifest;
+        manifest.offer = None;
+        assert!(matches!(
+            manifest.issue(),
+            Err(ManifestError::MissingField(BundledSchema::AssetSwapOffer, "offer"))
+        ));
+
+        let manifest =
+            IssueManifest::from_yaml(&yaml.replace("schema: ASO", "schema: NIA")).unwrap();
+        assert!(matches!(
+            manifest.issue(),
+            Err(ManifestError::UnexpectedField(BundledSchema::NonInflatableAsset, "offer"))
+        ));
+    }
+
+    #[test]
+    fn uda_yaml() {
+        let manifest = IssueManifest::from_yaml(
+            r#"
+schema: UDA
+ticker: TEST
+name: Test uda
+terms:
+  media:
+    type: text/markdown
+    file: README.md
+allocations:
+  - outpoint: 14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1
+token:
+  index: 2
+  preview:
+    type: text/plain
+    file: LICENSE
+  attachments:
+    - type: text/plain
+      file: DCO
+"#,
+        )
+        .unwrap();
+        let contract = manifest.issue().unwrap();
+        assert_eq!(contract.schema_id(), UniqueDigitalAsset::schema().schema_id());
+    }
+
+    #[test]
+    fn json_for_schema() {
+        let json = r#"{
+  "name": "Test asset",
+  "allocations": [{
+    "outpoint": "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1",
+    "amount": 100
+  }]
+}"#;
+        let manifest =
+            IssueManifest::from_json_for(BundledSchema::CollectibleFungibleAsset, json).unwrap();
+        assert_eq!(manifest.schema, BundledSchema::CollectibleFungibleAsset);
+        assert!(matches!(
+            IssueManifest::from_json_for(
+                BundledSchema::UniqueDigitalAsset,
+                &json.replacen('{', r#"{"schema": "NIA","#, 1)
+            ),
+            Err(ManifestError::SchemaMismatch(
+                BundledSchema::UniqueDigitalAsset,
+                BundledSchema::NonInflatableAsset
+            ))
+        ));
+        assert!(matches!(
+            IssueManifest::from_json_for(BundledSchema::NonInflatableAsset, "[]"),
+            Err(ManifestError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_issuer() {
+        for issuer in ["''", "ssi:анонім"] {
+            let yaml = format!(
+                r#"
+schema: NIA
+issuer: {issuer}
+ticker: TEST
+name: Test asset
+allocations: []
+"#
+            );
+            assert!(matches!(
+                IssueManifest::from_yaml(&yaml),
+                Err(ManifestError::InvalidIssuer(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn amount_overflow() {
+        let json = r#"{
+  "ticker": "TEST",
+  "name": "Test asset",
+  "allocations": [{
+    "outpoint": "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1",
+    "amount": 18446744073709551615
+  }, {
+    "outpoint": "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:2",
+    "amount": 1
+  }]
+}"#;
+        for schema in BundledSchema::ALL {
+            assert!(matches!(
+                IssueManifest::from_json_for(schema, json),
+                Err(ManifestError::AmountOverflow)
+            ));
+        }
+    }
+
+    #[test]
+    fn missing_fields() {
+        let manifest = IssueManifest::from_yaml(
+            r#"
+schema: NonInflatableAsset
+name: Test asset
+allocations: []
+"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            manifest.issue(),
+            Err(ManifestError::MissingField(BundledSchema::NonInflatableAsset, "ticker"))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Media files referenced from contract state.
+
+use std::str::FromStr;
+
+use amplify::Bytes32;
+use rgbstd::stl::{Attachment, MediaRegName, MediaType};
+use sha2::{Digest, Sha256};
+use strict_encoding::InvalidRString;
+
+/// Constructs an attachment committing to the provided file `data` with its
+/// SHA256 digest.
+pub(crate) fn attachment(ty: &str, data: &[u8]) -> Result<Attachment, InvalidRString> {
+    let digest = Sha256::digest(data);
+    Ok(Attachment {
+        ty: media_type(ty)?,
+        digest: Bytes32::from_byte_array(digest),
+    })
+}
+
+pub(crate) fn media_type(s: &str) -> Result<MediaType, InvalidRString> {
+    let (ty, subtype) = s.split_once('/').unwrap_or((s, "*"));
+    Ok(MediaType {
+        ty: MediaRegName::from_str(ty)?,
+        subtype: match subtype {
+            "*" => None,
+            subtype => Some(MediaRegName::from_str(subtype)?),
+        },
+        charset: None,
+    })
+}
@@ -0,0 +1,265 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Schema variants declaring [`TransferMemo`] metadata on the transfers, which
+//! lets invoices and accounting link a transfer to the payment it settles.
+//!
+//! Since the validation requires each of the metadata types declared by a
+//! transition schema to be present, transfers under the variants always carry
+//! the memo, which is [`TransferMemo::Empty`] when there is nothing to attach.
+//! Transfers built with [`crate::TransferBuilder`] carry the empty memo unless
+//! another one is added, so the memo is absent by default.
+
+use amplify::confinement::U16;
+use amplify::Wrapper;
+use rgbstd::interface::{IfaceImpl, NamedField};
+use rgbstd::stl::StandardTypes;
+use rgbstd::{MetaType, Schema, Transition};
+use strict_encoding::StrictDeserialize;
+use strict_types::TypeSystem;
+
+use crate::stl::schemata_types;
+use crate::{schemata_stl, TransferMemo, TS_TRANSFER};
+
+pub(crate) const MT_TRANSFER_MEMO: MetaType = MetaType::with(4101);
+
+/// Name of the transfer metadata with [`TransferMemo`] in the interface
+/// implementations of the schema variants.
+pub(crate) const FIELD_TRANSFER_MEMO: &str = "transferMemo";
+
+/// Declares memo metadata for the transfers of the schema.
+pub(crate) fn declare_memo(schema: &mut Schema) {
+    let types = StandardTypes::with(schemata_stl());
+    schema
+        .meta_types
+        .insert(MT_TRANSFER_MEMO, types.get("RGBSchemata.TransferMemo"))
+        .expect("too many metadata types");
+    schema
+        .transitions
+        .get_mut(&TS_TRANSFER)
+        .expect("bundled schemata always have transfer transition")
+        .metadata
+        .push(MT_TRANSFER_MEMO)
+        .expect("too many transfer metadata types");
+}
+
+/// Names memo metadata in the interface implementation of the schema variant.
+pub(crate) fn name_memo(iimpl: &mut IfaceImpl, schema: &Schema) {
+    iimpl.schema_id = schema.schema_id();
+    iimpl
+        .metadata
+        .push(NamedField::with(MT_TRANSFER_MEMO, fname!(FIELD_TRANSFER_MEMO)))
+        .expect("too many metadata fields");
+}
+
+/// Adds the types of the memo metadata to the type system.
+pub(crate) fn memo_types(types: &mut TypeSystem) {
+    types
+        .extend(schemata_types(["RGBSchemata.TransferMemo"]))
+        .expect("too many types");
+}
+
+impl TransferMemo {
+    /// Extracts memo from a transfer of a contract issued under one of the
+    /// schema variants with memo, returning `None` if the transition carries
+    /// [`TransferMemo::Empty`] or doesn't carry a well-formed memo.
+    pub fn from_transition(transition: &Transition) -> Option<Self> {
+        let value = transition.metadata.get(&MT_TRANSFER_MEMO)?;
+        match TransferMemo::from_strict_serialized::<U16>(value.to_inner()).ok()? {
+            TransferMemo::Empty => None,
+            memo => Some(memo),
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use std::str::FromStr;
+
+    use bp::dbc::Method;
+    use bp::{Outpoint, Txid};
+    use ifaces::rgb20::PrimaryIssue;
+    use ifaces::Rgb20;
+    use rgbstd::containers::BuilderSeal;
+    use rgbstd::interface::BuilderError;
+    use rgbstd::persistence::PersistedState;
+    use rgbstd::validation::Failure;
+    use rgbstd::{Assign, Genesis, GraphSeal, MetadataError, Operation, Opout, Precision, XChain};
+    use strict_encoding::StrictSerialize;
+
+    use super::*;
+    use crate::testkit::transfer;
+    use crate::{
+        lint, BundledSchema, LintIssue, OpValidator, PaymentRef, SchemaBuilder, TransferBuilder,
+        OS_ASSET,
+    };
+
+    const TXID: &str = "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5";
+
+    fn issue(builder: &SchemaBuilder) -> Genesis {
+        let contract = PrimaryIssue::testnet_with(
+            builder.issuer(Rgb20::FIXED),
+            "ssi:anonymous",
+            "MEMO",
+            "Asset with transfer memo",
+            None,
+            Precision::CentiMicro,
+        )
+        .unwrap()
+        .allocate(Method::TapretFirst, Outpoint::new(Txid::from_str(TXID).unwrap(), 1), 100000u64)
+        .unwrap()
+        .issue_contract()
+        .unwrap();
+        contract.genesis.clone()
+    }
+
+    fn validate(memo: Option<TransferMemo>) -> (Transition, Vec<Failure>) {
+        let builder = BundledSchema::NonInflatableAsset.builder().transfer_memo();
+        let genesis = issue(&builder);
+        let mut transition = transfer(&genesis, OS_ASSET);
+        if let Some(memo) = memo {
+            let value = memo.to_strict_serialized::<U16>().unwrap();
+            transition
+                .metadata
+                .add_value(MT_TRANSFER_MEMO, value.into())
+                .unwrap();
+        }
+        let mut validator =
+            OpValidator::new(builder.schema(), builder.types(), builder.scripts(), genesis);
+        let opid = validator.add_transition(transition.clone());
+        (transition, validator.validate(opid).failures)
+    }
+
+    /// Transfers the whole genesis allocation to a single output.
+    fn build(
+        variant: &SchemaBuilder,
+        genesis: &Genesis,
+        f: impl FnOnce(TransferBuilder) -> Result<TransferBuilder, BuilderError>,
+    ) -> Result<Transition, BuilderError> {
+        let Assign::Revealed { state, .. } = genesis.assignments[&OS_ASSET].as_fungible()[0] else {
+            unreachable!("genesis allocations are revealed")
+        };
+        let seal = GraphSeal::new_random(Method::TapretFirst, Txid::from_str(TXID).unwrap(), 2);
+        let builder = variant.transfer(genesis.contract_id()).with(|builder| {
+            builder
+                .add_asset_tag("assetOwner", state.tag)?
+                .add_input(
+                    Opout::new(genesis.id(), OS_ASSET, 0),
+                    PersistedState::Amount(state.value.as_u64().into(), state.blinding, state.tag),
+                )?
+                .add_fungible_state(
+                    "assetOwner",
+                    BuilderSeal::from(XChain::Bitcoin(seal)),
+                    state.value.as_u64(),
+                )
+        })?;
+        f(builder)?.complete_transition()
+    }
+
+    #[test]
+    fn variants() {
+        for schema in BundledSchema::ALL {
+            let builder = schema.builder().transfer_memo();
+            assert!(!builder.is_official());
+
+            let variant = builder.schema();
+            let iimpl = builder.issue_impl();
+            assert_ne!(variant.schema_id(), schema.schema().schema_id());
+            assert_eq!(iimpl.schema_id, variant.schema_id());
+            assert_eq!(
+                iimpl.meta_type(&fname!(SchemaBuilder::TRANSFER_MEMO)),
+                Some(MT_TRANSFER_MEMO)
+            );
+            if let Err(err) = iimpl.check(&schema.iface(), &variant) {
+                panic!("{schema} memo variant interface implementation doesn't match: {err:?}");
+            }
+            assert!(lint(&variant, &iimpl, &builder.scripts())
+                .iter()
+                .all(|issue| matches!(issue, LintIssue::UnnamedErrno(..))));
+        }
+    }
+
+    #[test]
+    fn reference() {
+        let memo = TransferMemo::Reference(PaymentRef::from_str("INV-2024-0042").unwrap());
+        let (transition, failures) = validate(Some(memo.clone()));
+        assert_eq!(failures, vec![]);
+        assert_eq!(TransferMemo::from_transition(&transition), Some(memo));
+    }
+
+    #[test]
+    fn empty() {
+        let (transition, failures) = validate(Some(TransferMemo::Empty));
+        assert_eq!(failures, vec![]);
+        assert_eq!(TransferMemo::from_transition(&transition), None);
+    }
+
+    #[test]
+    fn absent() {
+        let (transition, failures) = validate(None);
+        assert_eq!(failures, vec![Failure::SchemaNoMetadata(transition.id(), MT_TRANSFER_MEMO)]);
+        assert_eq!(TransferMemo::from_transition(&transition), None);
+    }
+
+    #[test]
+    fn transfer_builder() {
+        let variant = BundledSchema::NonInflatableAsset.builder().transfer_memo();
+        let genesis = issue(&variant);
+        let memo = TransferMemo::Reference(PaymentRef::from_str("INV-2024-0042").unwrap());
+
+        let with_memo =
+            build(&variant, &genesis, |builder| builder.add_memo(memo.clone())).unwrap();
+        assert_eq!(TransferMemo::from_transition(&with_memo), Some(memo.clone()));
+        let without_memo = build(&variant, &genesis, Ok).unwrap();
+        assert_eq!(TransferMemo::from_transition(&without_memo), None);
+
+        let mut validator =
+            OpValidator::new(variant.schema(), variant.types(), variant.scripts(), genesis.clone());
+        for transition in [with_memo, without_memo] {
+            let opid = validator.add_transition(transition);
+            assert_eq!(validator.validate(opid).failures, vec![]);
+        }
+
+        let err = build(&variant, &genesis, |builder| {
+            builder
+                .add_memo(memo.clone())?
+                .add_metadata(SchemaBuilder::TRANSFER_MEMO, TransferMemo::Empty)
+        })
+        .unwrap_err();
+        assert_eq!(
+            err,
+            BuilderError::MetadataInvalid(MetadataError::AlreadyExists(MT_TRANSFER_MEMO))
+        );
+
+        let official = BundledSchema::NonInflatableAsset.builder();
+        let err =
+            build(&official, &issue(&official), |builder| builder.add_memo(memo)).unwrap_err();
+        assert_eq!(err, BuilderError::MetadataNotFound(fname!(SchemaBuilder::TRANSFER_MEMO)));
+    }
+}
@@ -21,13 +21,21 @@
 
 //! Non-Inflatable Assets (NIA) schema implementing RGB20 fungible assets
 //! interface.
+//!
+//! The validation library of the schema is also used by the CFA, LFA and ASO
+//! schemata, thus the module is compiled with any of `nia`, `cfa`, `lfa` or
+//! `aso` features, while the schema itself requires `nia` feature.
+
+#![cfg_attr(not(feature = "nia"), allow(unused_imports))]
+
+use std::str::FromStr;
 
 use aluvm::isa::opcodes::INSTR_PUTA;
-use aluvm::isa::Instr;
 use aluvm::library::{Lib, LibSite};
 use amplify::confinement::Confined;
 use bp::dbc::Method;
-use ifaces::{IssuerWrapper, Rgb20, Rgb20Wrapper, LNPBP_IDENTITY};
+use ifaces::rgb20::IssuerError;
+use ifaces::{IssuerWrapper, Rgb20, Rgb20Wrapper};
 use rgbstd::containers::ValidContract;
 use rgbstd::interface::{IfaceClass, IfaceImpl, NamedField, NamedVariant, TxOutpoint, VerNo};
 use rgbstd::persistence::MemContract;
@@ -38,18 +46,41 @@ use rgbstd::schema::{
 use rgbstd::stl::StandardTypes;
 use rgbstd::validation::Scripts;
 use rgbstd::vm::opcodes::INSTR_PCVS;
-use rgbstd::vm::RgbIsa;
-use rgbstd::{rgbasm, Amount, Identity, Precision};
+use rgbstd::{Amount, AssetTag, BlindingFactor, Identity, Precision};
 use strict_encoding::InvalidRString;
 use strict_types::TypeSystem;
 
+use crate::asm::{schemata_asm, Asm};
 use crate::{
-    ERRNO_ISSUED_MISMATCH, ERRNO_NON_EQUAL_IN_OUT, GS_ISSUED_SUPPLY, GS_NOMINAL, GS_TERMS,
-    OS_ASSET, TS_TRANSFER,
+    SchemaParams, ERRNO_ISSUED_MISMATCH, ERRNO_NON_EQUAL_IN_OUT, GS_ISSUED_SUPPLY, GS_NOMINAL,
+    GS_TERMS, OS_ASSET, TS_TRANSFER,
 };
 
-pub(crate) fn nia_lib() -> Lib {
-    let code = rgbasm! {
+/// Errors of the deterministic issuance of fungible assets.
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum FungibleIssueError {
+    #[from]
+    #[display(inner)]
+    InvalidString(InvalidRString),
+
+    #[from]
+    #[display(inner)]
+    Issuer(IssuerError),
+
+    /// invalid issuer identity '{0}'; it must be a non-empty string of
+    /// printable ASCII characters.
+    InvalidIssuer(String),
+}
+
+/// Parses identity of the contract issuer, checking it before it reaches the
+/// contract builders, which panic on invalid identities.
+pub(crate) fn issuer_identity(issuer: &str) -> Result<Identity, FungibleIssueError> {
+    Identity::from_str(issuer).map_err(|_| FungibleIssueError::InvalidIssuer(issuer.to_owned()))
+}
+
+pub(crate) fn nia_asm() -> Asm {
+    schemata_asm! {
         // SUBROUTINE Transfer validation
         // Set errno
         put     a8[0],ERRNO_NON_EQUAL_IN_OUT;
@@ -73,13 +104,22 @@ pub(crate) fn nia_lib() -> Lib {
         pcas    OS_ASSET;
         test;
         ret;
-    };
-    Lib::assemble::<Instr<RgbIsa<MemContract>>>(&code).expect("wrong non-inflatable asset script")
+    }
+}
+
+pub(crate) fn nia_lib() -> Lib {
+    Lib::assemble(&nia_asm().code).expect("wrong non-inflatable asset script")
 }
+#[cfg(any(feature = "nia", feature = "cfa", feature = "lfa"))]
 pub(crate) const FN_NIA_GENESIS_OFFSET: u16 = 4 + 3 + 2;
+#[cfg(any(feature = "nia", feature = "cfa", feature = "aso"))]
 pub(crate) const FN_NIA_TRANSFER_OFFSET: u16 = 0;
 
-fn nia_schema() -> Schema {
+#[cfg(feature = "nia")]
+fn nia_schema() -> Schema { nia_schema_with(&SchemaParams::default()) }
+
+#[cfg(feature = "nia")]
+pub(crate) fn nia_schema_with(params: &SchemaParams) -> Schema {
     let types = StandardTypes::with(Rgb20::FIXED.stl());
 
     let alu_lib = nia_lib();
@@ -92,9 +132,9 @@ fn nia_schema() -> Schema {
     Schema {
         ffv: zero!(),
         flags: none!(),
-        name: tn!("NonInflatableAsset"),
-        timestamp: 1713343888,
-        developer: Identity::from(LNPBP_IDENTITY),
+        name: params.name_or(tn!("NonInflatableAsset")),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
         meta_types: none!(),
         global_types: tiny_bmap! {
             GS_NOMINAL => GlobalStateSchema::once(types.get("RGBContract.AssetSpec")),
@@ -137,16 +177,20 @@ fn nia_schema() -> Schema {
     }
 }
 
-fn nia_rgb20() -> IfaceImpl {
-    let schema = nia_schema();
+#[cfg(feature = "nia")]
+fn nia_rgb20() -> IfaceImpl { nia_rgb20_with(&SchemaParams::default()) }
+
+#[cfg(feature = "nia")]
+pub(crate) fn nia_rgb20_with(params: &SchemaParams) -> IfaceImpl {
+    let schema = nia_schema_with(params);
     let iface = Rgb20::FIXED;
 
     IfaceImpl {
         version: VerNo::V1,
         schema_id: schema.schema_id(),
         iface_id: iface.iface_id(),
-        timestamp: 1713343888,
-        developer: Identity::from(LNPBP_IDENTITY),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
         metadata: none!(),
         global_state: tiny_bset! {
             NamedField::with(GS_NOMINAL, fname!("spec")),
@@ -168,9 +212,11 @@ fn nia_rgb20() -> IfaceImpl {
     }
 }
 
+#[cfg(feature = "nia")]
 #[derive(Default)]
 pub struct NonInflatableAsset;
 
+#[cfg(feature = "nia")]
 impl IssuerWrapper for NonInflatableAsset {
     const FEATURES: Rgb20 = Rgb20::FIXED;
     type IssuingIface = Rgb20;
@@ -186,7 +232,14 @@ impl IssuerWrapper for NonInflatableAsset {
     }
 }
 
+#[cfg(feature = "nia")]
 impl NonInflatableAsset {
+    /// Issues contract allocating the asset to the provided outpoints.
+    ///
+    /// # Panics
+    ///
+    /// If the issuer identity is invalid or the allocated amounts overflow;
+    /// use [`Self::try_testnet`] to get these failures as errors.
     pub fn testnet(
         issuer: &str,
         ticker: &str,
@@ -195,23 +248,72 @@ impl NonInflatableAsset {
         precision: Precision,
         allocations: impl IntoIterator<Item = (Method, impl TxOutpoint, impl Into<Amount>)>,
     ) -> Result<ValidContract, InvalidRString> {
+        Self::try_testnet(issuer, ticker, name, details, precision, allocations).map_err(|err| {
+            match err {
+                FungibleIssueError::InvalidString(err) => err,
+                err => panic!("invalid contract data: {err}"),
+            }
+        })
+    }
+
+    /// Issues contract allocating the asset to the provided outpoints,
+    /// returning an error instead of panicking on invalid issuer identity or
+    /// allocated amount overflow.
+    pub fn try_testnet(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        allocations: impl IntoIterator<Item = (Method, impl TxOutpoint, impl Into<Amount>)>,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        issuer_identity(issuer)?;
         let mut issuer =
             Rgb20Wrapper::<MemContract>::testnet::<Self>(issuer, ticker, name, details, precision)?;
         for (method, beneficiary, amount) in allocations {
-            issuer = issuer
-                .allocate(method, beneficiary, amount)
-                .expect("invalid contract data");
+            issuer = issuer.allocate(method, beneficiary, amount)?;
         }
         Ok(issuer.issue_contract().expect("invalid contract data"))
     }
+
+    /// Issues contract in a deterministic way, such that the same arguments
+    /// always produce the same contract id.
+    ///
+    /// Each allocation is provided with the seal closing method, outpoint and
+    /// blinding, followed by the allocated amount and its blinding factor.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet_det(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        precision: Precision,
+        asset_tag: AssetTag,
+        allocations: impl IntoIterator<
+            Item = (Method, impl TxOutpoint, u64, impl Into<Amount>, BlindingFactor),
+        >,
+        timestamp: i64,
+    ) -> Result<ValidContract, FungibleIssueError> {
+        issuer_identity(issuer)?;
+        let mut issuer = Rgb20Wrapper::<MemContract>::testnet_det::<Self>(
+            issuer, ticker, name, details, precision, asset_tag,
+        )?;
+        for (method, beneficiary, seal_blinding, amount, amount_blinding) in allocations {
+            issuer =
+                issuer.allocate_det(method, beneficiary, seal_blinding, amount, amount_blinding)?;
+        }
+        Ok(issuer
+            .issue_contract_det(timestamp)
+            .expect("invalid contract data"))
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "nia"))]
 mod test {
     use std::str::FromStr;
 
     use bp::seals::txout::{BlindSeal, CloseMethod};
-    use bp::Txid;
+    use bp::{Outpoint, Txid};
     use chrono::DateTime;
     use rgbstd::containers::{BuilderSeal, ConsignmentExt};
     use rgbstd::interface::*;
@@ -297,4 +399,93 @@ mod test {
             s!("rgb:pOIzGFyQ-mA!yQq2-QH8vB5!-5fAplY!-x2lW!vz-JHDbYPg")
         );
     }
+
+    #[test]
+    fn testnet_det() {
+        let created_at = 1713261744;
+        let asset_tag = AssetTag::new_deterministic(
+            "contract_domain",
+            AssignmentType::with(0),
+            DateTime::from_timestamp(created_at, 0).unwrap(),
+            123456,
+        );
+        let outpoint = Outpoint::new(
+            Txid::from_str("8d54c98d4c29a1ec4fd90635f543f0f7a871a78eb6a6e706342f831d92e3ba19")
+                .unwrap(),
+            0,
+        );
+        let blinding = BlindingFactor::from_str(
+            "a3401bcceb26201b55978ff705fecf7d8a0a03598ebeccf2a947030b91a0ff53",
+        )
+        .unwrap();
+
+        let contract = NonInflatableAsset::testnet_det(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            Precision::try_from(2).unwrap(),
+            asset_tag,
+            [(Method::OpretFirst, outpoint, 654321, 999u64, blinding)],
+            created_at,
+        )
+        .unwrap();
+
+        assert_eq!(
+            contract.contract_id().to_string(),
+            s!("rgb:pOIzGFyQ-mA!yQq2-QH8vB5!-5fAplY!-x2lW!vz-JHDbYPg")
+        );
+
+        let overflow = NonInflatableAsset::testnet_det(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            Precision::try_from(2).unwrap(),
+            asset_tag,
+            [
+                (Method::OpretFirst, outpoint, 654321, u64::MAX, blinding),
+                (Method::OpretFirst, outpoint, 654322, 1u64, blinding),
+            ],
+            created_at,
+        );
+        assert_eq!(overflow.unwrap_err(), FungibleIssueError::Issuer(IssuerError::AmountOverflow));
+    }
+
+    #[test]
+    fn try_testnet() {
+        let outpoint = Outpoint::new(
+            Txid::from_str("8d54c98d4c29a1ec4fd90635f543f0f7a871a78eb6a6e706342f831d92e3ba19")
+                .unwrap(),
+            0,
+        );
+        let issue = |issuer: &str, amounts: &[u64]| {
+            NonInflatableAsset::try_testnet(
+                issuer,
+                "TICKER",
+                "NAME",
+                None,
+                Precision::try_from(2).unwrap(),
+                amounts
+                    .iter()
+                    .map(|amount| (Method::OpretFirst, outpoint, *amount)),
+            )
+        };
+
+        assert!(issue("ssi:anonymous", &[999]).is_ok());
+        assert_eq!(issue("", &[999]).unwrap_err(), FungibleIssueError::InvalidIssuer(s!("")));
+        assert_eq!(
+            issue("ssi:anonymous", &[u64::MAX, 1]).unwrap_err(),
+            FungibleIssueError::Issuer(IssuerError::AmountOverflow)
+        );
+        assert!(NonInflatableAsset::testnet(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            Precision::try_from(2).unwrap(),
+            [(Method::OpretFirst, outpoint, 999u64)],
+        )
+        .is_ok());
+    }
 }
@@ -0,0 +1,327 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing of kits by the developer of the schemata they contain.
+//!
+//! Developer identity is a BIP340 public key written as `ssi:<baid64>` string
+//! into `developer` field of the schema and interface implementation. The
+//! signatures commit to the schema and interface implementation ids and are
+//! kept in the kit `signatures` field, such that anyone importing the kit may
+//! check that it is published by the declared developer.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use amplify::confinement::{Confined, NonEmptyOrdMap};
+use amplify::ByteArray;
+use baid64::{Baid64ParseError, DisplayBaid64, FromBaid64Str};
+use bp::secp256k1::{schnorr, Keypair, XOnlyPublicKey, SECP256K1};
+use rgbstd::containers::{ContentId, ContentSigs, Kit, SigBlob};
+use rgbstd::Identity;
+use sha2::{Digest, Sha256};
+
+/// Tag of the signed message, separating kit signatures from signatures made
+/// with the same key for other purposes.
+const SIG_TAG: &[u8] = b"urn:lnp-bp:rgb:schemata:kit-sig#2024-10-18";
+
+/// Public key of a schema developer, which is used as the developer identity.
+#[derive(Wrapper, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, From)]
+#[wrapper(Deref)]
+pub struct DeveloperKey(XOnlyPublicKey);
+
+impl From<Keypair> for DeveloperKey {
+    fn from(keypair: Keypair) -> Self { DeveloperKey(keypair.x_only_public_key().0) }
+}
+
+impl TryFrom<[u8; 32]> for DeveloperKey {
+    type Error = bp::secp256k1::Error;
+
+    fn try_from(data: [u8; 32]) -> Result<Self, Self::Error> {
+        XOnlyPublicKey::from_slice(&data).map(DeveloperKey)
+    }
+}
+
+impl DisplayBaid64 for DeveloperKey {
+    const HRI: &'static str = "ssi";
+    const CHUNKING: bool = true;
+    const PREFIX: bool = true;
+    const EMBED_CHECKSUM: bool = true;
+    const MNEMONIC: bool = false;
+
+    fn to_baid64_payload(&self) -> [u8; 32] { self.0.serialize() }
+}
+
+impl FromBaid64Str for DeveloperKey {}
+
+impl Display for DeveloperKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.fmt_baid64(f) }
+}
+
+impl FromStr for DeveloperKey {
+    type Err = Baid64ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::from_baid64_str(s) }
+}
+
+impl DeveloperKey {
+    /// Developer identity to be put into schema and interface implementation.
+    pub fn identity(&self) -> Identity {
+        Identity::from_str(&self.to_string()).expect("baid64 string is a valid identity")
+    }
+
+    /// Extracts public key from a developer identity, if the identity is a
+    /// key.
+    pub fn with_identity(identity: &Identity) -> Option<Self> {
+        Self::from_str(identity.as_str()).ok()
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum SignError {
+    /// kit doesn't contain any schema.
+    NoSchema,
+
+    /// schema {schema_id} is developed by {developer}, while the signing key
+    /// corresponds to {signer}.
+    NotDeveloper {
+        schema_id: String,
+        developer: Identity,
+        signer: Identity,
+    },
+
+    /// kit has too many signatures.
+    TooManySignatures,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error)]
+#[display(doc_comments)]
+pub enum VerifyError {
+    /// kit doesn't contain any schema.
+    NoSchema,
+
+    /// developer identity {1} of {0} is not a public key, thus its signature
+    /// can't be verified.
+    UnknownIdentity(String, Identity),
+
+    /// {0} is not signed by its developer {1}.
+    Unsigned(String, Identity),
+
+    /// signature of {0} by {1} is invalid.
+    InvalidSignature(String, Identity),
+}
+
+/// Content of the kit together with its developer.
+fn developed_content(kit: &Kit) -> Vec<(ContentId, Identity)> {
+    let schemata = kit
+        .schemata
+        .iter()
+        .map(|schema| (ContentId::Schema(schema.schema_id()), schema.developer.clone()));
+    let iimpls = kit
+        .iimpls
+        .iter()
+        .map(|iimpl| (ContentId::IfaceImpl(iimpl.impl_id()), iimpl.developer.clone()));
+    schemata.chain(iimpls).collect()
+}
+
+fn content_name(content_id: ContentId) -> String {
+    match content_id {
+        ContentId::Schema(id) => format!("schema {id}"),
+        ContentId::Genesis(id) => format!("contract {id}"),
+        ContentId::Iface(id) => format!("interface {id}"),
+        ContentId::IfaceImpl(id) => format!("interface implementation {id}"),
+        ContentId::Suppl(id) => format!("supplement {id}"),
+    }
+}
+
+fn message(content_id: ContentId) -> [u8; 32] {
+    let (tag, id) = match content_id {
+        ContentId::Schema(id) => (0u8, id.to_byte_array()),
+        ContentId::Genesis(id) => (1, id.to_byte_array()),
+        ContentId::Iface(id) => (2, id.to_byte_array()),
+        ContentId::IfaceImpl(id) => (3, id.to_byte_array()),
+        ContentId::Suppl(id) => (4, id.to_byte_array()),
+    };
+    Sha256::new_with_prefix(SIG_TAG)
+        .chain_update([tag])
+        .chain_update(id)
+        .finalize()
+        .into()
+}
+
+/// Signs schemata and interface implementations of the kit developed by the
+/// key owner.
+///
+/// Fails if any of the schemata in the kit is developed by someone else;
+/// interface implementations by other developers are left unsigned.
+pub fn sign_kit(kit: &mut Kit, keypair: &Keypair) -> Result<(), SignError> {
+    if kit.schemata.is_empty() {
+        return Err(SignError::NoSchema);
+    }
+    let signer = DeveloperKey::from(*keypair).identity();
+    if let Some(schema) = kit
+        .schemata
+        .iter()
+        .find(|schema| schema.developer != signer)
+    {
+        return Err(SignError::NotDeveloper {
+            schema_id: schema.schema_id().to_string(),
+            developer: schema.developer.clone(),
+            signer,
+        });
+    }
+
+    for (content_id, developer) in developed_content(kit) {
+        if developer != signer {
+            continue;
+        }
+        let sig = SECP256K1.sign_schnorr_no_aux_rand(&message(content_id), keypair);
+        let sig = SigBlob::from(Confined::from_checked(sig.as_ref().to_vec()));
+        match kit.signatures.get_mut(&content_id) {
+            Some(sigs) => {
+                sigs.insert(signer.clone(), sig)
+                    .map_err(|_| SignError::TooManySignatures)?;
+            }
+            None => {
+                let sigs = ContentSigs::from(NonEmptyOrdMap::with_key_value(signer.clone(), sig));
+                kit.signatures
+                    .insert(content_id, sigs)
+                    .map_err(|_| SignError::TooManySignatures)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verifies that each schema and interface implementation of the kit is
+/// signed by its developer, returning the list of the developers.
+pub fn verify_kit(kit: &Kit) -> Result<Vec<DeveloperKey>, VerifyError> {
+    if kit.schemata.is_empty() {
+        return Err(VerifyError::NoSchema);
+    }
+    let mut developers = vec![];
+    for (content_id, developer) in developed_content(kit) {
+        let name = content_name(content_id);
+        let Some(key) = DeveloperKey::with_identity(&developer) else {
+            return Err(VerifyError::UnknownIdentity(name, developer));
+        };
+        let Some(sig) = kit
+            .signatures
+            .get(&content_id)
+            .and_then(|sigs| sigs.get(&developer))
+        else {
+            return Err(VerifyError::Unsigned(name, developer));
+        };
+        schnorr::Signature::from_slice(sig)
+            .and_then(|sig| SECP256K1.verify_schnorr(&sig, &message(content_id), &key))
+            .map_err(|_| VerifyError::InvalidSignature(name, developer))?;
+        if !developers.contains(&key) {
+            developers.push(key);
+        }
+    }
+    Ok(developers)
+}
+
+#[cfg(all(
+    test,
+    any(feature = "nia", feature = "uda", feature = "cfa", feature = "lfa", feature = "aso")
+))]
+mod test {
+    use bp::secp256k1::SecretKey;
+
+    use super::*;
+    use crate::BundledSchema;
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_slice(&[seed; 32]).unwrap();
+        Keypair::from_secret_key(SECP256K1, &secret)
+    }
+
+    fn kit(schema: BundledSchema, keypair: &Keypair) -> Kit {
+        schema
+            .builder()
+            .developer(DeveloperKey::from(*keypair).identity())
+            .kit()
+    }
+
+    #[test]
+    fn identity() {
+        let key = DeveloperKey::from(keypair(1));
+        let identity = key.identity();
+        assert!(identity.to_string().starts_with("ssi:"));
+        assert_eq!(DeveloperKey::with_identity(&identity), Some(key));
+        assert_eq!(DeveloperKey::with_identity(&Identity::default()), None);
+        assert_eq!(DeveloperKey::with_identity(&Identity::from(ifaces::LNPBP_IDENTITY)), None);
+    }
+
+    #[test]
+    fn sign_verify() {
+        let keypair = keypair(1);
+        for schema in BundledSchema::ALL {
+            let mut kit = kit(schema, &keypair);
+            assert!(matches!(verify_kit(&kit), Err(VerifyError::Unsigned(..))));
+            sign_kit(&mut kit, &keypair).unwrap();
+            assert_eq!(verify_kit(&kit), Ok(vec![DeveloperKey::from(keypair)]));
+            // Signing is idempotent
+            sign_kit(&mut kit, &keypair).unwrap();
+            assert_eq!(verify_kit(&kit), Ok(vec![DeveloperKey::from(keypair)]));
+        }
+    }
+
+    #[test]
+    fn wrong_key() {
+        let schema = BundledSchema::ALL[0];
+        let mut kit = kit(schema, &keypair(1));
+        assert!(matches!(sign_kit(&mut kit, &keypair(2)), Err(SignError::NotDeveloper { .. })));
+        assert!(matches!(
+            sign_kit(&mut schema.kit(), &keypair(1)),
+            Err(SignError::NotDeveloper { .. })
+        ));
+        assert!(matches!(verify_kit(&schema.kit()), Err(VerifyError::UnknownIdentity(..))));
+    }
+
+    #[test]
+    fn forged() {
+        let schema = BundledSchema::ALL[0];
+        let mut kit = kit(schema, &keypair(1));
+        sign_kit(&mut kit, &keypair(1)).unwrap();
+
+        // Signatures from another kit don't match
+        let mut other = schema
+            .builder()
+            .developer(DeveloperKey::from(keypair(1)).identity())
+            .timestamp(1)
+            .kit();
+        other.signatures = kit.signatures.clone();
+        assert!(matches!(verify_kit(&other), Err(VerifyError::Unsigned(..))));
+
+        // Signature by another key under the developer identity
+        let content_id = ContentId::Schema(kit.schemata.first().unwrap().schema_id());
+        let developer = DeveloperKey::from(keypair(1)).identity();
+        let forged = SECP256K1.sign_schnorr_no_aux_rand(&message(content_id), &keypair(2));
+        kit.signatures
+            .get_mut(&content_id)
+            .unwrap()
+            .insert(developer, SigBlob::from(Confined::from_checked(forged.as_ref().to_vec())))
+            .unwrap();
+        assert!(matches!(verify_kit(&kit), Err(VerifyError::InvalidSignature(..))));
+    }
+}
@@ -0,0 +1,256 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Strict types used by the schemata in addition to the ones defined by the
+//! standard RGB type libraries and the interfaces.
+
+use std::str::FromStr;
+
+use amplify::confinement::{self, SmallVec, TinyString};
+use amplify::Bytes32;
+use rgbstd::stl::StandardTypes;
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+use strict_types::stl::std_stl;
+use strict_types::{CompileError, LibBuilder, TypeLib, TypeSystem};
+
+pub const LIB_NAME_RGB_SCHEMATA: &str = "RGBSchemata";
+/// Strict types id for the library providing data types for the schemata.
+pub const LIB_ID_RGB_SCHEMATA: &str =
+    "stl:MW$zbNHq-qQaXd6f-du!cyqP-4l3ioHG-6JxRosM-gz3CDuI#milk-split-watch";
+
+/// Direction of a hashed time-locked contract (HTLC) relative to the local
+/// node of a Lightning channel.
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Display, Default)]
+#[display(lowercase)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA, tags = repr, into_u8, try_from_u8)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+#[repr(u8)]
+pub enum HtlcDirection {
+    #[default]
+    Offered = 0,
+    Received = 1,
+}
+
+/// Asset allocation locked in a hashed time-locked contract (HTLC).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct HtlcOutput {
+    pub direction: HtlcDirection,
+    pub payment_hash: Bytes32,
+    /// Block height after which the HTLC can be timed out.
+    pub cltv_expiry: u32,
+}
+
+/// Role of an asset allocation in a Lightning channel transaction.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA, tags = order, dumb = ChannelOutput::ToLocal)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum ChannelOutput {
+    /// Balance of the node which has signed the commitment transaction.
+    ToLocal,
+    /// Balance of the channel counterparty.
+    ToRemote,
+    /// Amount locked in a pending HTLC.
+    Htlc(HtlcOutput),
+}
+
+/// Roles of the asset allocations of a state transition, listed in the order
+/// of the allocations. Empty list is used by the transitions which don't
+/// belong to a channel.
+#[derive(Wrapper, WrapperMut, Clone, Eq, PartialEq, Hash, Debug, Default, From)]
+#[wrapper(Deref)]
+#[wrapper_mut(DerefMut)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct ChannelOutputs(SmallVec<ChannelOutput>);
+
+impl StrictSerialize for ChannelOutputs {}
+impl StrictDeserialize for ChannelOutputs {}
+
+impl ChannelOutputs {
+    pub fn from_iter_checked(outputs: impl IntoIterator<Item = ChannelOutput>) -> Self {
+        Self(SmallVec::from_iter_checked(outputs))
+    }
+}
+
+/// Reference of the payment settled by a transfer, such as an invoice number.
+#[derive(Wrapper, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Default, From, Display)]
+#[wrapper(Deref)]
+#[display(inner)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct PaymentRef(TinyString);
+
+impl FromStr for PaymentRef {
+    type Err = confinement::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> { TinyString::try_from(s.to_owned()).map(Self) }
+}
+
+/// Payment reference accompanied by a free-form note for the payee.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct StructuredMemo {
+    pub reference: PaymentRef,
+    pub note: TinyString,
+}
+
+/// Memo attached to an asset transfer. Transfers which don't carry any memo
+/// provide [`TransferMemo::Empty`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA, tags = order)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub enum TransferMemo {
+    #[default]
+    #[strict_type(dumb)]
+    Empty,
+    Reference(PaymentRef),
+    Structured(StructuredMemo),
+}
+
+impl StrictSerialize for TransferMemo {}
+impl StrictDeserialize for TransferMemo {}
+
+impl TransferMemo {
+    /// Payment reference of the memo, if any.
+    pub fn reference(&self) -> Option<&PaymentRef> {
+        match self {
+            TransferMemo::Empty => None,
+            TransferMemo::Reference(reference) => Some(reference),
+            TransferMemo::Structured(memo) => Some(&memo.reference),
+        }
+    }
+}
+
+/// Terms of an asset swap offer: the counter-asset and its amount asked by the
+/// maker in exchange for the whole offered supply.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct OfferTerms {
+    /// Id of the counter-asset contract.
+    pub counter_asset: Bytes32,
+    pub counter_amount: u64,
+}
+
+impl StrictSerialize for OfferTerms {}
+impl StrictDeserialize for OfferTerms {}
+
+/// Fill of an asset swap offer by a taker.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[derive(StrictType, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_RGB_SCHEMATA)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "camelCase"))]
+pub struct OfferFill {
+    /// Amount of the offered asset transferred to the taker.
+    pub taken: u64,
+    /// Amount of the counter-asset paid to the maker.
+    pub paid: u64,
+}
+
+impl StrictSerialize for OfferFill {}
+impl StrictDeserialize for OfferFill {}
+
+#[allow(clippy::result_large_err)]
+fn _schemata_stl() -> Result<TypeLib, CompileError> {
+    LibBuilder::new(libname!(LIB_NAME_RGB_SCHEMATA), tiny_bset! {
+        std_stl().to_dependency(),
+    })
+    .transpile::<ChannelOutputs>()
+    .transpile::<TransferMemo>()
+    .transpile::<OfferTerms>()
+    .transpile::<OfferFill>()
+    .compile()
+}
+
+/// Generates strict type library providing data types for the schemata.
+pub fn schemata_stl() -> TypeLib {
+    _schemata_stl().expect("invalid strict type RGBSchemata library")
+}
+
+/// Standard RGB types extended with the given types of [`schemata_stl`] and
+/// the types they depend on. Unlike [`StandardTypes::with`], the type system
+/// doesn't change when unrelated types are added to the library.
+pub(crate) fn schemata_types(names: impl IntoIterator<Item = &'static str>) -> TypeSystem {
+    let lib = StandardTypes::with(schemata_stl());
+    let extract = lib
+        .type_system()
+        .extract(names.into_iter().map(|name| lib.get(name)))
+        .expect("RGBSchemata types are always complete");
+    let mut types = StandardTypes::new().type_system();
+    types.extend(extract).expect("too many types");
+    types
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lib_id() {
+        let lib = schemata_stl();
+        assert_eq!(lib.id().to_string(), LIB_ID_RGB_SCHEMATA);
+    }
+
+    #[test]
+    fn channel_outputs() {
+        let outputs = ChannelOutputs::from_iter_checked([
+            ChannelOutput::ToLocal,
+            ChannelOutput::Htlc(HtlcOutput {
+                direction: HtlcDirection::Received,
+                payment_hash: Bytes32::from_array([0xA5; 32]),
+                cltv_expiry: 840000,
+            }),
+        ]);
+        let data = outputs.to_strict_serialized::<0xFFFF>().unwrap();
+        // length prefix of the list is followed by the tags of the outputs
+        assert_eq!(&data[..3], &[2, 0, 0]);
+        assert_eq!(ChannelOutputs::from_strict_serialized::<0xFFFF>(data).unwrap(), outputs);
+    }
+
+    #[test]
+    fn transfer_memo() {
+        let data = TransferMemo::Empty
+            .to_strict_serialized::<0xFFFF>()
+            .unwrap();
+        assert_eq!(data.as_slice(), &[0]);
+
+        let memo = TransferMemo::Structured(StructuredMemo {
+            reference: PaymentRef::from_str("INV-2024-0042").unwrap(),
+            note: TinyString::try_from(s!("March rent")).unwrap(),
+        });
+        assert_eq!(memo.reference().unwrap().to_string(), "INV-2024-0042");
+        let data = memo.to_strict_serialized::<0xFFFF>().unwrap();
+        assert_eq!(TransferMemo::from_strict_serialized::<0xFFFF>(data).unwrap(), memo);
+
+        assert!(PaymentRef::from_str(&"0".repeat(256)).is_err());
+    }
+}
@@ -0,0 +1,524 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sample contracts of the bundled schemata and operations spending them,
+//! shared by the unit tests, property tests, script tracing tests and fuzzing
+//! targets.
+//!
+//! The module is compiled into the library only with the `testkit` feature,
+//! which is not a part of the library API and may change at any time.
+
+use std::collections::BTreeMap;
+use std::iter;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use amplify::confinement::{Confined, U16};
+use bp::dbc::Method;
+use bp::{Outpoint, Txid};
+use chrono::DateTime;
+use rgbstd::containers::ValidContract;
+use rgbstd::validation::Scripts;
+use rgbstd::{
+    Allocation, Amount, AssetTag, Assign, AssignmentType, Assignments, BlindingFactor, ContractId,
+    ExposedSeal, Genesis, GlobalValues, GraphSeal, Input, Inputs, OpId, Operation, Opout,
+    Precision, RevealedData, RevealedValue, Schema, Transition, TypedAssigns, XChain,
+};
+use strict_encoding::{StrictDeserialize, StrictSerialize};
+use strict_types::TypeSystem;
+
+use crate::{
+    AssetSwapOffer, BundledSchema, ChannelOutput, ChannelOutputs, CollectibleFungibleAsset,
+    LightningFungibleAsset, NonInflatableAsset, OfferFill, OfferTerms, OpValidator, SchemaErrno,
+    UniqueDigitalAsset, GS_FILL, GS_ISSUED_SUPPLY, OS_ASSET, OS_LOT, TS_TAKE, TS_TRANSFER,
+};
+
+pub const TIMESTAMP: i64 = 1713261744;
+pub const SEAL_BLINDING: u64 = 654321;
+
+/// Contract issued under the schema with fixed parameters, such that its id is
+/// pinned by the golden vectors.
+pub fn sample_contract(schema: BundledSchema) -> ValidContract {
+    let asset_tag = AssetTag::new_deterministic(
+        "contract_domain",
+        AssignmentType::with(0),
+        DateTime::from_timestamp(TIMESTAMP, 0).unwrap(),
+        123456,
+    );
+    let outpoint = Outpoint::new(
+        Txid::from_str("8d54c98d4c29a1ec4fd90635f543f0f7a871a78eb6a6e706342f831d92e3ba19").unwrap(),
+        0,
+    );
+    let blinding = BlindingFactor::from_str(
+        "a3401bcceb26201b55978ff705fecf7d8a0a03598ebeccf2a947030b91a0ff53",
+    )
+    .unwrap();
+    let allocations = [(Method::OpretFirst, outpoint, SEAL_BLINDING, 999u64, blinding)];
+
+    match schema {
+        BundledSchema::NonInflatableAsset => NonInflatableAsset::testnet_det(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            Precision::Centi,
+            asset_tag,
+            allocations,
+            TIMESTAMP,
+        )
+        .unwrap(),
+        BundledSchema::UniqueDigitalAsset => UniqueDigitalAsset::testnet_det(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            0,
+            Some(("image/png", &[0u8; 4][..])),
+            [("text/plain", &b"attachment"[..])],
+            Method::OpretFirst,
+            outpoint,
+            SEAL_BLINDING,
+            123456,
+            TIMESTAMP,
+        )
+        .unwrap(),
+        BundledSchema::CollectibleFungibleAsset => CollectibleFungibleAsset::testnet_det(
+            "ssi:anonymous",
+            "NAME",
+            None,
+            Precision::Centi,
+            asset_tag,
+            allocations,
+            TIMESTAMP,
+        )
+        .unwrap(),
+        BundledSchema::LightningFungibleAsset => LightningFungibleAsset::testnet_det(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            Precision::Centi,
+            asset_tag,
+            allocations,
+            TIMESTAMP,
+        )
+        .unwrap(),
+        BundledSchema::AssetSwapOffer => AssetSwapOffer::testnet_det(
+            "ssi:anonymous",
+            "TICKER",
+            "NAME",
+            None,
+            Precision::Centi,
+            OfferTerms::new(ContractId::from([0x11; 32]), 500u64),
+            asset_tag,
+            [(Method::OpretFirst, outpoint, SEAL_BLINDING, 999u64, 123456u128)],
+            TIMESTAMP,
+        )
+        .unwrap(),
+    }
+}
+
+/// Schema data and the sample genesis, which are expensive to construct and
+/// thus are shared between test cases and fuzzing iterations.
+pub struct Fixture {
+    schema: Schema,
+    types: TypeSystem,
+    scripts: Scripts,
+    pub genesis: Genesis,
+}
+
+impl Fixture {
+    pub fn get(schema: BundledSchema) -> &'static Fixture {
+        static FIXTURES: OnceLock<BTreeMap<BundledSchema, Fixture>> = OnceLock::new();
+        let fixtures = FIXTURES.get_or_init(|| {
+            BundledSchema::ALL
+                .into_iter()
+                .map(|schema| {
+                    (schema, Fixture {
+                        schema: schema.schema(),
+                        types: schema.types(),
+                        scripts: schema.scripts(),
+                        genesis: sample_contract(schema).genesis.clone(),
+                    })
+                })
+                .collect()
+        });
+        &fixtures[&schema]
+    }
+
+    pub fn validator(&self, genesis: Genesis) -> OpValidator {
+        OpValidator::new(self.schema.clone(), self.types.clone(), self.scripts.clone(), genesis)
+    }
+}
+
+/// Genesis of the sample contract issued under the schema.
+pub fn genesis(schema: BundledSchema) -> Genesis { Fixture::get(schema).genesis.clone() }
+
+/// Transfer spending all genesis assignments of the given type into the same
+/// state.
+pub fn transfer(genesis: &Genesis, ty: AssignmentType) -> Transition {
+    let opid = genesis.id();
+    let count = genesis
+        .assignments
+        .get(&ty)
+        .expect("genesis assignment")
+        .len_u16();
+    let inputs = (0..count).map(|no| Input::with(Opout::new(opid, ty, no)));
+    Transition {
+        ffv: zero!(),
+        contract_id: genesis.contract_id(),
+        nonce: u64::MAX,
+        transition_type: TS_TRANSFER,
+        metadata: none!(),
+        globals: none!(),
+        inputs: Inputs::from(Confined::from_iter_checked(inputs)),
+        assignments: genesis.assignments.transmutate_seals(),
+        valencies: none!(),
+        validator: none!(),
+        witness: none!(),
+    }
+}
+
+/// Tags all asset outputs of an LFA transfer as the local balance, as required
+/// by the schema; transfers of other schemata are left intact.
+pub fn tag_outputs(schema: BundledSchema, transition: &mut Transition) {
+    if schema != BundledSchema::LightningFungibleAsset {
+        return;
+    }
+    let count = transition
+        .assignments
+        .get(&OS_ASSET)
+        .map_or(0, |a| a.len_u16());
+    ChannelOutputs::from_iter_checked(iter::repeat(ChannelOutput::ToLocal).take(count as usize))
+        .attach(transition)
+        .unwrap();
+}
+
+/// Operation together with the outcome expected from its validation.
+pub struct Scenario {
+    pub name: &'static str,
+    pub validator: OpValidator,
+    pub opid: OpId,
+    pub errno: Option<SchemaErrno>,
+}
+
+/// Takes all the lots of the ASO genesis, paying them out into a single
+/// allocation of the asset and recording the fill of the offer.
+pub fn take(genesis: &Genesis) -> Transition {
+    let mut transition = transfer(genesis, OS_LOT);
+    let taken = genesis.assignments[&OS_LOT]
+        .as_structured()
+        .iter()
+        .filter_map(|assign| assign.as_revealed_state())
+        .map(|state| {
+            let data: &[u8] = state.value.as_ref();
+            Amount::from_strict_serialized::<U16>(Confined::from_checked(data.to_vec()))
+                .unwrap()
+                .value()
+        })
+        .sum();
+    let fill = OfferFill { taken, paid: 500 };
+    transition.transition_type = TS_TAKE;
+    transition
+        .globals
+        .insert(GS_FILL, GlobalValues::with(fill.to_strict_serialized::<U16>().unwrap().into()))
+        .unwrap();
+    let seal = GraphSeal::with_blinded_vout(Method::OpretFirst, 0, SEAL_BLINDING);
+    let state =
+        RevealedValue::with_blinding(taken, BlindingFactor::EMPTY, genesis.asset_tags[&OS_ASSET]);
+    let assign = Assign::revealed(XChain::Bitcoin(seal), state);
+    transition.assignments = none!();
+    transition
+        .assignments
+        .insert(OS_ASSET, TypedAssigns::Fungible(Confined::from_checked(vec![assign])))
+        .unwrap();
+    transition
+}
+
+/// Transfers the asset taken by the take transition into the same state.
+fn taken_transfer(take: &Transition) -> Transition {
+    let opid = take.id();
+    let count = take.assignments[&OS_ASSET].len_u16();
+    let inputs = (0..count).map(|no| Input::with(Opout::new(opid, OS_ASSET, no)));
+    Transition {
+        nonce: u64::MAX,
+        transition_type: TS_TRANSFER,
+        globals: none!(),
+        inputs: Inputs::from(Confined::from_iter_checked(inputs)),
+        ..take.clone()
+    }
+}
+
+fn edit_amount<Seal: ExposedSeal>(
+    assignments: &mut Assignments<Seal>,
+    f: impl Fn(&mut RevealedValue),
+) {
+    let assigns = assignments
+        .get_mut(&OS_ASSET)
+        .unwrap()
+        .as_fungible_mut()
+        .unwrap();
+    for assign in assigns.iter_mut() {
+        let Assign::Revealed { state, .. } = assign else {
+            unreachable!()
+        };
+        f(state);
+    }
+}
+
+fn edit_allocation<Seal: ExposedSeal>(assignments: &mut Assignments<Seal>, allocation: Allocation) {
+    let assigns = assignments
+        .get_mut(&OS_ASSET)
+        .unwrap()
+        .as_structured_mut()
+        .unwrap();
+    for assign in assigns.iter_mut() {
+        let Assign::Revealed { state, .. } = assign else {
+            unreachable!()
+        };
+        *state =
+            RevealedData::with_salt(allocation.to_strict_serialized::<U16>().unwrap(), state.salt);
+    }
+}
+
+fn genesis_scenario(
+    name: &'static str,
+    schema: BundledSchema,
+    genesis: Genesis,
+    errno: Option<SchemaErrno>,
+) -> Scenario {
+    let opid = genesis.id();
+    Scenario {
+        name,
+        validator: OpValidator::bundled(schema, genesis),
+        opid,
+        errno,
+    }
+}
+
+/// Scenario of a transition spending the asset taken from the ASO genesis with
+/// [`take`].
+fn taken_scenario(
+    name: &'static str,
+    transition: impl FnOnce(&Transition) -> Transition,
+    errno: Option<SchemaErrno>,
+) -> Scenario {
+    let schema = BundledSchema::AssetSwapOffer;
+    let genesis = genesis(schema);
+    let take = take(&genesis);
+    let transition = transition(&take);
+    let mut validator = OpValidator::bundled(schema, genesis);
+    validator.add_transition(take);
+    let opid = validator.add_transition(transition);
+    Scenario {
+        name,
+        validator,
+        opid,
+        errno,
+    }
+}
+
+fn transfer_scenario(
+    name: &'static str,
+    schema: BundledSchema,
+    transition: impl FnOnce(&Genesis) -> Transition,
+    errno: Option<SchemaErrno>,
+) -> Scenario {
+    let genesis = genesis(schema);
+    let mut transition = transition(&genesis);
+    tag_outputs(schema, &mut transition);
+    let mut validator = OpValidator::bundled(schema, genesis);
+    let opid = validator.add_transition(transition);
+    Scenario {
+        name,
+        validator,
+        opid,
+        errno,
+    }
+}
+
+/// Valid and invalid genesis and transfer operations for each of the
+/// bundled schemata.
+pub fn scenarios() -> Vec<Scenario> {
+    use BundledSchema::*;
+
+    let mut scenarios = vec![];
+    for schema in
+        [NonInflatableAsset, CollectibleFungibleAsset, LightningFungibleAsset, AssetSwapOffer]
+    {
+        scenarios.push(genesis_scenario("fungible genesis", schema, genesis(schema), None));
+
+        let mut genesis = genesis(schema);
+        let supply = Amount::from(1000u64).to_strict_serialized::<U16>().unwrap();
+        genesis
+            .globals
+            .insert(GS_ISSUED_SUPPLY, GlobalValues::with(supply.into()))
+            .unwrap();
+        let errno = Some(match schema {
+            NonInflatableAsset => SchemaErrno::NiaIssuedMismatch,
+            CollectibleFungibleAsset => SchemaErrno::CfaIssuedMismatch,
+            LightningFungibleAsset => SchemaErrno::LfaIssuedMismatch,
+            _ => SchemaErrno::AsoIssuedMismatch,
+        });
+        scenarios.push(genesis_scenario(
+            "fungible genesis supply mismatch",
+            schema,
+            genesis,
+            errno,
+        ));
+    }
+
+    for schema in [NonInflatableAsset, CollectibleFungibleAsset, LightningFungibleAsset] {
+        scenarios.push(transfer_scenario(
+            "fungible transfer",
+            schema,
+            |genesis| transfer(genesis, OS_ASSET),
+            None,
+        ));
+
+        let errno = Some(match schema {
+            NonInflatableAsset => SchemaErrno::NiaNonEqualAmounts,
+            CollectibleFungibleAsset => SchemaErrno::CfaNonEqualAmounts,
+            _ => SchemaErrno::LfaNonEqualAmounts,
+        });
+        scenarios.push(transfer_scenario(
+            "fungible transfer inflation",
+            schema,
+            |genesis| {
+                let mut transition = transfer(genesis, OS_ASSET);
+                edit_amount(&mut transition.assignments, |state| {
+                    *state = RevealedValue::with_blinding(
+                        state.value.as_u64() + 1,
+                        state.blinding,
+                        state.tag,
+                    )
+                });
+                transition
+            },
+            errno,
+        ));
+    }
+
+    let schema = AssetSwapOffer;
+    scenarios.push(transfer_scenario("ASO take", schema, take, None));
+    scenarios.push(transfer_scenario(
+        "ASO take fill mismatch",
+        schema,
+        |genesis| {
+            let mut transition = take(genesis);
+            let fill = OfferFill {
+                taken: 998,
+                paid: 500,
+            };
+            transition
+                .globals
+                .insert(
+                    GS_FILL,
+                    GlobalValues::with(fill.to_strict_serialized::<U16>().unwrap().into()),
+                )
+                .unwrap();
+            transition
+        },
+        Some(SchemaErrno::AsoFillMismatch),
+    ));
+    scenarios.push(transfer_scenario(
+        "ASO take inflation",
+        schema,
+        |genesis| {
+            let mut transition = take(genesis);
+            edit_amount(&mut transition.assignments, |state| {
+                *state = RevealedValue::with_blinding(
+                    state.value.as_u64() + 1,
+                    state.blinding,
+                    state.tag,
+                )
+            });
+            transition
+        },
+        Some(SchemaErrno::AsoFillMismatch),
+    ));
+    scenarios.push(taken_scenario("ASO transfer", taken_transfer, None));
+    scenarios.push(taken_scenario(
+        "ASO transfer inflation",
+        |take| {
+            let mut transition = taken_transfer(take);
+            edit_amount(&mut transition.assignments, |state| {
+                *state = RevealedValue::with_blinding(
+                    state.value.as_u64() + 1,
+                    state.blinding,
+                    state.tag,
+                )
+            });
+            transition
+        },
+        Some(SchemaErrno::AsoNonEqualAmounts),
+    ));
+
+    let schema = UniqueDigitalAsset;
+    scenarios.push(genesis_scenario("UDA genesis", schema, genesis(schema), None));
+
+    let mut genesis = self::genesis(schema);
+    edit_allocation(&mut genesis.assignments, Allocation::with(1, 1));
+    scenarios.push(genesis_scenario(
+        "UDA genesis unknown token",
+        schema,
+        genesis,
+        Some(SchemaErrno::UdaUnknownToken),
+    ));
+
+    let mut genesis = self::genesis(schema);
+    edit_allocation(&mut genesis.assignments, Allocation::with(0, 2));
+    scenarios.push(genesis_scenario(
+        "UDA genesis fractional token",
+        schema,
+        genesis,
+        Some(SchemaErrno::UdaNonFractionalToken),
+    ));
+
+    scenarios.push(transfer_scenario(
+        "UDA transfer",
+        schema,
+        |genesis| transfer(genesis, OS_ASSET),
+        None,
+    ));
+    scenarios.push(transfer_scenario(
+        "UDA transfer unknown token",
+        schema,
+        |genesis| {
+            let mut transition = transfer(genesis, OS_ASSET);
+            edit_allocation(&mut transition.assignments, Allocation::with(1, 1));
+            transition
+        },
+        Some(SchemaErrno::UdaUnknownToken),
+    ));
+    scenarios.push(transfer_scenario(
+        "UDA transfer fractional token",
+        schema,
+        |genesis| {
+            let mut transition = transfer(genesis, OS_ASSET);
+            edit_allocation(&mut transition.assignments, Allocation::with(0, 2));
+            transition
+        },
+        Some(SchemaErrno::UdaNonFractionalToken),
+    ));
+
+    scenarios
+}
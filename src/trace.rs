@@ -0,0 +1,609 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Step-by-step execution traces of schema validation scripts.
+//!
+//! AluVM doesn't expose hooks into instruction execution; compiled with `log`
+//! feature it prints to STDERR each instruction it executes, together with the
+//! values of the registers the instruction reads and writes. The trace of a
+//! validation is parsed from this output, captured while the operation is
+//! validated. Each logged instruction is checked against the instruction
+//! decoded from the library code at the logged offset, such that changes to
+//! the log format are reported as errors instead of producing partial traces.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+#[cfg(unix)]
+use std::fs::File;
+use std::io;
+#[cfg(unix)]
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::fd::{FromRawFd, RawFd};
+#[cfg(unix)]
+use std::sync::Mutex;
+#[cfg(unix)]
+use std::thread;
+
+use aluvm::isa::{Instr, InstructionSet};
+use aluvm::library::{LibId, LibSite};
+use baid64::DisplayBaid64;
+use rgbstd::persistence::MemContract;
+#[cfg(unix)]
+use rgbstd::validation::{ConsignmentApi, OpRef};
+use rgbstd::validation::{Failure, Scripts, Status};
+use rgbstd::vm::RgbIsa;
+#[cfg(unix)]
+use rgbstd::OpId;
+
+use crate::lint::decode;
+#[cfg(unix)]
+use crate::OpValidator;
+
+/// How the execution continued after an instruction.
+#[derive(Clone, Eq, PartialEq, Debug, Display)]
+pub enum StepOutcome {
+    /// Execution proceeded to the next instruction.
+    #[display("")]
+    Next,
+
+    /// Execution jumped to an offset within the same library.
+    #[display("jump x{0:04X}")]
+    Jump(u16),
+
+    /// Execution called other library.
+    #[display("call {0}")]
+    Call(LibSite),
+
+    /// Execution has stopped with the provided value of `st0` register.
+    #[display("stop, st0={0}")]
+    Stop(bool),
+
+    /// Execution has failed, setting `st0` to false, either by the instruction
+    /// itself or by jumping to a non-existing offset.
+    #[display("halt")]
+    Halt,
+
+    /// Execution was aborted since it has exceeded the complexity limit.
+    #[display("complexity overflow")]
+    Overflow,
+}
+
+/// Single instruction executed by a validation script.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct TraceStep {
+    /// Library containing the instruction.
+    pub lib: LibId,
+    /// Offset of the instruction within the library code.
+    pub pos: u16,
+    /// Disassembled instruction.
+    pub instr: String,
+    /// Registers read by the instruction with their values before execution.
+    pub src: Vec<(String, String)>,
+    /// Registers written by the instruction with their values after
+    /// execution.
+    pub dst: Vec<(String, String)>,
+    /// All registers set since the start of the script execution, with their
+    /// values after the instruction, in the order they were first written.
+    pub registers: Vec<(String, String)>,
+    /// Value of `st0` register after the instruction.
+    pub st0: bool,
+    pub outcome: StepOutcome,
+}
+
+impl TraceStep {
+    /// Value of a register after the step, if the register was set.
+    pub fn register(&self, name: &str) -> Option<&str> {
+        self.registers
+            .iter()
+            .find(|(reg, _)| reg == name)
+            .map(|(_, val)| val.as_str())
+    }
+
+    /// Location of the instruction.
+    pub fn site(&self) -> LibSite { LibSite::with(self.pos, self.lib) }
+}
+
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lib = self.lib.to_baid64_mnemonic();
+        let line = format!("{lib}@x{:04X}: {:<32} {}", self.pos, self.instr, self.outcome);
+        writeln!(f, "{}", line.trim_end())?;
+        for (reg, val) in &self.registers {
+            writeln!(f, "    {reg:<8} {val}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors of tracing validation scripts.
+#[derive(Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum TraceError {
+    /// unable to capture AluVM log: {0}
+    #[from]
+    Capture(io::Error),
+
+    /// validation script was run, but AluVM has not logged any instructions;
+    /// the output of the current thread may be captured by the test harness.
+    NoLog,
+
+    /// unrecognized line in AluVM log: {0}
+    Unrecognized(String),
+
+    /// AluVM log line doesn't match any instruction of the validator
+    /// libraries: {0}
+    UnknownInstr(String),
+}
+
+/// Execution trace of the validation of a single operation.
+#[derive(Clone, Debug)]
+pub struct ExecTrace {
+    pub steps: Vec<TraceStep>,
+    pub status: Status,
+}
+
+impl ExecTrace {
+    /// Parses the log of a single validation script execution made by AluVM
+    /// with `log` feature, resolving the instructions against the provided
+    /// libraries.
+    pub fn parse(log: &str, scripts: &Scripts, status: Status) -> Result<Self, TraceError> {
+        let mut parser = LogParser::new(scripts);
+        for line in log.lines() {
+            parser.parse_line(&strip_ansi(line))?;
+        }
+        Ok(ExecTrace {
+            steps: parser.steps,
+            status,
+        })
+    }
+
+    /// Last executed instruction, which determines the script result.
+    pub fn last_step(&self) -> Option<&TraceStep> { self.steps.last() }
+
+    /// Result of the script execution, if the script was run.
+    pub fn script_result(&self) -> Option<bool> { self.last_step().map(|step| step.st0) }
+
+    /// Failure of the validation script reported by the schema validation,
+    /// with the error code the script has set.
+    pub fn vm_failure(&self) -> Option<Option<u8>> {
+        self.status
+            .failures
+            .iter()
+            .find_map(|failure| match failure {
+                Failure::ScriptFailure(_, errno, _) => Some(*errno),
+                _ => None,
+            })
+    }
+
+    /// Checks that the trace ends with the same result as reported by the
+    /// schema validation: the script completed with the same value of `st0`
+    /// register and, on failure, with the same error code in `a8[0]`.
+    pub fn matches_vm(&self) -> bool {
+        let errno = self
+            .last_step()
+            .and_then(|step| step.register("a8[0]"))
+            .map(|errno| errno.parse::<u8>().ok());
+        match (self.script_result(), self.vm_failure()) {
+            (None | Some(true), None) => true,
+            (Some(false), Some(failure)) => {
+                errno.map_or(failure.is_none(), |errno| errno == failure)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for ExecTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            Display::fmt(step, f)?;
+        }
+        if !self.matches_vm() {
+            writeln!(f, "Warning: traced execution doesn't match the validation result")?;
+        }
+        writeln!(f, "Operation {}", self.status.validity())?;
+        write!(f, "{}", self.status)
+    }
+}
+
+#[cfg(unix)]
+impl OpValidator {
+    /// Validates operation with the given id, tracing execution of the
+    /// validation script from the AluVM log.
+    ///
+    /// The log is captured by temporarily redirecting the process STDERR, thus
+    /// output of other threads made in the meantime breaks the trace. Inside
+    /// the test harness the output of the test thread is intercepted by the
+    /// harness, making the method fail with [`TraceError::NoLog`].
+    ///
+    /// The trace is empty if the operation is not known or the schema doesn't
+    /// define validation script for it.
+    pub fn trace(&self, opid: OpId) -> Result<ExecTrace, TraceError> {
+        let (status, log) = capture_stderr(|| self.validate(opid))?;
+        let trace = ExecTrace::parse(&log, self.scripts(), status)?;
+        let has_script = match self.operation(opid) {
+            Some(OpRef::Genesis(_)) => self.schema().genesis.validator.is_some(),
+            Some(OpRef::Transition(transition)) => self
+                .schema()
+                .transitions
+                .get(&transition.transition_type)
+                .is_some_and(|ty| ty.validator.is_some()),
+            _ => false,
+        };
+        let script_run = trace.status.failures.is_empty() || trace.vm_failure().is_some();
+        if has_script && script_run && trace.steps.is_empty() {
+            return Err(TraceError::NoLog);
+        }
+        Ok(trace)
+    }
+}
+
+/// Text of the line which AluVM logs when execution jumps outside of the
+/// library code.
+const INVALID_JUMP: &str = "jump to non-existing offset; halting, st0 is set to false";
+
+/// Instructions decoded from the library code by their offsets.
+type LibCode = BTreeMap<u16, Instr<RgbIsa<MemContract>>>;
+
+struct LogParser {
+    /// Decoded instructions of each library by the prefix of the library id
+    /// mnemonic, with which AluVM refers to the library.
+    code: BTreeMap<String, Vec<(LibId, LibCode)>>,
+    steps: Vec<TraceStep>,
+    registers: Vec<(String, String)>,
+    st0: bool,
+}
+
+impl LogParser {
+    fn new(scripts: &Scripts) -> Self {
+        let mut code = BTreeMap::<_, Vec<_>>::new();
+        for (id, lib) in scripts {
+            let instrs = decode(lib)
+                .into_iter()
+                .filter_map(|(pos, instr)| Some((pos, instr?)))
+                .collect();
+            let lib_ref = id.to_baid64_mnemonic().split_at(5).0.to_owned();
+            code.entry(lib_ref).or_default().push((*id, instrs));
+        }
+        LogParser {
+            code,
+            steps: vec![],
+            registers: vec![],
+            st0: true,
+        }
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), TraceError> {
+        let unrecognized = || TraceError::Unrecognized(line.to_owned());
+        if line == INVALID_JUMP {
+            let last = self.steps.last_mut().ok_or_else(unrecognized)?;
+            last.st0 = false;
+            last.outcome = StepOutcome::Halt;
+            return Ok(());
+        }
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let (lib_ref, rest) = line.split_once("@x").ok_or_else(unrecognized)?;
+        let (pos, rest) = rest.split_once(": ").ok_or_else(unrecognized)?;
+        if lib_ref.chars().count() != 5 || pos.len() != 6 {
+            return Err(unrecognized());
+        }
+        let pos = u16::from_str_radix(pos, 16).map_err(|_| unrecognized())?;
+
+        let mut found = self
+            .code
+            .get(lib_ref)
+            .into_iter()
+            .flatten()
+            .filter_map(|(id, code)| {
+                let instr = code.get(&pos)?;
+                let rest = rest.strip_prefix(&format!("{:<32}; ", instr.to_string()))?;
+                Some((*id, instr, rest))
+            });
+        let (lib, instr, rest) = found
+            .next()
+            .ok_or_else(|| TraceError::UnknownInstr(line.to_owned()))?;
+        if found.next().is_some() {
+            return Err(TraceError::UnknownInstr(line.to_owned()));
+        }
+
+        let src_regs = instr.src_regs().into_iter().map(|reg| reg.to_string());
+        let (src, rest) = reg_values(rest, src_regs, "-> ").ok_or_else(unrecognized)?;
+        let rest = rest.strip_prefix("-> ").ok_or_else(unrecognized)?;
+
+        let (rest, outcome) = parse_outcome(rest).ok_or_else(unrecognized)?;
+        let (rest, st0) = match rest
+            .strip_suffix(" st0=true ")
+            .map(|rest| (rest, true))
+            .or_else(|| rest.strip_suffix(" st0=false ").map(|rest| (rest, false)))
+        {
+            Some((rest, st0)) => (rest, Some(st0)),
+            None => (rest, None),
+        };
+        let dst_regs = instr.dst_regs().into_iter().map(|reg| reg.to_string());
+        let (dst, rest) = reg_values(rest, dst_regs, "").ok_or_else(unrecognized)?;
+        if !rest.is_empty() {
+            return Err(unrecognized());
+        }
+
+        for (reg, val) in &dst {
+            match self.registers.iter_mut().find(|(r, _)| r == reg) {
+                Some((_, v)) => *v = val.clone(),
+                None => self.registers.push((reg.clone(), val.clone())),
+            }
+        }
+        self.st0 = match outcome {
+            StepOutcome::Stop(st0) => st0,
+            StepOutcome::Halt => false,
+            _ => st0.unwrap_or(self.st0),
+        };
+        self.steps.push(TraceStep {
+            lib,
+            pos,
+            instr: instr.to_string(),
+            src,
+            dst,
+            registers: self.registers.clone(),
+            st0: self.st0,
+            outcome,
+        });
+        Ok(())
+    }
+}
+
+/// Parses the outcome AluVM logs at the end of the instruction line, returning
+/// the rest of the line.
+fn parse_outcome(line: &str) -> Option<(&str, StepOutcome)> {
+    if let Some(rest) = line.strip_suffix("execution stopped; st0=true") {
+        return Some((rest, StepOutcome::Stop(true)));
+    }
+    if let Some(rest) = line.strip_suffix("execution stopped; st0=false") {
+        return Some((rest, StepOutcome::Stop(false)));
+    }
+    if let Some(rest) = line.strip_suffix("halting, st0 is set to false") {
+        return Some((rest, StepOutcome::Halt));
+    }
+    if let Some(rest) = line.strip_suffix("complexity overflow") {
+        return Some((rest, StepOutcome::Overflow));
+    }
+    if line.is_empty() || line.ends_with(' ') {
+        return Some((line, StepOutcome::Next));
+    }
+    let (rest, last) = split_last(line);
+    if let Some((rest, pos)) = rest.strip_suffix(" @ ").map(split_last) {
+        let site = LibSite::with(pos.parse().ok()?, last.parse().ok()?);
+        return Some((rest, StepOutcome::Call(site)));
+    }
+    Some((rest, StepOutcome::Jump(last.parse().ok()?)))
+}
+
+/// Splits the last word from the text, keeping the space before it with the
+/// rest of the text.
+fn split_last(text: &str) -> (&str, &str) {
+    let at = text.rfind(' ').map_or(0, |pos| pos + 1);
+    text.split_at(at)
+}
+
+/// Parses values of the given registers logged by AluVM as `reg=value `,
+/// where the value of the last register is followed by the `end` text.
+fn reg_values<'a>(
+    mut text: &'a str,
+    regs: impl IntoIterator<Item = String>,
+    end: &str,
+) -> Option<(Vec<(String, String)>, &'a str)> {
+    let regs = regs.into_iter().collect::<Vec<_>>();
+    let mut values = vec![];
+    for (no, reg) in regs.iter().enumerate() {
+        text = text.strip_prefix(reg.as_str())?.strip_prefix('=')?;
+        let len = match regs.get(no + 1) {
+            Some(next) => text.find(&format!(" {next}="))?,
+            None if end.is_empty() => text.len().checked_sub(1)?,
+            None => text.find(&format!(" {end}"))?,
+        };
+        values.push((reg.clone(), text[..len].to_owned()));
+        text = text[len..].strip_prefix(' ')?;
+    }
+    Some((values, text))
+}
+
+/// Removes terminal color codes from a line of the log.
+fn strip_ansi(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1B' => {
+                chars.by_ref().find(|c| *c == 'm');
+            }
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+/// Restores the original STDERR when dropped, including on panic.
+#[cfg(unix)]
+struct StderrGuard(RawFd);
+
+#[cfg(unix)]
+impl Drop for StderrGuard {
+    fn drop(&mut self) {
+        let _ = io::stderr().flush();
+        unsafe {
+            libc::dup2(self.0, libc::STDERR_FILENO);
+            libc::close(self.0);
+        }
+    }
+}
+
+/// Runs the function with the process STDERR redirected into a pipe, returning
+/// everything written to it.
+#[cfg(unix)]
+fn capture_stderr<T>(f: impl FnOnce() -> T) -> io::Result<(T, String)> {
+    // STDERR is shared by the whole process, thus the captures can't overlap
+    static CAPTURE: Mutex<()> = Mutex::new(());
+    let _lock = CAPTURE.lock().unwrap_or_else(|err| err.into_inner());
+
+    let mut fds = [0 as RawFd; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let [read_fd, write_fd] = fds;
+    let mut reader = unsafe { File::from_raw_fd(read_fd) };
+    let collector = thread::spawn(move || {
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf).map(|_| buf)
+    });
+
+    io::stderr().flush()?;
+    let saved = unsafe { libc::dup(libc::STDERR_FILENO) };
+    if saved < 0 || unsafe { libc::dup2(write_fd, libc::STDERR_FILENO) } < 0 {
+        let err = io::Error::last_os_error();
+        unsafe { libc::close(write_fd) };
+        if saved >= 0 {
+            unsafe { libc::close(saved) };
+        }
+        return Err(err);
+    }
+    unsafe { libc::close(write_fd) };
+
+    let guard = StderrGuard(saved);
+    let res = f();
+    drop(guard);
+
+    let log = collector
+        .join()
+        .map_err(|_| io::Error::other("STDERR collector has panicked"))??;
+    Ok((res, String::from_utf8_lossy(&log).into_owned()))
+}
+
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use rgbstd::validation::Validity;
+
+    use super::*;
+    use crate::nia::nia_lib;
+
+    fn nia_scripts() -> Scripts {
+        let lib = nia_lib();
+        Scripts::from_iter_checked([(lib.id(), lib)])
+    }
+
+    /// Writes the line AluVM logs for the instruction at the given offset of
+    /// NIA library, setting all registers to 1.
+    fn log_line(pos: u16, st0: &str, outcome: &str) -> String {
+        let lib = nia_lib();
+        let lib_ref = lib.id().to_baid64_mnemonic().split_at(5).0.to_owned();
+        let instr = decode(&lib)
+            .into_iter()
+            .find(|(p, _)| *p == pos)
+            .and_then(|(_, instr)| instr)
+            .map_or(s!("nop"), |instr| instr.to_string());
+        let regs = |regs: Vec<String>| {
+            regs.into_iter()
+                .map(|reg| format!("\x1B[0;37;2m{reg}=\x1B[0m\x1B[1;1m1\x1B[0m "))
+                .collect::<String>()
+        };
+        let (src, dst) = match decode(&lib).into_iter().find(|(p, _)| *p == pos) {
+            Some((_, Some(instr))) => (
+                regs(
+                    instr
+                        .src_regs()
+                        .into_iter()
+                        .map(|r| r.to_string())
+                        .collect(),
+                ),
+                regs(
+                    instr
+                        .dst_regs()
+                        .into_iter()
+                        .map(|r| r.to_string())
+                        .collect(),
+                ),
+            ),
+            _ => (s!(""), s!("")),
+        };
+        format!(
+            "\x1B[0;35m{lib_ref}@x{pos:06X}:\x1B[0m {instr: <32}; {src}-> {dst}{st0}{outcome}\n"
+        )
+    }
+
+    #[test]
+    fn parse() {
+        let lib = nia_lib();
+        let code = decode(&lib);
+        let (first, second) = (code[0].0, code[1].0);
+        let log = log_line(first, "", "")
+            + &log_line(second, " st0=false ", "halting, st0 is set to false");
+        let trace = ExecTrace::parse(&log, &nia_scripts(), Status::new())
+            .unwrap_or_else(|err| panic!("{err}\n{log}"));
+        assert_eq!(trace.steps.len(), 2);
+        assert_eq!(trace.steps[0].lib, lib.id());
+        assert_eq!(trace.steps[0].pos, first);
+        assert_eq!(trace.steps[0].outcome, StepOutcome::Next);
+        assert!(trace.steps[0].st0);
+        assert!(trace.steps[0].dst.iter().all(|(_, val)| val == "1"));
+        assert_eq!(trace.steps[1].outcome, StepOutcome::Halt);
+        assert!(!trace.steps[1].st0);
+        assert!(trace.steps[1].src.iter().all(|(_, val)| val == "1"));
+        assert_eq!(trace.script_result(), Some(false));
+        assert_eq!(trace.status.validity(), Validity::Valid);
+        assert!(!trace.matches_vm());
+    }
+
+    #[test]
+    fn unrecognized() {
+        let err = ExecTrace::parse("some output", &nia_scripts(), Status::new()).unwrap_err();
+        assert!(matches!(err, TraceError::Unrecognized(_)));
+
+        let log = log_line(u16::MAX, "", "");
+        let err = ExecTrace::parse(&log, &nia_scripts(), Status::new()).unwrap_err();
+        assert!(matches!(err, TraceError::UnknownInstr(_)));
+    }
+
+    #[test]
+    fn outcome() {
+        let lib = nia_lib().id();
+        assert_eq!(parse_outcome("a8[0]=1 "), Some(("a8[0]=1 ", StepOutcome::Next)));
+        assert_eq!(parse_outcome("23"), Some(("", StepOutcome::Jump(23))));
+        assert_eq!(parse_outcome(" st0=false 23"), Some((" st0=false ", StepOutcome::Jump(23))));
+        assert_eq!(
+            parse_outcome(&format!("a16[0]=1 5 @ {lib}")),
+            Some(("a16[0]=1 ", StepOutcome::Call(LibSite::with(5, lib))))
+        );
+        assert_eq!(
+            parse_outcome(" st0=false execution stopped; st0=false"),
+            Some((" st0=false ", StepOutcome::Stop(false)))
+        );
+        assert_eq!(parse_outcome("a8[0]=x"), None);
+    }
+}
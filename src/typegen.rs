@@ -0,0 +1,576 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generation of JSON Schema documents and TypeScript declarations for the
+//! interface-level state of the bundled schemata, as serialized with serde
+//! (for instance, by `contract.spec()` or `inspect-contract --json`).
+//!
+//! The state document of a schema is an object with a property for each of
+//! the global and owned state types named in its interface implementation.
+//! Global state is typed by its strict type, owned state is typed as a list
+//! of allocations. Strict types and allocations are mapped onto the shapes
+//! which rgb-std produces when serializing them with serde.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use rgbstd::schema::OwnedStateSchema;
+use serde_json::{json, Map, Value};
+
+use crate::export::symbols;
+use crate::BundledSchema;
+
+const HEX32: &str = "^[0-9a-f]{64}$";
+
+/// Shape of a JSON value.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Shape {
+    String {
+        min: usize,
+        max: usize,
+        pattern: Option<&'static str>,
+    },
+    Integer(u64),
+    /// Byte string, serialized as an array of numbers.
+    Bytes,
+    Nullable(Box<Shape>),
+    Array(Box<Shape>),
+    Tuple(Vec<Shape>),
+    Object(Vec<(&'static str, Shape)>),
+    /// Object with numeric keys.
+    Map(Box<Shape>),
+    Enum(&'static [&'static str]),
+    Ref(&'static str),
+}
+
+fn string(min: usize, max: usize) -> Shape {
+    Shape::String {
+        min,
+        max,
+        pattern: None,
+    }
+}
+
+fn pattern(max: usize, pattern: &'static str) -> Shape {
+    Shape::String {
+        min: 1,
+        max,
+        pattern: Some(pattern),
+    }
+}
+
+fn nullable(name: &'static str) -> Shape { Shape::Nullable(Box::new(Shape::Ref(name))) }
+
+/// Named shapes of the strict types and allocations used by the bundled
+/// schemata.
+pub(crate) fn definitions() -> BTreeMap<&'static str, Shape> {
+    use Shape::*;
+    bmap! {
+        "Ticker" => pattern(8, "^[A-Za-z][A-Za-z0-9]*$"),
+        "Article" => pattern(32, "^[A-Za-z][A-Za-z0-9]*$"),
+        "Name" => pattern(40, "^[ -~]+$"),
+        "Details" => string(1, 255),
+        "Precision" => Enum(&[
+            "indivisible", "deci", "centi", "milli", "deciMilli", "centiMilli", "micro",
+            "deciMicro", "centiMicro", "nano", "deciNano", "centiNano", "pico", "deciPico",
+            "centiPico", "femto", "deciFemto", "centiFemto", "atto",
+        ]),
+        "Amount" => Integer(u64::MAX),
+        "AssetSpec" => Object(vec![
+            ("ticker", Ref("Ticker")),
+            ("name", Ref("Name")),
+            ("details", nullable("Details")),
+            ("precision", Ref("Precision")),
+        ]),
+        "MediaRegName" => string(1, 64),
+        "MediaType" => Object(vec![
+            ("type", Ref("MediaRegName")),
+            ("subtype", nullable("MediaRegName")),
+            ("charset", nullable("MediaRegName")),
+        ]),
+        "Attachment" => Object(vec![
+            ("type", Ref("MediaType")),
+            ("digest", pattern(64, HEX32)),
+        ]),
+        "ContractTerms" => Object(vec![
+            ("text", string(0, u16::MAX as usize)),
+            ("media", nullable("Attachment")),
+        ]),
+        "EmbeddedMedia" => Object(vec![
+            ("type", Ref("MediaType")),
+            ("data", Bytes),
+        ]),
+        "ProofOfReserves" => Object(vec![
+            ("utxo", pattern(75, "^[0-9a-f]{64}:[0-9]+$")),
+            ("proof", Bytes),
+        ]),
+        "TokenIndex" => Integer(u32::MAX as u64),
+        "TokenData" => Object(vec![
+            ("index", Ref("TokenIndex")),
+            ("ticker", nullable("Ticker")),
+            ("name", nullable("Name")),
+            ("details", nullable("Details")),
+            ("preview", nullable("EmbeddedMedia")),
+            ("media", nullable("Attachment")),
+            ("attachments", Map(Box::new(Ref("Attachment")))),
+            ("reserves", nullable("ProofOfReserves")),
+        ]),
+        "AttachmentType" => Object(vec![
+            ("id", Integer(u8::MAX as u64)),
+            ("name", string(1, 20)),
+        ]),
+        "Chain" => Enum(&["bitcoin", "liquid"]),
+        "OutputSeal" => Object(vec![
+            ("chain", Ref("Chain")),
+            ("data", Object(vec![
+                ("method", Enum(&["opretFirst", "tapretFirst"])),
+                ("txid", pattern(64, HEX32)),
+                ("vout", Integer(u32::MAX as u64)),
+            ])),
+        ]),
+        "WitnessId" => Object(vec![
+            ("chain", Ref("Chain")),
+            ("data", pattern(64, HEX32)),
+        ]),
+        "Opout" => Object(vec![
+            ("op", pattern(64, HEX32)),
+            ("ty", Integer(u16::MAX as u64)),
+            ("no", Integer(u16::MAX as u64)),
+        ]),
+        "FungibleAllocation" => Object(vec![
+            ("opout", Ref("Opout")),
+            ("seal", Ref("OutputSeal")),
+            ("state", Ref("Amount")),
+            ("witness", nullable("WitnessId")),
+        ]),
+        "TokenAllocation" => Object(vec![
+            ("seal", Ref("OutputSeal")),
+            ("allocation", Tuple(vec![Ref("TokenIndex"), Integer(u64::MAX)])),
+            ("witness", nullable("WitnessId")),
+        ]),
+    }
+}
+
+/// Definition name for a strict type used in global or structured owned
+/// state.
+fn definition(type_name: &str) -> Option<&'static str> {
+    Some(match type_name {
+        "RGBContract.AssetSpec" => "AssetSpec",
+        "RGBContract.ContractTerms" => "ContractTerms",
+        "RGBContract.Amount" => "Amount",
+        "RGBContract.Article" => "Article",
+        "RGBContract.Name" => "Name",
+        "RGBContract.Details" => "Details",
+        "RGBContract.Precision" => "Precision",
+        "RGB21.TokenData" => "TokenData",
+        "RGB21.AttachmentType" => "AttachmentType",
+        "RGBContract.Allocation" => "TokenAllocation",
+        _ => return None,
+    })
+}
+
+/// Interface-level state document of a bundled schema.
+pub(crate) struct StateDoc {
+    pub title: String,
+    /// Properties with their shapes and whether they are required.
+    pub properties: Vec<(String, Shape, bool)>,
+    pub definitions: BTreeMap<&'static str, Shape>,
+}
+
+impl StateDoc {
+    pub fn with(bundled: BundledSchema) -> Self {
+        let schema = bundled.schema();
+        let iimpl = bundled.issue_impl();
+        let sys = symbols(bundled);
+        let type_name = |sem_id| {
+            let name = sys
+                .lookup(sem_id)
+                .map(|fqn| fqn.to_string())
+                .expect("schema uses unnamed type");
+            definition(&name).unwrap_or_else(|| panic!("no JSON shape for {name}"))
+        };
+
+        let mut properties = vec![];
+        for field in &iimpl.global_state {
+            let global = &schema.global_types[&field.id];
+            let mut shape = Shape::Ref(type_name(global.sem_id));
+            if global.max_items.into_u32() > 1 {
+                shape = Shape::Array(Box::new(shape));
+            }
+            let required = schema
+                .genesis
+                .globals
+                .get(&field.id)
+                .is_some_and(|occ| occ.min_value() > 0);
+            properties.push((field.name.to_string(), shape, required));
+        }
+        for field in &iimpl.assignments {
+            let allocation = match schema.owned_types[&field.id] {
+                OwnedStateSchema::Fungible(_) => "FungibleAllocation",
+                OwnedStateSchema::Structured(sem_id) => type_name(sem_id),
+                ref other => panic!("no JSON shape for {other:?} owned state"),
+            };
+            properties.push((
+                field.name.to_string(),
+                Shape::Array(Box::new(Shape::Ref(allocation))),
+                true,
+            ));
+        }
+
+        let all = definitions();
+        let mut definitions = BTreeMap::new();
+        let mut pending = properties
+            .iter()
+            .map(|(_, shape, _)| shape.clone())
+            .collect::<Vec<_>>();
+        while let Some(shape) = pending.pop() {
+            shape.visit_refs(&mut |name| {
+                if !definitions.contains_key(name) {
+                    let def = all[name].clone();
+                    definitions.insert(name, def.clone());
+                    pending.push(def);
+                }
+            });
+        }
+
+        StateDoc {
+            title: format!("{}State", schema.name),
+            properties,
+            definitions,
+        }
+    }
+}
+
+impl Shape {
+    fn visit_refs(&self, f: &mut impl FnMut(&'static str)) {
+        match self {
+            Shape::Ref(name) => f(name),
+            Shape::Nullable(inner) | Shape::Array(inner) | Shape::Map(inner) => inner.visit_refs(f),
+            Shape::Tuple(items) => items.iter().for_each(|item| item.visit_refs(f)),
+            Shape::Object(fields) => fields.iter().for_each(|(_, field)| field.visit_refs(f)),
+            Shape::String { .. } | Shape::Integer(_) | Shape::Bytes | Shape::Enum(_) => {}
+        }
+    }
+
+    fn json_schema(&self) -> Value {
+        match self {
+            Shape::String { min, max, pattern } => {
+                let mut schema = json!({ "type": "string", "minLength": min, "maxLength": max });
+                if let Some(pattern) = pattern {
+                    schema["pattern"] = json!(pattern);
+                }
+                schema
+            }
+            Shape::Integer(max) => json!({ "type": "integer", "minimum": 0, "maximum": max }),
+            Shape::Bytes => {
+                json!({ "type": "array", "items": { "type": "integer", "minimum": 0, "maximum": 255 } })
+            }
+            Shape::Nullable(inner) => json!({ "anyOf": [inner.json_schema(), { "type": "null" }] }),
+            Shape::Array(inner) => json!({ "type": "array", "items": inner.json_schema() }),
+            Shape::Tuple(items) => json!({
+                "type": "array",
+                "prefixItems": items.iter().map(Shape::json_schema).collect::<Vec<_>>(),
+                "items": false,
+            }),
+            Shape::Object(fields) => {
+                let properties = fields
+                    .iter()
+                    .map(|(name, field)| (name.to_string(), field.json_schema()))
+                    .collect::<Map<_, _>>();
+                let required = fields.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            Shape::Map(inner) => json!({
+                "type": "object",
+                "propertyNames": { "pattern": "^[0-9]+$" },
+                "additionalProperties": inner.json_schema(),
+            }),
+            Shape::Enum(variants) => json!({ "type": "string", "enum": variants }),
+            Shape::Ref(name) => json!({ "$ref": format!("#/$defs/{name}") }),
+        }
+    }
+
+    fn typescript(&self, indent: usize) -> String {
+        match self {
+            Shape::String { .. } => s!("string"),
+            // Values above 2^53 lose precision when parsed as JSON numbers.
+            Shape::Integer(_) => s!("number"),
+            Shape::Bytes => s!("number[]"),
+            Shape::Nullable(inner) => format!("{} | null", inner.typescript(indent)),
+            Shape::Array(inner) => match **inner {
+                Shape::Nullable(_) | Shape::Enum(_) => format!("({})[]", inner.typescript(indent)),
+                _ => format!("{}[]", inner.typescript(indent)),
+            },
+            Shape::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| item.typescript(indent))
+                    .collect::<Vec<_>>();
+                format!("[{}]", items.join(", "))
+            }
+            Shape::Object(fields) => {
+                let pad = "  ".repeat(indent + 1);
+                let mut ts = s!("{\n");
+                for (name, field) in fields {
+                    writeln!(ts, "{pad}{name}: {};", field.typescript(indent + 1)).unwrap();
+                }
+                ts + &"  ".repeat(indent) + "}"
+            }
+            Shape::Map(inner) => format!("{{ [index: string]: {} }}", inner.typescript(indent)),
+            Shape::Enum(variants) => variants
+                .iter()
+                .map(|variant| format!("\"{variant}\""))
+                .collect::<Vec<_>>()
+                .join(" | "),
+            Shape::Ref(name) => name.to_string(),
+        }
+    }
+}
+
+/// JSON Schema (draft 2020-12) of the interface-level state of a bundled
+/// schema.
+pub fn state_json_schema(schema: BundledSchema) -> Value {
+    let doc = StateDoc::with(schema);
+    let properties = doc
+        .properties
+        .iter()
+        .map(|(name, shape, _)| (name.clone(), shape.json_schema()))
+        .collect::<Map<_, _>>();
+    let required = doc
+        .properties
+        .iter()
+        .filter(|(_, _, required)| *required)
+        .map(|(name, _, _)| name.as_str())
+        .collect::<Vec<_>>();
+    let defs = doc
+        .definitions
+        .iter()
+        .map(|(name, shape)| (name.to_string(), shape.json_schema()))
+        .collect::<Map<_, _>>();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": doc.title,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+        "$defs": defs,
+    })
+}
+
+/// TypeScript declarations of the interface-level state of a bundled schema.
+pub fn state_typescript(schema: BundledSchema) -> String {
+    let doc = StateDoc::with(schema);
+    let mut ts = format!("// Interface-level state of {schema} contracts.\n");
+    for (name, shape) in &doc.definitions {
+        match shape {
+            Shape::Object(_) => writeln!(ts, "\nexport interface {name} {}", shape.typescript(0)),
+            _ => writeln!(ts, "\nexport type {name} = {};", shape.typescript(0)),
+        }
+        .unwrap();
+    }
+    writeln!(ts, "\nexport interface {} {{", doc.title).unwrap();
+    for (name, shape, required) in &doc.properties {
+        let optional = if *required { "" } else { "?" };
+        writeln!(ts, "  {name}{optional}: {};", shape.typescript(1)).unwrap();
+    }
+    ts + "}\n"
+}
+
+#[cfg(all(
+    test,
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use bp::Txid;
+    use ifaces::rgb21::AttachmentType;
+    use rgbstd::vm::XWitnessId;
+
+    use super::*;
+    use crate::ContractReport;
+
+    fn conforms(value: &Value, shape: &Shape, defs: &BTreeMap<&str, Shape>) -> bool {
+        match (shape, value) {
+            (Shape::String { min, max, pattern }, Value::String(s)) => {
+                let len = s.chars().count();
+                let charset_ok = match *pattern {
+                    Some(HEX32) => s.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')),
+                    Some("^[A-Za-z][A-Za-z0-9]*$") => {
+                        s.starts_with(|c: char| c.is_ascii_alphabetic())
+                            && s.chars().all(|c| c.is_ascii_alphanumeric())
+                    }
+                    Some("^[ -~]+$") => s.chars().all(|c| matches!(c, ' '..='~')),
+                    _ => true,
+                };
+                (*min..=*max).contains(&len) && charset_ok
+            }
+            (Shape::Integer(max), Value::Number(n)) => n.as_u64().is_some_and(|n| n <= *max),
+            (Shape::Bytes, Value::Array(items)) => items
+                .iter()
+                .all(|item| item.as_u64().is_some_and(|byte| byte <= 0xFF)),
+            (Shape::Nullable(_), Value::Null) => true,
+            (Shape::Nullable(inner), value) => conforms(value, inner, defs),
+            (Shape::Array(inner), Value::Array(items)) => {
+                items.iter().all(|item| conforms(item, inner, defs))
+            }
+            (Shape::Tuple(shapes), Value::Array(items)) => {
+                shapes.len() == items.len()
+                    && items
+                        .iter()
+                        .zip(shapes)
+                        .all(|(item, shape)| conforms(item, shape, defs))
+            }
+            (Shape::Object(fields), Value::Object(map)) => {
+                map.len() == fields.len()
+                    && fields.iter().all(|(name, field)| {
+                        map.get(*name)
+                            .is_some_and(|value| conforms(value, field, defs))
+                    })
+            }
+            (Shape::Map(inner), Value::Object(map)) => map
+                .iter()
+                .all(|(key, value)| key.parse::<u8>().is_ok() && conforms(value, inner, defs)),
+            (Shape::Enum(variants), Value::String(s)) => variants.contains(&s.as_str()),
+            (Shape::Ref(name), value) => conforms(value, &defs[name], defs),
+            _ => false,
+        }
+    }
+
+    fn check(schema: BundledSchema, state: Value) {
+        let doc = StateDoc::with(schema);
+        let state = state.as_object().unwrap();
+        for (name, shape, required) in &doc.properties {
+            match state.get(name) {
+                Some(value) => assert!(
+                    conforms(value, shape, &doc.definitions),
+                    "{name} does not conform to its shape: {value}"
+                ),
+                None => assert!(!required, "missing required {name}"),
+            }
+        }
+        assert!(state
+            .keys()
+            .all(|key| doc.properties.iter().any(|(name, ..)| name == key)));
+    }
+
+    fn report(file: &str) -> Value {
+        let path = format!("{}/test/{file}.rgb", env!("CARGO_MANIFEST_DIR"));
+        serde_json::to_value(ContractReport::load_file(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn nia() {
+        let report = report("rgb20-example");
+        check(
+            BundledSchema::NonInflatableAsset,
+            json!({
+                "spec": report["spec"],
+                "terms": report["terms"],
+                "issuedSupply": report["issuedSupply"],
+                "assetOwner": report["allocations"],
+            }),
+        );
+
+        let schema = state_json_schema(BundledSchema::NonInflatableAsset);
+        assert_eq!(schema["title"], "NonInflatableAssetState");
+        assert_eq!(schema["required"], json!(["spec", "terms", "issuedSupply", "assetOwner"]));
+        assert_eq!(schema["properties"]["spec"]["$ref"], "#/$defs/AssetSpec");
+        assert_eq!(
+            schema["properties"]["assetOwner"]["items"]["$ref"],
+            "#/$defs/FungibleAllocation"
+        );
+    }
+
+    #[test]
+    fn uda() {
+        let report = report("rgb21-example");
+        let attachment_types = serde_json::to_value(AttachmentType::with(0, "image")).unwrap();
+        check(
+            BundledSchema::UniqueDigitalAsset,
+            json!({
+                "spec": report["spec"],
+                "terms": report["terms"],
+                "tokens": report["token"],
+                "attachmentTypes": attachment_types,
+                "assetOwner": report["allocations"],
+            }),
+        );
+        check(
+            BundledSchema::UniqueDigitalAsset,
+            json!({
+                "spec": report["spec"],
+                "terms": report["terms"],
+                "tokens": report["token"],
+                "assetOwner": report["allocations"],
+            }),
+        );
+
+        let ts = state_typescript(BundledSchema::UniqueDigitalAsset);
+        assert!(ts.contains("export interface UniqueDigitalAssetState {\n"));
+        assert!(ts.contains("  tokens: TokenData;\n"));
+        assert!(ts.contains("  attachmentTypes?: AttachmentType;\n"));
+        assert!(ts.contains("  assetOwner: TokenAllocation[];\n"));
+    }
+
+    #[test]
+    fn cfa() {
+        let report = report("rgb25-example");
+        let mut state = json!({
+            "name": report["name"],
+            "precision": report["precision"],
+            "terms": report["terms"],
+            "issuedSupply": report["issuedSupply"],
+            "assetOwner": report["allocations"],
+        });
+        // optional global state is absent from the state document when not set
+        if !report["details"].is_null() {
+            state["details"] = report["details"].clone();
+        }
+        check(BundledSchema::CollectibleFungibleAsset, state);
+        assert!(state_typescript(BundledSchema::CollectibleFungibleAsset)
+            .contains("  details?: Details;\n"));
+    }
+
+    #[test]
+    fn witness() {
+        let defs = definitions();
+        let witness = serde_json::to_value(XWitnessId::Bitcoin(Txid::coinbase())).unwrap();
+        assert!(conforms(&witness, &Shape::Ref("WitnessId"), &defs));
+        assert!(!conforms(&json!({ "chain": "bitcoin" }), &Shape::Ref("WitnessId"), &defs));
+    }
+
+    #[test]
+    fn definitions_resolve() {
+        let defs = definitions();
+        for shape in defs.values() {
+            shape.visit_refs(&mut |name| assert!(defs.contains_key(name), "{name}"));
+        }
+    }
+}
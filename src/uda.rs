@@ -21,32 +21,43 @@
 
 //! Unique digital asset (UDA) schema implementing RGB21 NFT interface.
 
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
 use aluvm::isa::opcodes::{INSTR_EXTR, INSTR_PUTA};
-use aluvm::isa::Instr;
 use aluvm::library::{Lib, LibSite};
-use amplify::confinement::Confined;
-use ifaces::{IssuerWrapper, Rgb21, LNPBP_IDENTITY};
-use rgbstd::interface::{IfaceClass, IfaceImpl, NamedField, NamedVariant, VerNo};
-use rgbstd::persistence::MemContract;
+use amplify::confinement::{Confined, SmallBlob, U16};
+use bp::dbc::Method;
+use ifaces::rgb21::{EmbeddedMedia, TokenData};
+use ifaces::{IssuerWrapper, Rgb21};
+use rgbstd::containers::ValidContract;
+use rgbstd::interface::{
+    ContractBuilder, IfaceClass, IfaceImpl, NamedField, NamedVariant, TxOutpoint, VerNo,
+};
+use rgbstd::invoice::Precision;
 use rgbstd::schema::{GenesisSchema, GlobalStateSchema, Occurrences, Schema, TransitionSchema};
-use rgbstd::stl::StandardTypes;
+use rgbstd::stl::{
+    AssetSpec, ContractTerms, Details, Name, RicardianContract, StandardTypes, Ticker,
+};
 use rgbstd::validation::Scripts;
 use rgbstd::vm::opcodes::INSTR_LDG;
-use rgbstd::vm::RgbIsa;
-use rgbstd::{rgbasm, Identity, OwnedStateSchema};
+use rgbstd::{Allocation, GenesisSeal, Identity, OwnedStateSchema, RevealedData, TokenIndex};
+use strict_encoding::{InvalidRString, StrictSerialize};
 use strict_types::TypeSystem;
 
+use crate::asm::{schemata_asm, Asm};
+use crate::media::{attachment, media_type};
 use crate::{
-    ERRNO_NON_EQUAL_IN_OUT, ERRNO_NON_FRACTIONAL, GS_ATTACH, GS_NOMINAL, GS_TERMS, GS_TOKENS,
-    OS_ASSET, TS_TRANSFER,
+    SchemaParams, ERRNO_NON_EQUAL_IN_OUT, ERRNO_NON_FRACTIONAL, GS_ATTACH, GS_NOMINAL, GS_TERMS,
+    GS_TOKENS, OS_ASSET, TS_TRANSFER,
 };
 
 pub const FN_GENESIS_OFFSET: u16 = 4 + 4 + 3;
 pub const FN_TRANSFER_OFFSET: u16 = 0;
 pub const FN_SHARED_OFFSET: u16 = FN_GENESIS_OFFSET + 4 + 4 + 4;
 
-fn uda_lib() -> Lib {
-    let code = rgbasm! {
+pub(crate) fn uda_asm() -> Asm {
+    schemata_asm! {
         // SUBROUTINE 2: Transfer validation
         // Put 0 to a16[0]
         put     a16[0],0;
@@ -90,11 +101,16 @@ fn uda_lib() -> Lib {
         eq.n    a64[0],a64[1];
         // Fail if not
         test;
-    };
-    Lib::assemble::<Instr<RgbIsa<MemContract>>>(&code).expect("wrong unique digital asset script")
+    }
 }
 
-fn uda_schema() -> Schema {
+pub(crate) fn uda_lib() -> Lib {
+    Lib::assemble(&uda_asm().code).expect("wrong unique digital asset script")
+}
+
+fn uda_schema() -> Schema { uda_schema_with(&SchemaParams::default()) }
+
+pub(crate) fn uda_schema_with(params: &SchemaParams) -> Schema {
     let types = StandardTypes::with(Rgb21::NONE.stl());
 
     let alu_lib = uda_lib();
@@ -109,9 +125,9 @@ fn uda_schema() -> Schema {
     Schema {
         ffv: zero!(),
         flags: none!(),
-        name: tn!("UniqueDigitalAsset"),
-        timestamp: 1713343888,
-        developer: Identity::from(LNPBP_IDENTITY),
+        name: params.name_or(tn!("UniqueDigitalAsset")),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
         meta_types: none!(),
         global_types: tiny_bmap! {
             GS_NOMINAL => GlobalStateSchema::once(types.get("RGBContract.AssetSpec")),
@@ -156,15 +172,17 @@ fn uda_schema() -> Schema {
     }
 }
 
-fn uda_rgb21() -> IfaceImpl {
-    let schema = uda_schema();
+fn uda_rgb21() -> IfaceImpl { uda_rgb21_with(&SchemaParams::default()) }
+
+pub(crate) fn uda_rgb21_with(params: &SchemaParams) -> IfaceImpl {
+    let schema = uda_schema_with(params);
 
     IfaceImpl {
         version: VerNo::V1,
         schema_id: schema.schema_id(),
         iface_id: Rgb21::NONE.iface_id(),
-        timestamp: 1713343888,
-        developer: Identity::from(LNPBP_IDENTITY),
+        timestamp: params.timestamp,
+        developer: params.developer.clone(),
         metadata: none!(),
         global_state: tiny_bset! {
             NamedField::with(GS_NOMINAL, fname!("spec")),
@@ -205,10 +223,197 @@ impl IssuerWrapper for UniqueDigitalAsset {
     }
 }
 
+#[derive(Clone, Eq, PartialEq, Debug, Display, Error, From)]
+#[display(doc_comments)]
+pub enum UdaIssueError {
+    #[from]
+    #[display(inner)]
+    InvalidString(InvalidRString),
+
+    /// embedded token preview must not exceed 64 kiB.
+    PreviewTooLarge,
+
+    /// token can't have more than 20 attachments.
+    TooManyAttachments,
+
+    /// invalid issuer identity '{0}'; it must be a non-empty string of
+    /// printable ASCII characters.
+    InvalidIssuer(String),
+}
+
+impl UniqueDigitalAsset {
+    /// Issues a single-token UDA contract assigning the whole token to the
+    /// `beneficiary`.
+    ///
+    /// The `preview` is embedded into the token data as-is, while each of the
+    /// `attachments` is committed to by its SHA256 digest and indexed in the
+    /// order it is provided. Both are given as a pair of a MIME type (like
+    /// `image/png`) and the file content.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet<'a>(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        index: impl Into<TokenIndex>,
+        preview: Option<(&str, &[u8])>,
+        attachments: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+        method: Method,
+        beneficiary: impl TxOutpoint,
+    ) -> Result<ValidContract, UdaIssueError> {
+        let terms = ContractTerms {
+            text: RicardianContract::default(),
+            media: None,
+        };
+        let token_data = Self::token_data(ticker, name, details, index, preview, attachments)?;
+        Self::issue(issuer, terms, token_data, method, beneficiary)
+    }
+
+    pub(crate) fn token_data<'a>(
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        index: impl Into<TokenIndex>,
+        preview: Option<(&str, &[u8])>,
+        attachments: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+    ) -> Result<TokenData, UdaIssueError> {
+        let preview = preview
+            .map(|(ty, data)| -> Result<_, UdaIssueError> {
+                Ok(EmbeddedMedia {
+                    ty: media_type(ty)?,
+                    data: SmallBlob::try_from_iter(data.iter().copied())
+                        .map_err(|_| UdaIssueError::PreviewTooLarge)?,
+                })
+            })
+            .transpose()?;
+        let attachments = attachments
+            .into_iter()
+            .enumerate()
+            .map(|(no, (ty, data))| {
+                let no = u8::try_from(no).map_err(|_| UdaIssueError::TooManyAttachments)?;
+                Ok((no, attachment(ty, data)?))
+            })
+            .collect::<Result<BTreeMap<_, _>, UdaIssueError>>()?;
+        Ok(TokenData {
+            index: index.into(),
+            ticker: Some(Ticker::try_from(ticker.to_owned())?),
+            name: Some(Name::try_from(name.to_owned())?),
+            details: details.map(Details::from_str).transpose()?,
+            preview,
+            media: None,
+            attachments: Confined::try_from(attachments)
+                .map_err(|_| UdaIssueError::TooManyAttachments)?,
+            reserves: None,
+        })
+    }
+
+    /// Issues contract in a deterministic way, such that the same arguments
+    /// always produce the same contract id.
+    ///
+    /// The seal is defined by the closing method, `beneficiary` outpoint and
+    /// `seal_blinding`; the `salt` is used to conceal the token allocation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn testnet_det<'a>(
+        issuer: &str,
+        ticker: &str,
+        name: &str,
+        details: Option<&str>,
+        index: impl Into<TokenIndex>,
+        preview: Option<(&str, &[u8])>,
+        attachments: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+        method: Method,
+        beneficiary: impl TxOutpoint,
+        seal_blinding: u64,
+        salt: u128,
+        timestamp: i64,
+    ) -> Result<ValidContract, UdaIssueError> {
+        let terms = ContractTerms {
+            text: RicardianContract::default(),
+            media: None,
+        };
+        let token_data = Self::token_data(ticker, name, details, index, preview, attachments)?;
+        let allocation = Allocation::with(token_data.index, 1)
+            .to_strict_serialized::<U16>()
+            .expect("allocation serialization");
+        let beneficiary = beneficiary.map_to_xchain(|outpoint| {
+            GenesisSeal::with_blinding(method, outpoint.txid, outpoint.vout, seal_blinding)
+        });
+        Ok(Self::builder(issuer, terms, token_data, true)?
+            .add_data_det("assetOwner", beneficiary, RevealedData::with_salt(allocation, salt))
+            .and_then(|builder| builder.issue_contract_det(timestamp))
+            .expect("invalid contract data"))
+    }
+
+    pub(crate) fn issue(
+        issuer: &str,
+        terms: ContractTerms,
+        token_data: TokenData,
+        method: Method,
+        beneficiary: impl TxOutpoint,
+    ) -> Result<ValidContract, UdaIssueError> {
+        let allocation = Allocation::with(token_data.index, 1);
+        let beneficiary = beneficiary.map_to_xchain(|outpoint| {
+            GenesisSeal::new_random(method, outpoint.txid, outpoint.vout)
+        });
+        Ok(Self::builder(issuer, terms, token_data, false)?
+            .add_data("assetOwner", beneficiary, allocation)
+            .and_then(|builder| builder.issue_contract())
+            .expect("invalid contract data"))
+    }
+
+    fn builder(
+        issuer: &str,
+        terms: ContractTerms,
+        token_data: TokenData,
+        deterministic: bool,
+    ) -> Result<ContractBuilder, UdaIssueError> {
+        let spec = AssetSpec {
+            ticker: token_data
+                .ticker
+                .clone()
+                .expect("token data without ticker"),
+            name: token_data.name.clone().expect("token data without name"),
+            details: token_data.details.clone(),
+            precision: Precision::Indivisible,
+        };
+        let issuer = Identity::from_str(issuer)
+            .map_err(|_| UdaIssueError::InvalidIssuer(issuer.to_owned()))?;
+        let builder = match deterministic {
+            false => ContractBuilder::with,
+            true => ContractBuilder::deterministic,
+        };
+        Ok(builder(
+            issuer,
+            Self::FEATURES.iface(),
+            Self::schema(),
+            Self::issue_impl(),
+            Self::types(),
+            Self::scripts(),
+        )
+        .add_global_state("spec", spec)
+        .and_then(|builder| builder.add_global_state("terms", terms))
+        .and_then(|builder| builder.add_global_state("tokens", token_data))
+        .expect("invalid contract data"))
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use amplify::Wrapper;
+    use bp::{Outpoint, Txid};
+    use rgbstd::containers::ConsignmentExt;
+    use strict_encoding::StrictDeserialize;
+
     use super::*;
 
+    fn beneficiary() -> Outpoint {
+        Outpoint::new(
+            Txid::from_str("14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5")
+                .unwrap(),
+            1,
+        )
+    }
+
     #[test]
     fn iimpl_check() {
         let iface = UniqueDigitalAsset::FEATURES.iface();
@@ -219,4 +424,62 @@ mod test {
             panic!("invalid UDA RGB21 interface implementation");
         }
     }
+
+    #[test]
+    fn testnet_issue() {
+        let contract = UniqueDigitalAsset::testnet(
+            "ssi:anonymous",
+            "TEST",
+            "Test uda",
+            None,
+            2,
+            Some(("image/png", &[0u8, 0][..])),
+            [("text/plain", &b"attachment"[..])],
+            Method::TapretFirst,
+            beneficiary(),
+        )
+        .unwrap();
+        assert_eq!(contract.schema_id(), UniqueDigitalAsset::schema().schema_id());
+
+        let token = contract
+            .genesis()
+            .globals
+            .get(&GS_TOKENS)
+            .expect("genesis must contain token data");
+        let token = TokenData::from_strict_serialized::<{ u16::MAX as usize }>(
+            token.first().unwrap().to_inner(),
+        )
+        .unwrap();
+        assert_eq!(token.index, TokenIndex::from(2));
+        assert_eq!(token.attachments.len(), 1);
+        assert_eq!(
+            token.attachments.get(&0).unwrap(),
+            &attachment("text/plain", b"attachment").unwrap()
+        );
+    }
+
+    #[test]
+    fn testnet_det() {
+        let issue = || {
+            UniqueDigitalAsset::testnet_det(
+                "ssi:anonymous",
+                "TEST",
+                "Test uda",
+                None,
+                2,
+                Some(("image/png", &[0u8, 0][..])),
+                [("text/plain", &b"attachment"[..])],
+                Method::TapretFirst,
+                beneficiary(),
+                654321,
+                123456,
+                1713261744,
+            )
+            .unwrap()
+        };
+
+        let contract = issue();
+        assert_eq!(contract.schema_id(), UniqueDigitalAsset::schema().schema_id());
+        assert_eq!(contract.contract_id(), issue().contract_id());
+    }
 }
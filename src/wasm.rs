@@ -0,0 +1,117 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bindings for JavaScript, exported with `wasm-bindgen` when the library is
+//! compiled for `wasm32` target.
+//!
+//! Errors are returned as strings, which are thrown as exceptions on the
+//! JavaScript side. Media files referenced by issuance manifests can't be
+//! read in a browser, thus manifests used from JavaScript should not provide
+//! media.
+
+use rgbstd::containers::FileContent;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::BundledSchema;
+#[cfg(feature = "serde")]
+use crate::IssueManifest;
+
+/// Returns identifier of the bundled schema with the provided name.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = schemaId))]
+pub fn schema_id(schema: &str) -> Result<String, String> {
+    let schema = schema
+        .parse::<BundledSchema>()
+        .map_err(|err| err.to_string())?;
+    Ok(schema.schema().schema_id().to_string())
+}
+
+/// Returns binary kit of the bundled schema with the provided name.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = schemaKit))]
+pub fn schema_kit(schema: &str) -> Result<Vec<u8>, String> {
+    let schema = schema
+        .parse::<BundledSchema>()
+        .map_err(|err| err.to_string())?;
+    let mut data = vec![];
+    schema
+        .kit()
+        .save(&mut data)
+        .map_err(|err| err.to_string())?;
+    Ok(data)
+}
+
+/// Issues contract from a JSON issuance manifest, returning it ASCII-armored.
+#[cfg(feature = "serde")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = issueContract))]
+pub fn issue_contract(manifest: &str) -> Result<String, String> {
+    let manifest = IssueManifest::from_json(manifest).map_err(|err| err.to_string())?;
+    let contract = manifest.issue().map_err(|err| err.to_string())?;
+    Ok(contract.to_string())
+}
+
+#[cfg(all(
+    test,
+    feature = "serde",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso"
+))]
+mod test {
+    use std::str::FromStr;
+
+    use rgbstd::containers::{Contract, Kit};
+
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+  "schema": "NIA",
+  "ticker": "TEST",
+  "name": "Test asset",
+  "allocations": [{
+    "outpoint": "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1",
+    "amount": 100
+  }]
+}"#;
+
+    #[test]
+    fn kits() {
+        for schema in BundledSchema::ALL {
+            let data = schema_kit(&schema.to_string()).unwrap();
+            let kit = Kit::load(data.as_slice()).unwrap();
+            assert_eq!(kit.schemata.first().unwrap().schema_id(), schema.schema().schema_id());
+            assert_eq!(
+                schema_id(&schema.to_string()).unwrap(),
+                schema.schema().schema_id().to_string()
+            );
+        }
+        assert!(schema_kit("RGB20").is_err());
+    }
+
+    #[test]
+    fn issue() {
+        let armored = issue_contract(MANIFEST).unwrap();
+        let contract = Contract::from_str(&armored).unwrap();
+        assert_eq!(contract.schema_id(), BundledSchema::NonInflatableAsset.schema().schema_id());
+        assert!(issue_contract(&MANIFEST.replace(r#""ticker": "TEST","#, "")).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compiles C-side tests of the `ffi` feature against the library and the
+//! header in `include`, and runs them.
+
+#![cfg(all(
+    feature = "ffi",
+    feature = "nia",
+    feature = "uda",
+    feature = "cfa",
+    feature = "lfa",
+    feature = "aso",
+    unix
+))]
+
+use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+use std::path::Path;
+use std::process::Command;
+use std::{env, fs};
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+#[test]
+fn header() {
+    let header = fs::read_to_string(Path::new(MANIFEST_DIR).join("include/schemata.h")).unwrap();
+    let source = fs::read_to_string(Path::new(MANIFEST_DIR).join("src/ffi.rs")).unwrap();
+    let exported = source
+        .split("extern \"C\" fn ")
+        .skip(1)
+        .map(|rest| rest.split('(').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(exported.len(), 10);
+    for name in exported {
+        assert!(
+            header.contains(&format!(" {name}(")) || header.contains(&format!("*{name}(")),
+            "`{name}` is not declared in include/schemata.h, regenerate it with cbindgen"
+        );
+    }
+}
+
+#[test]
+fn c_tests() {
+    // Integration tests are placed next to the library build artifacts.
+    let exe = env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    assert!(
+        deps.join(format!("{DLL_PREFIX}schemata{DLL_SUFFIX}"))
+            .exists(),
+        "dynamic library is not found in {}",
+        deps.display()
+    );
+    let out = deps.join("schemata_ffi_test");
+
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let status = Command::new(cc)
+        .current_dir(MANIFEST_DIR)
+        .args(["-std=c99", "-Wall", "-Werror", "-Iinclude", "tests/ffi/schemata_test.c"])
+        .arg("-L")
+        .arg(deps)
+        .args(["-lschemata", "-o"])
+        .arg(&out)
+        .status()
+        .expect("C compiler is not available");
+    assert!(status.success(), "C tests failed to compile");
+
+    // Cargo puts the stale library copy in `target/<profile>` on the search
+    // path, thus we point the dynamic linker to the fresh build only.
+    let output = Command::new(&out)
+        .current_dir(MANIFEST_DIR)
+        .env("LD_LIBRARY_PATH", deps)
+        .env("DYLD_LIBRARY_PATH", deps)
+        .output()
+        .unwrap();
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.status.success(), "C tests failed");
+}
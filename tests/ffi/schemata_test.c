@@ -0,0 +1,144 @@
+/* C-side tests of the `ffi` feature, compiled and run by `tests/ffi.rs`. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "schemata.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n", \
+                    __FILE__, __LINE__, #cond, schemata_error_message());  \
+            failures++;                                                    \
+        }                                                                  \
+    } while (0)
+
+#define OUTPOINT "14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1"
+
+static void test_kits(void) {
+    const char *names[] = {"NIA", "UDA", "CFA", "LFA", "NonInflatableAsset"};
+    for (size_t i = 0; i < sizeof(names) / sizeof(names[0]); i++) {
+        SchemataBytes kit = {NULL, 0};
+        CHECK(schemata_kit(names[i], &kit) == SCHEMATA_STATUS_OK);
+        CHECK(kit.data != NULL && kit.len > 7);
+        CHECK(memcmp(kit.data, "RGB\0KIT", 7) == 0);
+        schemata_bytes_free(kit);
+
+        char *armored = NULL;
+        CHECK(schemata_kit_armored(names[i], &armored) == SCHEMATA_STATUS_OK);
+        CHECK(armored != NULL && strncmp(armored, "-----BEGIN RGB KIT-----", 23) == 0);
+        CHECK(strcmp(schemata_error_message(), "") == 0);
+        schemata_string_free(armored);
+    }
+
+    SchemataBytes kit = {NULL, 0};
+    CHECK(schemata_kit("RGB20", &kit) == SCHEMATA_STATUS_UNKNOWN_SCHEMA);
+    CHECK(kit.data == NULL);
+    CHECK(strstr(schemata_error_message(), "RGB20") != NULL);
+    CHECK(schemata_kit(NULL, &kit) == SCHEMATA_STATUS_NULL_ARGUMENT);
+    CHECK(schemata_kit("NIA", NULL) == SCHEMATA_STATUS_NULL_ARGUMENT);
+    CHECK(schemata_kit("\xff", &kit) == SCHEMATA_STATUS_INVALID_UTF8);
+}
+
+static void check_issued(SchemataStatus status, char *contract_id, char *contract) {
+    CHECK(status == SCHEMATA_STATUS_OK);
+    CHECK(contract_id != NULL && strncmp(contract_id, "rgb:", 4) == 0);
+    CHECK(contract != NULL && strncmp(contract, "-----BEGIN RGB CONSIGNMENT-----", 31) == 0);
+    schemata_string_free(contract_id);
+    schemata_string_free(contract);
+}
+
+static void test_issue(void) {
+    char *contract_id = NULL, *contract = NULL;
+
+    SchemataStatus status = schemata_issue_nia(
+        "{\"ticker\": \"TEST\", \"name\": \"Test asset\", \"precision\": \"centiMicro\","
+        " \"allocations\": [{\"outpoint\": \"" OUTPOINT "\", \"amount\": 100000}]}",
+        &contract_id, &contract);
+    check_issued(status, contract_id, contract);
+
+    contract_id = NULL, contract = NULL;
+    status = schemata_issue_cfa(
+        "{\"schema\": \"CFA\", \"name\": \"Test asset\", \"details\": \"Some details\","
+        " \"allocations\": [{\"outpoint\": \"" OUTPOINT "\", \"amount\": 100}]}",
+        &contract_id, &contract);
+    check_issued(status, contract_id, contract);
+
+    contract_id = NULL, contract = NULL;
+    status = schemata_issue_lfa(
+        "{\"ticker\": \"TEST\", \"name\": \"Test asset\","
+        " \"allocations\": [{\"outpoint\": \"" OUTPOINT "\", \"amount\": 1000}]}",
+        &contract_id, &contract);
+    check_issued(status, contract_id, contract);
+
+    char *counter_id = NULL;
+    contract_id = NULL, contract = NULL;
+    status = schemata_issue_nia(
+        "{\"ticker\": \"USDT\", \"name\": \"Counter asset\","
+        " \"allocations\": [{\"outpoint\": \"" OUTPOINT "\", \"amount\": 500}]}",
+        &counter_id, &contract);
+    CHECK(status == SCHEMATA_STATUS_OK);
+    schemata_string_free(contract);
+
+    char params[512];
+    snprintf(params, sizeof(params),
+             "{\"ticker\": \"SWAP\", \"name\": \"Test offer\","
+             " \"offer\": {\"counterAsset\": \"%s\", \"counterAmount\": 500},"
+             " \"allocations\": [{\"outpoint\": \"" OUTPOINT "\", \"amount\": 1000}]}",
+             counter_id);
+    schemata_string_free(counter_id);
+    contract = NULL;
+    status = schemata_issue_aso(params, &contract_id, &contract);
+    check_issued(status, contract_id, contract);
+
+    contract_id = NULL, contract = NULL;
+    status = schemata_issue_uda(
+        "{\"ticker\": \"TEST\", \"name\": \"Test uda\","
+        " \"allocations\": [{\"outpoint\": \"" OUTPOINT "\"}],"
+        " \"token\": {\"index\": 2, \"attachments\": [{\"type\": \"text/plain\", \"file\": "
+        "\"DCO\"}]}}",
+        &contract_id, &contract);
+    check_issued(status, contract_id, contract);
+
+    contract_id = NULL, contract = NULL;
+    status = schemata_issue_nia("{\"name\": \"Test asset\", \"allocations\": []}", &contract_id,
+                                &contract);
+    CHECK(status == SCHEMATA_STATUS_ISSUE_FAILED);
+    CHECK(strstr(schemata_error_message(), "ticker") != NULL);
+    CHECK(contract_id == NULL && contract == NULL);
+
+    status = schemata_issue_aso(
+        "{\"ticker\": \"SWAP\", \"name\": \"Test offer\", \"allocations\": []}",
+        &contract_id, &contract);
+    CHECK(status == SCHEMATA_STATUS_ISSUE_FAILED);
+    CHECK(strstr(schemata_error_message(), "offer") != NULL);
+    CHECK(contract_id == NULL && contract == NULL);
+
+    CHECK(schemata_issue_uda("{\"schema\": \"NIA\"}", &contract_id, &contract) ==
+          SCHEMATA_STATUS_SCHEMA_MISMATCH);
+    CHECK(schemata_issue_uda("{\"schema\": \"RGB21\"}", &contract_id, &contract) ==
+          SCHEMATA_STATUS_INVALID_PARAMS);
+    CHECK(schemata_issue_cfa("{\"name\": 1}", &contract_id, &contract) ==
+          SCHEMATA_STATUS_INVALID_PARAMS);
+    CHECK(schemata_issue_cfa("not json", &contract_id, &contract) ==
+          SCHEMATA_STATUS_INVALID_PARAMS);
+    CHECK(schemata_issue_cfa("{}", NULL, &contract) == SCHEMATA_STATUS_NULL_ARGUMENT);
+    CHECK(contract_id == NULL && contract == NULL);
+
+    schemata_string_free(NULL);
+}
+
+int main(void) {
+    test_kits();
+    test_issue();
+    if (failures) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    printf("all checks passed\n");
+    return EXIT_SUCCESS;
+}
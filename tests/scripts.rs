@@ -0,0 +1,71 @@
+// RGB schemata by LNP/BP Standards Association
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+// Written in 2023-2024 by
+//     Dr Maxim Orlovsky <orlovsky@lnp-bp.org>
+//
+// Copyright (C) 2023-2024 LNP/BP Standards Association. All rights reserved.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traces validation scripts of the test scenarios from the AluVM log and
+//! checks that the scenarios cover all instructions of the bundled libraries.
+//!
+//! AluVM logs the executed instructions to STDERR, which the test harness
+//! intercepts in the test threads, thus the test is run without the harness.
+
+#[cfg(unix)]
+fn main() {
+    use schemata::testkit::scenarios;
+    use schemata::{BundledSchema, ScriptCoverage, StepOutcome};
+
+    let mut coverage = ScriptCoverage::bundled();
+    for scenario in scenarios() {
+        let name = scenario.name;
+        let trace = scenario
+            .validator
+            .trace(scenario.opid)
+            .unwrap_or_else(|err| panic!("{name}: {err}"));
+        assert!(trace.last_step().is_some(), "{name}: validation script is not run");
+        assert!(trace.matches_vm(), "{name}:\n{trace}");
+        let errno = scenario.errno.map(|errno| Some(errno.errno()));
+        assert_eq!(trace.vm_failure(), errno, "{name}");
+
+        if name == "UDA transfer fractional token" {
+            let last = trace.last_step().unwrap();
+            assert_eq!(last.instr, "test");
+            assert_eq!(last.outcome, StepOutcome::Halt);
+            assert_eq!(last.register("a8[0]"), Some("10"));
+            assert!(last.register("s16[1]").is_some());
+        }
+        coverage.record(&trace);
+        println!("trace {name} ... ok");
+    }
+
+    let uda = BundledSchema::UniqueDigitalAsset.scripts();
+    let report = coverage.report();
+    print!("{coverage}");
+    for lib in &report {
+        if uda.contains_key(&lib.lib) {
+            assert!(lib.gaps.is_empty(), "not all UDA failure points are reached:\n{lib}");
+        }
+    }
+    assert!(
+        report.iter().all(|lib| lib.is_complete()),
+        "bundled libraries are not fully covered by the validation scenarios:\n{coverage}"
+    );
+}
+
+#[cfg(not(unix))]
+fn main() {}
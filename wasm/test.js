@@ -0,0 +1,35 @@
+// Headless test of the WebAssembly build, run under Node.js with
+//
+//     wasm-pack build --target nodejs --out-dir wasm/pkg --out-name schemata
+//     node wasm/test.js
+
+const assert = require('node:assert');
+const schemata = require('./pkg/schemata.js');
+
+for (const schema of ['NIA', 'UDA', 'CFA']) {
+    const kit = schemata.schemaKit(schema);
+    assert.ok(kit instanceof Uint8Array);
+    assert.strictEqual(Buffer.from(kit.slice(0, 7)).toString('latin1'), 'RGB\0KIT');
+    assert.match(schemata.schemaId(schema), /^rgb:sch:/);
+}
+assert.throws(() => schemata.schemaKit('RGB20'), /unknown bundled schema name/);
+
+const manifest = {
+    schema: 'NIA',
+    ticker: 'TEST',
+    name: 'Test asset',
+    precision: 'centiMicro',
+    terms: { text: 'Issued for testing purposes only' },
+    allocations: [{
+        outpoint: '14295d5bb1a191cdb6286dc0944df938421e3dfcbf0811353ccac4100c2068c5:1',
+        amount: 100000,
+    }],
+};
+const contract = schemata.issueContract(JSON.stringify(manifest));
+assert.match(contract, /^-----BEGIN RGB CONSIGNMENT-----/);
+assert.match(contract, /-----END RGB CONSIGNMENT-----\s*$/);
+
+delete manifest.ticker;
+assert.throws(() => schemata.issueContract(JSON.stringify(manifest)), /must specify `ticker`/);
+
+console.log('wasm: all tests passed');